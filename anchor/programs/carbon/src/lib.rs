@@ -2,17 +2,268 @@
 #![allow(unexpected_cfgs)]
 
 use anchor_lang::prelude::*;
-use anchor_spl::{associated_token::AssociatedToken, token::{self, Mint, Token, TokenAccount, Burn, MintTo}};
+use anchor_spl::{
+    associated_token::{get_associated_token_address_with_program_id, AssociatedToken},
+    token::Token,
+    token_2022::{
+        spl_token_2022::{
+            self,
+            extension::{transfer_hook::TransferHookAccount, BaseStateWithExtensions, StateWithExtensions},
+            state::Account as SplTokenAccount,
+        },
+        Token2022,
+    },
+    token_interface::{self, Burn, CloseAccount, Mint, MintTo, TokenAccount, TokenInterface, TransferChecked},
+};
+use spl_discriminator::SplDiscriminate;
+use spl_tlv_account_resolution::{account::ExtraAccountMeta, seeds::Seed, state::ExtraAccountMetaList};
+use spl_transfer_hook_interface::instruction::ExecuteInstruction;
 
 declare_id!("4WEXh5GfWzfAyxmhAdVC5VFLxhj9hsv5zo7t6CeQMf5B");
 
+/// Share of the collateral, in basis points, kept from sealed bids that are never revealed.
+pub const SEALED_BID_FORFEIT_BPS: u64 = 5_000;
+
+/// Share of a period's obligation, in basis points, an industry may cover with offset credits
+/// until a regulator sets its own limit.
+pub const DEFAULT_MAX_OFFSET_BPS: u16 = 1_000;
+
+/// Longest company name an industry record has room for.
+pub const MAX_COMPANY_NAME_LEN: usize = 100;
+
+/// Largest signer set an industry can be governed by.
+pub const MAX_INDUSTRY_SIGNERS: usize = 10;
+
 #[program]
 pub mod carbon {
     use anchor_lang::system_program;
 
     use super::*;
 
-    pub fn initialize(_ctx: Context<InitializeMint>) -> Result<()> {
+    pub fn initialize(ctx: Context<InitializeMint>, treasury: Pubkey) -> Result<()> {
+        open_config(
+            &mut ctx.accounts.config,
+            ctx.accounts.authority.key(),
+            ctx.accounts.ct_mint.key(),
+            ctx.accounts.token_program.key(),
+            treasury,
+            ctx.bumps.config,
+        );
+
+        Ok(())
+    }
+
+    /// Like `initialize`, but creates CT as a Token-2022 mint whose transfers are routed
+    /// through `transfer_hook`, so credits can only move between verified industries and
+    /// accredited traders.
+    pub fn initialize_with_transfer_hook(ctx: Context<InitializeHookedMint>, treasury: Pubkey) -> Result<()> {
+        open_config(
+            &mut ctx.accounts.config,
+            ctx.accounts.authority.key(),
+            ctx.accounts.ct_mint.key(),
+            ctx.accounts.token_program.key(),
+            treasury,
+            ctx.bumps.config,
+        );
+
+        let metas = transfer_hook_account_metas()?;
+        let mut data = ctx.accounts.extra_account_meta_list.try_borrow_mut_data()?;
+        ExtraAccountMetaList::init::<ExecuteInstruction>(&mut data, &metas)?;
+
+        Ok(())
+    }
+
+    pub fn advance_compliance_period(ctx: Context<UpdateComplianceTerms>, period_deadline: i64) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+
+        require!(period_deadline > Clock::get()?.unix_timestamp, CarbonError::InvalidDeadline);
+
+        config.compliance_period = config.compliance_period
+            .checked_add(1)
+            .ok_or(CarbonError::MathOverflow)?;
+        config.period_deadline = period_deadline;
+
+        emit!(CompliancePeriodAdvanced {
+            compliance_period: config.compliance_period,
+            period_deadline,
+        });
+
+        Ok(())
+    }
+
+    pub fn set_compliance_terms(ctx: Context<UpdateComplianceTerms>, period_deadline: i64, penalty_per_tonne: u64) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+
+        require!(period_deadline > Clock::get()?.unix_timestamp, CarbonError::InvalidDeadline);
+
+        config.period_deadline = period_deadline;
+        config.penalty_per_tonne = penalty_per_tonne;
+
+        emit!(ComplianceTermsUpdated {
+            compliance_period: config.compliance_period,
+            period_deadline,
+            penalty_per_tonne,
+        });
+
+        Ok(())
+    }
+
+    /// Sets how long banked vintages remain surrenderable. `Some(0)` only accepts CT of the
+    /// open period's vintage.
+    pub fn set_banking_rules(ctx: Context<UpdateComplianceTerms>, banking_periods: Option<u32>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+
+        config.banking_periods = banking_periods;
+
+        emit!(BankingRulesUpdated {
+            compliance_period: config.compliance_period,
+            banking_periods,
+        });
+
+        Ok(())
+    }
+
+    /// Chooses what `deregister_industry` does with CT an exiting industry still holds.
+    pub fn set_exit_ct_policy(ctx: Context<UpdateComplianceTerms>, exit_ct_policy: ExitCtPolicy) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+
+        config.exit_ct_policy = exit_ct_policy;
+
+        emit!(ExitCtPolicyUpdated { exit_ct_policy });
+
+        Ok(())
+    }
+
+    /// Sets the allowance budget for `compliance_period`, split between CT sold at auction,
+    /// CT allocated for free and CT held back in the cost containment reserve. The open period
+    /// and later ones can be (re)planned, but never below what has already been issued against them.
+    pub fn set_cap_schedule(
+        ctx: Context<SetCapSchedule>,
+        compliance_period: u32,
+        auction_cap: u64,
+        free_allocation_cap: u64,
+        reserve_cap: u64,
+    ) -> Result<()> {
+        let schedule = &mut ctx.accounts.cap_schedule;
+
+        require!(compliance_period >= ctx.accounts.config.compliance_period, CarbonError::CompliancePeriodClosed);
+        require!(
+            auction_cap >= schedule.auctioned
+                && free_allocation_cap >= schedule.allocated
+                && reserve_cap >= schedule.reserve_released,
+            CarbonError::CapBelowIssued
+        );
+        auction_cap
+            .checked_add(free_allocation_cap)
+            .and_then(|cap| cap.checked_add(reserve_cap))
+            .ok_or(CarbonError::MathOverflow)?;
+
+        schedule.compliance_period = compliance_period;
+        schedule.auction_cap = auction_cap;
+        schedule.free_allocation_cap = free_allocation_cap;
+        schedule.reserve_cap = reserve_cap;
+        schedule.bump = ctx.bumps.cap_schedule;
+
+        emit!(CapScheduleSet {
+            compliance_period,
+            auction_cap,
+            free_allocation_cap,
+            reserve_cap,
+            auctioned: schedule.auctioned,
+            allocated: schedule.allocated,
+        });
+
+        Ok(())
+    }
+
+    pub fn set_reserve_config(
+        ctx: Context<SetReserveConfig>,
+        trigger_price: u64,
+        sale_price: u64,
+        release_amount: u64,
+    ) -> Result<()> {
+        require!(
+            trigger_price > 0 && sale_price > 0 && release_amount > 0,
+            CarbonError::InvalidReserveConfig
+        );
+
+        let reserve = &mut ctx.accounts.reserve_config;
+        reserve.trigger_price = trigger_price;
+        reserve.sale_price = sale_price;
+        reserve.release_amount = release_amount;
+        reserve.bump = ctx.bumps.reserve_config;
+
+        emit!(ReserveConfigured {
+            trigger_price,
+            sale_price,
+            release_amount,
+        });
+
+        Ok(())
+    }
+
+    pub fn propose_admin(ctx: Context<ProposeAdmin>, new_admin: Pubkey) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+
+        config.pending_admin = Some(new_admin);
+
+        emit!(AdminProposed {
+            admin: config.admin,
+            pending_admin: new_admin,
+        });
+
+        Ok(())
+    }
+
+    pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+
+        require!(
+            config.pending_admin == Some(ctx.accounts.new_admin.key()),
+            CarbonError::NotPendingAdmin
+        );
+
+        let previous_admin = config.admin;
+        config.admin = ctx.accounts.new_admin.key();
+        config.pending_admin = None;
+
+        emit!(AdminTransferred {
+            previous_admin,
+            new_admin: config.admin,
+        });
+
+        Ok(())
+    }
+
+    pub fn grant_role(ctx: Context<GrantRole>, role: Role, holder: Pubkey) -> Result<()> {
+        let assignment = &mut ctx.accounts.role_assignment;
+
+        assignment.role = role;
+        assignment.holder = holder;
+        assignment.granted_by = ctx.accounts.admin.key();
+        assignment.granted_at = Clock::get()?.unix_timestamp;
+        assignment.bump = ctx.bumps.role_assignment;
+
+        emit!(RoleGranted {
+            role_assignment: assignment.key(),
+            role,
+            holder,
+            granted_by: assignment.granted_by,
+        });
+
+        Ok(())
+    }
+
+    pub fn revoke_role(ctx: Context<RevokeRole>) -> Result<()> {
+        let assignment = &ctx.accounts.role_assignment;
+
+        emit!(RoleRevoked {
+            role_assignment: assignment.key(),
+            role: assignment.role,
+            holder: assignment.holder,
+            revoked_by: ctx.accounts.admin.key(),
+        });
+
         Ok(())
     }
 
@@ -25,15 +276,485 @@ pub mod carbon {
         industry.company_name = company_name;
         industry.bond_amount = bond_amount;
         industry.verified = false;
+        industry.suspended = false;
         industry.ct_balance = 0;
         industry.total_purchased = 0;
+        industry.total_allocated = 0;
         industry.total_burned = 0;
+        industry.verified_emissions = 0;
+        industry.compliance_period = ctx.accounts.config.compliance_period;
+        industry.period_obligation = 0;
+        industry.period_carried_over = 0;
+        industry.period_surrendered = 0;
+        industry.period_allocated = 0;
+        industry.offset_balance = 0;
+        industry.period_offsets_surrendered = 0;
+        industry.max_offset_bps = DEFAULT_MAX_OFFSET_BPS;
+        industry.total_slashed = 0;
+        industry.slash_count = 0;
+        industry.last_slashed_at = 0;
+        industry.last_slashed_period = 0;
+        industry.period_slashed_shortfall = 0;
+        industry.unsettled_shortfall = 0;
+        industry.settled_through_period = ctx.accounts.config.compliance_period.saturating_sub(1);
+        industry.penalty_debt = 0;
+        industry.open_positions = 0;
+        industry.vintage_holdings = 0;
+        industry.pending_authority = None;
+        industry.migrated_from = None;
+        industry.signers = Vec::new();
+        industry.signer_threshold = 0;
         industry.compliance_status = ComplianceStatus::Pending;
         industry.created_at = Clock::get()?.unix_timestamp;
         industry.bump = ctx.bumps.industry_account;
         // Initialize bond_vault_bump to 0, will be set on first deposit
         industry.bond_vault_bump = 0;
 
+        emit!(IndustryRegistered {
+            industry: industry.key(),
+            authority: industry.authority,
+            company_name: industry.company_name.clone(),
+            bond_amount,
+            timestamp: industry.created_at,
+        });
+
+        Ok(())
+    }
+
+    /// Renames the industry. The legal entity is unchanged, so verification stands; a new
+    /// entity takes over through `propose_industry_authority` instead.
+    pub fn update_industry_profile(ctx: Context<UpdateIndustryProfile>, company_name: String) -> Result<()> {
+        let industry = &mut ctx.accounts.industry_account;
+
+        require!(
+            !company_name.is_empty() && company_name.len() <= MAX_COMPANY_NAME_LEN,
+            CarbonError::InvalidCompanyName
+        );
+
+        let previous_name = std::mem::replace(&mut industry.company_name, company_name);
+
+        emit!(IndustryProfileUpdated {
+            industry: industry.key(),
+            authority: industry.authority,
+            previous_name,
+            company_name: industry.company_name.clone(),
+        });
+
+        Ok(())
+    }
+
+    /// First step of handing the industry to `new_authority`. The industry's CT is moved
+    /// into escrow now, while the current authority can still sign for it, and is released
+    /// to the new authority by `accept_industry_authority`.
+    pub fn propose_industry_authority<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ProposeIndustryAuthority<'info>>,
+        new_authority: Pubkey,
+    ) -> Result<()> {
+        let industry = &mut ctx.accounts.industry_account;
+
+        require!(new_authority != industry.authority, CarbonError::InvalidNewAuthority);
+        // A signer set would otherwise be sidestepped by handing the record to another wallet
+        require!(industry.signer_threshold == 0, CarbonError::MultisigGoverned);
+        // Bids, offers and reports are keyed by the current wallet and record, which the transfer closes
+        require!(industry.open_positions == 0, CarbonError::PositionsOpen);
+
+        let ct_escrowed = ctx.accounts.industry_ct_account.amount;
+        if ct_escrowed > 0 {
+            transfer_ct(
+                &ctx.accounts.token_program,
+                ctx.accounts.industry_ct_account.to_account_info(),
+                &ctx.accounts.ct_mint,
+                ctx.accounts.transfer_escrow.to_account_info(),
+                ctx.accounts.authority.to_account_info(),
+                ctx.remaining_accounts,
+                ct_escrowed,
+                &[],
+            )?;
+            industry.sync_ct_balance(&mut ctx.accounts.industry_ct_account)?;
+        }
+
+        industry.pending_authority = Some(new_authority);
+
+        emit!(IndustryAuthorityProposed {
+            industry: industry.key(),
+            authority: industry.authority,
+            pending_authority: new_authority,
+            ct_escrowed,
+        });
+
+        Ok(())
+    }
+
+    /// Withdraws a pending authority transfer, returning the escrowed CT.
+    pub fn cancel_industry_authority_transfer<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CancelIndustryAuthorityTransfer<'info>>,
+    ) -> Result<()> {
+        let industry = &mut ctx.accounts.industry_account;
+
+        require!(industry.pending_authority.is_some(), CarbonError::NotPendingAuthority);
+
+        let authority_seeds: &[&[&[u8]]] = &[&[b"offer_authority", &[ctx.bumps.offer_authority]]];
+
+        let ct_returned = ctx.accounts.transfer_escrow.amount;
+        if ct_returned > 0 {
+            transfer_ct(
+                &ctx.accounts.token_program,
+                ctx.accounts.transfer_escrow.to_account_info(),
+                &ctx.accounts.ct_mint,
+                ctx.accounts.industry_ct_account.to_account_info(),
+                ctx.accounts.offer_authority.to_account_info(),
+                ctx.remaining_accounts,
+                ct_returned,
+                authority_seeds,
+            )?;
+            industry.sync_ct_balance(&mut ctx.accounts.industry_ct_account)?;
+        }
+
+        close_offer_escrow(
+            &ctx.accounts.token_program,
+            &ctx.accounts.transfer_escrow,
+            ctx.accounts.authority.to_account_info(),
+            &ctx.accounts.offer_authority,
+            authority_seeds,
+        )?;
+
+        let pending_authority = industry.pending_authority.take().unwrap_or_default();
+
+        emit!(IndustryAuthorityTransferCancelled {
+            industry: industry.key(),
+            authority: industry.authority,
+            pending_authority,
+            ct_returned,
+        });
+
+        Ok(())
+    }
+
+    /// Second step, signed by the proposed authority. The record is re-created under the new
+    /// authority's seeds and the bond vault and escrowed CT follow it; the old record and
+    /// escrow are closed with their rent going to the new authority. Vintage holdings are
+    /// carried over one at a time by `migrate_vintage_holding`. The new record is unverified
+    /// until a verifier approves whoever now holds the authority.
+    pub fn accept_industry_authority<'info>(
+        ctx: Context<'_, '_, 'info, 'info, AcceptIndustryAuthority<'info>>,
+    ) -> Result<()> {
+        let previous = &ctx.accounts.previous_industry;
+        let new_authority = ctx.accounts.new_authority.key();
+
+        require!(previous.pending_authority == Some(new_authority), CarbonError::NotPendingAuthority);
+        require!(previous.open_positions == 0, CarbonError::PositionsOpen);
+
+        let previous_key = previous.key();
+
+        let industry = &mut ctx.accounts.industry_account;
+        industry.set_inner(IndustryAccount {
+            authority: new_authority,
+            pending_authority: None,
+            migrated_from: Some(previous_key),
+            // Holdings stay with the previous record until they are migrated one by one
+            vintage_holdings: 0,
+            bump: ctx.bumps.industry_account,
+            bond_vault_bump: if previous.bond_vault_bump == 0 { 0 } else { ctx.bumps.bond_vault },
+            ..(**previous).clone()
+        });
+
+        let bond_migrated = ctx.accounts.previous_bond_vault.lamports();
+        if bond_migrated > 0 {
+            let bump = previous.bond_vault_bump;
+            let signer_seeds: &[&[&[u8]]] = &[&[
+                b"bond_vault",
+                previous_key.as_ref(),
+                &[bump],
+            ]];
+
+            system_program::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.previous_bond_vault.to_account_info(),
+                        to: ctx.accounts.bond_vault.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                bond_migrated,
+            )?;
+        }
+
+        let authority_seeds: &[&[&[u8]]] = &[&[b"offer_authority", &[ctx.bumps.offer_authority]]];
+
+        let ct_migrated = ctx.accounts.transfer_escrow.amount;
+        if ct_migrated > 0 {
+            // A transfer hook reads the new record, so it is written out before the CT moves
+            // and only then loses its verification
+            industry.exit(&crate::ID)?;
+
+            transfer_ct(
+                &ctx.accounts.token_program,
+                ctx.accounts.transfer_escrow.to_account_info(),
+                &ctx.accounts.ct_mint,
+                ctx.accounts.industry_ct_account.to_account_info(),
+                ctx.accounts.offer_authority.to_account_info(),
+                ctx.remaining_accounts,
+                ct_migrated,
+                authority_seeds,
+            )?;
+            industry.sync_ct_balance(&mut ctx.accounts.industry_ct_account)?;
+        }
+
+        close_offer_escrow(
+            &ctx.accounts.token_program,
+            &ctx.accounts.transfer_escrow,
+            ctx.accounts.new_authority.to_account_info(),
+            &ctx.accounts.offer_authority,
+            authority_seeds,
+        )?;
+
+        industry.verified = false;
+        industry.refresh_compliance_status();
+
+        emit!(IndustryAuthorityTransferred {
+            previous_industry: previous_key,
+            industry: industry.key(),
+            previous_authority: previous.authority,
+            authority: new_authority,
+            bond_migrated,
+            ct_migrated,
+        });
+
+        Ok(())
+    }
+
+    /// Moves one vintage holding of the record this industry was migrated from onto the
+    /// industry's own seeds, closing the old holding.
+    pub fn migrate_vintage_holding(ctx: Context<MigrateVintageHolding>) -> Result<()> {
+        let industry = &mut ctx.accounts.industry_account;
+        let previous = &ctx.accounts.previous_holding;
+        let holding = &mut ctx.accounts.vintage_holding;
+
+        holding.open(industry, previous.vintage, ctx.bumps.vintage_holding);
+        holding.credit(previous.amount)?;
+
+        emit!(VintageHoldingMigrated {
+            industry: industry.key(),
+            previous_industry: previous.industry,
+            vintage: previous.vintage,
+            amount: previous.amount,
+        });
+
+        Ok(())
+    }
+
+    /// Closes out an industry that has no obligations left. The bond is returned in full,
+    /// leftover CT is retired under the configured `ExitCtPolicy`, and the record is closed
+    /// behind a tombstone that keeps its history in the registry.
+    pub fn deregister_industry<'info>(ctx: Context<'_, '_, 'info, 'info, DeregisterIndustry<'info>>) -> Result<()> {
+        let config = &ctx.accounts.config;
+        let industry = &mut ctx.accounts.industry_account;
+        let now = Clock::get()?.unix_timestamp;
+
+        require!(industry.pending_authority.is_none(), CarbonError::AuthorityTransferPending);
+        require!(industry.open_positions == 0, CarbonError::PositionsOpen);
+        // Deregistering returns the bond, which a signer set has to approve
+        require!(industry.signer_threshold == 0, CarbonError::MultisigGoverned);
+
+        industry.roll_to_period(config.compliance_period);
+        require!(
+            industry.outstanding_obligation() == 0 && industry.unsettled_shortfall == 0 && industry.penalty_debt == 0,
+            CarbonError::ObligationsOutstanding
+        );
+
+        let ct_retired = ctx.accounts.industry_ct_account.amount;
+        if ct_retired > 0 {
+            match config.exit_ct_policy {
+                ExitCtPolicy::Burn => token_interface::burn(
+                    CpiContext::new(
+                        ctx.accounts.token_program.to_account_info(),
+                        Burn {
+                            mint: ctx.accounts.ct_mint.to_account_info(),
+                            from: ctx.accounts.industry_ct_account.to_account_info(),
+                            authority: ctx.accounts.authority.to_account_info(),
+                        },
+                    ),
+                    ct_retired,
+                )?,
+                ExitCtPolicy::Surrender => {
+                    let treasury_ct_account = ctx.accounts.treasury_ct_account
+                        .as_ref()
+                        .ok_or(CarbonError::InvalidTokenAccount)?;
+
+                    transfer_ct(
+                        &ctx.accounts.token_program,
+                        ctx.accounts.industry_ct_account.to_account_info(),
+                        &ctx.accounts.ct_mint,
+                        treasury_ct_account.to_account_info(),
+                        ctx.accounts.authority.to_account_info(),
+                        ctx.remaining_accounts,
+                        ct_retired,
+                        &[],
+                    )?;
+                }
+            }
+        }
+
+        // Holdings are recognised among the remaining accounts, which also carry any transfer
+        // hook accounts; a holding passed twice is only counted before it is closed
+        let mut holdings_closed = 0;
+        for info in ctx.remaining_accounts {
+            if info.owner != &crate::ID || !info.try_borrow_data()?.starts_with(VintageHolding::DISCRIMINATOR) {
+                continue;
+            }
+            let holding = Account::<VintageHolding>::try_from(info)?;
+            require_keys_eq!(holding.industry, industry.key(), CarbonError::ForeignVintageHolding);
+            holding.close(ctx.accounts.authority.to_account_info())?;
+            holdings_closed += 1;
+        }
+        require!(holdings_closed == industry.vintage_holdings, CarbonError::VintageHoldingsOpen);
+
+        let bond_returned = ctx.accounts.bond_vault.lamports();
+        if bond_returned > 0 {
+            let industry_key = industry.key();
+            // The stored bump is only set once a bond has been deposited
+            let bump = ctx.bumps.bond_vault;
+            let signer_seeds: &[&[&[u8]]] = &[&[
+                b"bond_vault",
+                industry_key.as_ref(),
+                &[bump],
+            ]];
+
+            system_program::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.bond_vault.to_account_info(),
+                        to: ctx.accounts.authority.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                bond_returned,
+            )?;
+        }
+
+        let tombstone = &mut ctx.accounts.tombstone;
+        tombstone.industry = industry.key();
+        tombstone.authority = industry.authority;
+        tombstone.company_name = industry.company_name.clone();
+        tombstone.registered_at = industry.created_at;
+        tombstone.deregistered_at = now;
+        tombstone.last_compliance_period = industry.compliance_period;
+        tombstone.verified_emissions = industry.verified_emissions;
+        tombstone.total_purchased = industry.total_purchased;
+        tombstone.total_allocated = industry.total_allocated;
+        tombstone.total_burned = industry.total_burned;
+        tombstone.total_slashed = industry.total_slashed;
+        tombstone.bond_returned = bond_returned;
+        tombstone.ct_retired = ct_retired;
+        tombstone.ct_policy = config.exit_ct_policy;
+        tombstone.bump = ctx.bumps.tombstone;
+
+        emit!(IndustryDeregistered {
+            industry: industry.key(),
+            authority: industry.authority,
+            tombstone: tombstone.key(),
+            bond_returned,
+            ct_retired,
+            ct_policy: config.exit_ct_policy,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    /// Puts the industry under an M-of-N signer set, or changes or removes it. The authority
+    /// alone may set up the first set; once one is in place, changing it takes an approved
+    /// `UpdateSigners` proposal. A threshold of zero with no signers returns control to the
+    /// authority.
+    pub fn set_industry_signers(ctx: Context<SetIndustrySigners>, signers: Vec<Pubkey>, threshold: u8) -> Result<()> {
+        let industry = &mut ctx.accounts.industry_account;
+
+        if let Some(action) = industry.approved_action(&ctx.accounts.proposal)? {
+            require!(
+                *action == IndustryAction::UpdateSigners { signers: signers.clone(), threshold },
+                CarbonError::ProposalMismatch
+            );
+        }
+
+        let unique = signers.iter().enumerate().all(|(i, signer)| !signers[..i].contains(signer));
+        require!(
+            unique
+                && signers.len() <= MAX_INDUSTRY_SIGNERS
+                && (threshold as usize) <= signers.len()
+                && (threshold == 0) == signers.is_empty(),
+            CarbonError::InvalidSignerSet
+        );
+
+        industry.signers = signers;
+        industry.signer_threshold = threshold;
+
+        emit!(IndustrySignersUpdated {
+            industry: industry.key(),
+            signers: industry.signers.clone(),
+            threshold,
+        });
+
+        Ok(())
+    }
+
+    /// Opens a proposal for an action of a multisig-governed industry, counting the
+    /// proposer's approval. The authority carries it out once enough signers approve.
+    pub fn propose_industry_action(ctx: Context<ProposeIndustryAction>, proposal_id: u64, action: IndustryAction) -> Result<()> {
+        let industry = &ctx.accounts.industry_account;
+        let proposer = ctx.accounts.proposer.key();
+
+        require!(industry.signers.contains(&proposer), CarbonError::NotIndustrySigner);
+
+        let proposal = &mut ctx.accounts.proposal;
+        proposal.industry = industry.key();
+        proposal.proposal_id = proposal_id;
+        proposal.proposer = proposer;
+        proposal.action = action;
+        proposal.approvals = vec![proposer];
+        proposal.created_at = Clock::get()?.unix_timestamp;
+        proposal.bump = ctx.bumps.proposal;
+
+        emit!(IndustryActionProposed {
+            industry: industry.key(),
+            proposal: proposal.key(),
+            proposer,
+            action: proposal.action.clone(),
+        });
+
+        Ok(())
+    }
+
+    pub fn approve_industry_action(ctx: Context<ApproveIndustryAction>) -> Result<()> {
+        let industry = &ctx.accounts.industry_account;
+        let proposal = &mut ctx.accounts.proposal;
+        let signer = ctx.accounts.signer.key();
+
+        require!(industry.signers.contains(&signer), CarbonError::NotIndustrySigner);
+        require!(!proposal.approvals.contains(&signer), CarbonError::AlreadyApproved);
+
+        proposal.approvals.push(signer);
+
+        emit!(IndustryActionApproved {
+            industry: industry.key(),
+            proposal: proposal.key(),
+            signer,
+            approvals: proposal.approvals.len() as u8,
+            threshold: industry.signer_threshold,
+        });
+
+        Ok(())
+    }
+
+    /// Withdraws a proposal that has not been carried out, returning its rent to the proposer.
+    pub fn cancel_industry_action(ctx: Context<CancelIndustryAction>) -> Result<()> {
+        emit!(IndustryActionCancelled {
+            industry: ctx.accounts.proposal.industry,
+            proposal: ctx.accounts.proposal.key(),
+            proposer: ctx.accounts.proposer.key(),
+        });
+
         Ok(())
     }
 
@@ -42,7 +763,13 @@ pub mod carbon {
 
         require!(!industry.verified, CarbonError::AlreadyVerified);
         industry.verified = true;
-        industry.compliance_status = ComplianceStatus::Compliant;
+        industry.refresh_compliance_status();
+
+        emit!(IndustryVerified {
+            industry: industry.key(),
+            authority: industry.authority,
+            verifier: ctx.accounts.verifier.key(),
+        });
 
         Ok(())
     }
@@ -70,16 +797,34 @@ pub mod carbon {
 
         industry.bond_amount += amount;
 
+        emit!(BondDeposited {
+            industry: industry.key(),
+            bond_vault: ctx.accounts.bond_vault.key(),
+            amount,
+            bond_amount: industry.bond_amount,
+        });
+
         Ok(())
     }
 
-    pub fn burn_ct_for_compliance(ctx: Context<BurnCT>, amount: u64, emission_amount: u64) -> Result<()> {
+    pub fn burn_ct_for_compliance(ctx: Context<BurnCT>, amount: u64) -> Result<()> {
         let industry = &mut ctx.accounts.industry_account;
+        let vintage = ctx.accounts.vintage_holding.vintage;
+
+        if let Some(action) = industry.approved_action(&ctx.accounts.proposal)? {
+            require!(*action == IndustryAction::BurnCt { vintage, amount }, CarbonError::ProposalMismatch);
+        }
 
         require!(industry.verified, CarbonError::NotVerified);
-        require!(industry.ct_balance >= amount, CarbonError::InsufficientCT);
+        require!(ctx.accounts.industry_ct_account.amount >= amount, CarbonError::InsufficientCT);
 
-        token::burn(
+        industry.roll_to_period(ctx.accounts.config.compliance_period);
+        require!(amount <= industry.outstanding_obligation(), CarbonError::ExceedsObligation);
+
+        require!(ctx.accounts.config.vintage_surrenderable(vintage), CarbonError::VintageNotSurrenderable);
+        ctx.accounts.vintage_holding.debit(amount)?;
+
+        token_interface::burn(
             CpiContext::new(
                 ctx.accounts.token_program.to_account_info(),
                 Burn {
@@ -91,14 +836,20 @@ pub mod carbon {
             amount,
         )?;
 
-        industry.ct_balance -= amount;
+        industry.sync_ct_balance(&mut ctx.accounts.industry_ct_account)?;
         industry.total_burned += amount;
+        industry.period_surrendered += amount;
+        industry.refresh_compliance_status();
 
-        if amount >= emission_amount {
-            industry.compliance_status = ComplianceStatus::Compliant;
-        } else {
-            industry.compliance_status = ComplianceStatus::NonCompliant;
-        }
+        emit!(CreditsBurned {
+            industry: industry.key(),
+            amount,
+            vintage,
+            compliance_period: industry.compliance_period,
+            period_obligation: industry.period_obligation,
+            period_surrendered: industry.period_surrendered,
+            compliance_status: industry.compliance_status.clone(),
+        });
 
         Ok(())
     }
@@ -109,8 +860,76 @@ pub mod carbon {
         report.industry = ctx.accounts.industry_account.key();
         report.co2_emitted = co2_emitted;
         report.report_period = report_period;
+        report.compliance_period = ctx.accounts.config.compliance_period;
         report.submitted_at = Clock::get()?.unix_timestamp;
         report.verified = false;
+        report.status = ReportStatus::Pending;
+        report.verifier = None;
+        report.verified_at = 0;
+        report.adjusted_co2_emitted = None;
+        ctx.accounts.industry_account.open_position()?;
+
+        emit!(ReportSubmitted {
+            report: report.key(),
+            industry: report.industry,
+            co2_emitted,
+            compliance_period: report.compliance_period,
+            timestamp: report.submitted_at,
+        });
+
+        Ok(())
+    }
+
+    pub fn verify_emission_report(ctx: Context<ReviewEmissionReport>, adjusted_co2_emitted: Option<u64>) -> Result<()> {
+        let report = &mut ctx.accounts.emission_report;
+        let industry = &mut ctx.accounts.industry_account;
+
+        require!(report.status == ReportStatus::Pending, CarbonError::ReportAlreadyReviewed);
+
+        report.verified = true;
+        report.status = ReportStatus::Verified;
+        report.verifier = Some(ctx.accounts.verifier.key());
+        report.verified_at = Clock::get()?.unix_timestamp;
+        report.adjusted_co2_emitted = adjusted_co2_emitted;
+
+        let emissions = report.effective_co2_emitted();
+        industry.verified_emissions = industry.verified_emissions
+            .checked_add(emissions)
+            .ok_or(CarbonError::MathOverflow)?;
+
+        industry.roll_to_period(ctx.accounts.config.compliance_period);
+        industry.period_obligation = industry.period_obligation
+            .checked_add(emissions)
+            .ok_or(CarbonError::MathOverflow)?;
+        industry.settle_position();
+        industry.refresh_compliance_status();
+
+        emit!(ReportVerified {
+            report: report.key(),
+            industry: industry.key(),
+            verifier: ctx.accounts.verifier.key(),
+            co2_emitted: emissions,
+            period_obligation: industry.period_obligation,
+        });
+
+        Ok(())
+    }
+
+    pub fn reject_emission_report(ctx: Context<ReviewEmissionReport>) -> Result<()> {
+        let report = &mut ctx.accounts.emission_report;
+
+        require!(report.status == ReportStatus::Pending, CarbonError::ReportAlreadyReviewed);
+
+        report.status = ReportStatus::Rejected;
+        report.verifier = Some(ctx.accounts.verifier.key());
+        report.verified_at = Clock::get()?.unix_timestamp;
+        ctx.accounts.industry_account.settle_position();
+
+        emit!(ReportRejected {
+            report: report.key(),
+            industry: report.industry,
+            verifier: ctx.accounts.verifier.key(),
+        });
 
         Ok(())
     }
@@ -118,7 +937,18 @@ pub mod carbon {
     pub fn withdraw_bond(ctx: Context<WithdrawBond>, amount: u64) -> Result<()> {
         let industry = &mut ctx.accounts.industry_account;
 
+        if let Some(action) = industry.approved_action(&ctx.accounts.proposal)? {
+            require!(*action == IndustryAction::WithdrawBond { amount }, CarbonError::ProposalMismatch);
+        }
+
+        industry.roll_to_period(ctx.accounts.config.compliance_period);
+        industry.refresh_compliance_status();
         require!(industry.compliance_status == ComplianceStatus::Compliant, CarbonError::NotCompliant);
+        // Shortfall still to be settled and unpaid penalties are collected from the bond first
+        require!(
+            industry.unsettled_shortfall == 0 && industry.penalty_debt == 0,
+            CarbonError::ObligationsOutstanding
+        );
         require!(industry.bond_amount >= amount, CarbonError::InsufficientBond);
 
         let rent_exempt = Rent::get()?.minimum_balance(0);
@@ -150,30 +980,335 @@ pub mod carbon {
         )?;
 
         industry.bond_amount -= amount;
+
+        emit!(BondWithdrawn {
+            industry: industry.key(),
+            bond_vault: ctx.accounts.bond_vault.key(),
+            amount,
+            bond_amount: industry.bond_amount,
+        });
+
         Ok(())
     }
 
-    pub fn create_auction(ctx: Context<CreateAuction>, batch_number: u32, total_tokens: u64, start_price: u64, reserve_price: u64, duration_seconds: i64) -> Result<()> {
-        let auction = &mut ctx.accounts.auction;
-        let clock = Clock::get()?;
+    pub fn slash_bond(ctx: Context<SlashBond>) -> Result<()> {
+        let config = &ctx.accounts.config;
+        let industry = &mut ctx.accounts.industry_account;
+        let now = Clock::get()?.unix_timestamp;
 
-        require!(start_price > reserve_price, CarbonError::InvalidPricing);
-        require!(duration_seconds > 0, CarbonError::InvalidDuration);
-        require!(total_tokens > 0, CarbonError::InvalidTokenAmount);
+        industry.roll_to_period(config.compliance_period);
+        industry.refresh_compliance_status();
 
-        auction.authority = ctx.accounts.authority.key();
-        auction.batch_number = batch_number;
-        auction.total_tokens = total_tokens;
-        auction.tokens_remaining = total_tokens;
-        auction.start_price = start_price;
-        auction.current_price = start_price;
-        auction.reserve_price = reserve_price;
-        auction.start_time = clock.unix_timestamp;
-        auction.end_time = clock.unix_timestamp + duration_seconds;
-        auction.status = AuctionStatus::Active;
-        auction.total_raised = 0;
-        auction.participant_count = 0;
-        auction.tokens_sold = 0;
+        require!(industry.compliance_status == ComplianceStatus::NonCompliant, CarbonError::NotSlashable);
+        require!(config.period_deadline > 0 && now > config.period_deadline, CarbonError::DeadlineNotPassed);
+        require!(industry.last_slashed_period < config.compliance_period, CarbonError::AlreadySlashed);
+
+        // Carried-over shortfall was penalised when its own period settled
+        let shortfall = industry.period_shortfall();
+        require!(shortfall > 0, CarbonError::NotSlashable);
+
+        let shortfall_penalty = (shortfall as u128)
+            .checked_mul(config.penalty_per_tonne as u128)
+            .ok_or(CarbonError::MathOverflow)?
+            .checked_div(1_000_000_000) // Obligation is in CT base units (9 decimals)
+            .ok_or(CarbonError::MathOverflow)?;
+
+        let rent_exempt = Rent::get()?.minimum_balance(0);
+        let available_balance = ctx.accounts.bond_vault.lamports()
+            .checked_sub(rent_exempt)
+            .ok_or(CarbonError::InsufficientBond)?;
+
+        let penalty = shortfall_penalty.min(available_balance as u128) as u64;
+        require!(penalty > 0, CarbonError::InsufficientBond);
+
+        // Tonnes the bond could not pay for are left for `settle_period` to charge or carry as debt
+        let paid_shortfall = if penalty as u128 == shortfall_penalty {
+            shortfall
+        } else {
+            ((penalty as u128) * 1_000_000_000 / config.penalty_per_tonne as u128) as u64
+        };
+
+        let industry_account_key = industry.key();
+        let bump = industry.bond_vault_bump;
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            b"bond_vault",
+            industry_account_key.as_ref(),
+            &[bump],
+        ]];
+
+        system_program::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.bond_vault.to_account_info(),
+                    to: ctx.accounts.treasury.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            penalty,
+        )?;
+
+        industry.bond_amount = industry.bond_amount.saturating_sub(penalty);
+        industry.total_slashed += penalty;
+        industry.slash_count += 1;
+        industry.last_slashed_at = now;
+        industry.last_slashed_period = config.compliance_period;
+        industry.period_slashed_shortfall = paid_shortfall;
+
+        emit!(BondSlashed {
+            industry: industry.key(),
+            bond_vault: ctx.accounts.bond_vault.key(),
+            treasury: ctx.accounts.treasury.key(),
+            penalty,
+            compliance_period: config.compliance_period,
+            outstanding_obligation: industry.outstanding_obligation(),
+        });
+
+        Ok(())
+    }
+
+    /// Settles every closed period the industry has not yet settled: the shortfall of verified
+    /// emissions over surrendered CT is charged `penalty_per_tonne`, collected from the bond
+    /// vault with any remainder kept as debt, and stays owed in the open period.
+    pub fn settle_period(ctx: Context<SettlePeriod>) -> Result<()> {
+        let config = &ctx.accounts.config;
+        let industry = &mut ctx.accounts.industry_account;
+        let closed_period = config.compliance_period.saturating_sub(1);
+
+        require!(industry.settled_through_period < closed_period, CarbonError::PeriodAlreadySettled);
+
+        industry.roll_to_period(config.compliance_period);
+        industry.refresh_compliance_status();
+
+        let shortfall = industry.unsettled_shortfall;
+        let penalty = (shortfall as u128)
+            .checked_mul(config.penalty_per_tonne as u128)
+            .ok_or(CarbonError::MathOverflow)?
+            .checked_div(1_000_000_000) // Shortfall is in CT base units (9 decimals)
+            .ok_or(CarbonError::MathOverflow)?;
+        let penalty = u64::try_from(penalty).map_err(|_| CarbonError::MathOverflow)?;
+        let amount_due = industry.penalty_debt
+            .checked_add(penalty)
+            .ok_or(CarbonError::MathOverflow)?;
+
+        let rent_exempt = Rent::get()?.minimum_balance(0);
+        let available_balance = ctx.accounts.bond_vault.lamports().saturating_sub(rent_exempt);
+        let collected = amount_due.min(available_balance);
+
+        if collected > 0 {
+            let industry_account_key = industry.key();
+            let bump = industry.bond_vault_bump;
+            let signer_seeds: &[&[&[u8]]] = &[&[
+                b"bond_vault",
+                industry_account_key.as_ref(),
+                &[bump],
+            ]];
+
+            system_program::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.bond_vault.to_account_info(),
+                        to: ctx.accounts.treasury.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                collected,
+            )?;
+
+            industry.bond_amount = industry.bond_amount.saturating_sub(collected);
+            industry.total_slashed += collected;
+        }
+
+        industry.penalty_debt = amount_due - collected;
+        industry.unsettled_shortfall = 0;
+        industry.settled_through_period = closed_period;
+
+        emit!(PeriodSettled {
+            industry: industry.key(),
+            compliance_period: closed_period,
+            shortfall,
+            penalty,
+            collected,
+            penalty_debt: industry.penalty_debt,
+            carried_obligation: industry.period_carried_over,
+        });
+
+        Ok(())
+    }
+
+    pub fn create_auction(ctx: Context<CreateAuction>, batch_number: u32, total_tokens: u64, start_price: u64, reserve_price: u64, duration_seconds: i64) -> Result<()> {
+        let auction = &mut ctx.accounts.auction;
+        let clock = Clock::get()?;
+
+        require!(start_price > reserve_price, CarbonError::InvalidPricing);
+        require!(duration_seconds > 0, CarbonError::InvalidDuration);
+        require!(total_tokens > 0, CarbonError::InvalidTokenAmount);
+
+        ctx.accounts.cap_schedule.issue_auctioned(total_tokens)?;
+
+        auction.authority = ctx.accounts.authority.key();
+        auction.batch_number = batch_number;
+        auction.total_tokens = total_tokens;
+        auction.tokens_remaining = total_tokens;
+        auction.start_price = start_price;
+        auction.current_price = start_price;
+        auction.reserve_price = reserve_price;
+        auction.start_time = clock.unix_timestamp;
+        auction.end_time = clock.unix_timestamp
+            .checked_add(duration_seconds)
+            .ok_or(CarbonError::MathOverflow)?;
+        auction.status = AuctionStatus::Active;
+        auction.total_raised = 0;
+        auction.participant_count = 0;
+        auction.tokens_sold = 0;
+        auction.kind = AuctionKind::Dutch;
+        auction.reveal_end_time = auction.end_time;
+        auction.min_collateral = 0;
+        auction.revealed_count = 0;
+        auction.max_tokens_per_bidder = 0;
+        auction.max_bidder_share_bps = 0;
+        auction.settled_count = 0;
+        auction.vintage = ctx.accounts.config.compliance_period;
+        auction.curve = PriceCurve::Linear;
+        auction.payment_mint = None;
+
+        emit!(AuctionCreated {
+            auction: auction.key(),
+            batch_number,
+            kind: AuctionKind::Dutch,
+            vintage: auction.vintage,
+            total_tokens,
+            start_price,
+            reserve_price,
+            start_time: auction.start_time,
+            end_time: auction.end_time,
+        });
+
+        Ok(())
+    }
+
+    pub fn create_sealed_bid_auction(
+        ctx: Context<CreateAuction>,
+        batch_number: u32,
+        total_tokens: u64,
+        reserve_price: u64,
+        duration_seconds: i64,
+        reveal_duration_seconds: i64,
+        min_collateral: u64,
+    ) -> Result<()> {
+        let auction = &mut ctx.accounts.auction;
+        let clock = Clock::get()?;
+
+        require!(duration_seconds > 0 && reveal_duration_seconds > 0, CarbonError::InvalidDuration);
+        require!(total_tokens > 0, CarbonError::InvalidTokenAmount);
+        require!(min_collateral > 0, CarbonError::InsufficientCollateral);
+
+        ctx.accounts.cap_schedule.issue_auctioned(total_tokens)?;
+
+        auction.authority = ctx.accounts.authority.key();
+        auction.batch_number = batch_number;
+        auction.total_tokens = total_tokens;
+        auction.tokens_remaining = total_tokens;
+        auction.start_price = reserve_price;
+        auction.current_price = reserve_price;
+        auction.reserve_price = reserve_price;
+        auction.start_time = clock.unix_timestamp;
+        auction.end_time = clock.unix_timestamp
+            .checked_add(duration_seconds)
+            .ok_or(CarbonError::MathOverflow)?;
+        auction.reveal_end_time = auction.end_time
+            .checked_add(reveal_duration_seconds)
+            .ok_or(CarbonError::MathOverflow)?;
+        auction.status = AuctionStatus::Active;
+        auction.total_raised = 0;
+        auction.participant_count = 0;
+        auction.tokens_sold = 0;
+        auction.kind = AuctionKind::SealedBid;
+        auction.min_collateral = min_collateral;
+        auction.revealed_count = 0;
+        auction.max_tokens_per_bidder = 0;
+        auction.max_bidder_share_bps = 0;
+        auction.settled_count = 0;
+        auction.vintage = ctx.accounts.config.compliance_period;
+        auction.curve = PriceCurve::Linear;
+        auction.payment_mint = None;
+
+        emit!(AuctionCreated {
+            auction: auction.key(),
+            batch_number,
+            kind: AuctionKind::SealedBid,
+            vintage: auction.vintage,
+            total_tokens,
+            start_price: reserve_price,
+            reserve_price,
+            start_time: auction.start_time,
+            end_time: auction.end_time,
+        });
+
+        Ok(())
+    }
+
+    /// Caps how much of an auction a single bidder may take, as an absolute amount of CT, a
+    /// share of the batch in basis points, or both (the stricter applies). Zero leaves that
+    /// limit off. Caps can only change before the first bid, so operators set them in the
+    /// same transaction that creates the auction.
+    pub fn set_bidder_cap(ctx: Context<SetBidderCap>, max_tokens_per_bidder: u64, max_bidder_share_bps: u16) -> Result<()> {
+        let auction = &mut ctx.accounts.auction;
+
+        require!(auction.status == AuctionStatus::Active, CarbonError::AuctionNotActive);
+        require!(auction.participant_count == 0, CarbonError::AuctionHasBids);
+        require!(max_bidder_share_bps <= 10_000, CarbonError::InvalidBidderCap);
+
+        auction.max_tokens_per_bidder = max_tokens_per_bidder;
+        auction.max_bidder_share_bps = max_bidder_share_bps;
+
+        emit!(BidderCapSet {
+            auction: auction.key(),
+            max_tokens_per_bidder,
+            max_bidder_share_bps,
+            bidder_cap: auction.bidder_cap(),
+        });
+
+        Ok(())
+    }
+
+    pub fn set_price_curve(ctx: Context<SetPriceCurve>, curve: PriceCurve) -> Result<()> {
+        let auction = &mut ctx.accounts.auction;
+
+        require!(auction.kind == AuctionKind::Dutch, CarbonError::InvalidAuctionKind);
+        require!(auction.status == AuctionStatus::Active, CarbonError::AuctionNotActive);
+        require!(auction.participant_count == 0, CarbonError::AuctionHasBids);
+        curve.validate()?;
+
+        auction.curve = curve;
+
+        emit!(PriceCurveSet {
+            auction: auction.key(),
+            curve: auction.curve,
+        });
+
+        Ok(())
+    }
+
+    /// Denominates a Dutch auction in an SPL token, such as a USD stablecoin, instead of
+    /// lamports and opens the escrow token account its bids are paid into. Prices are then
+    /// read in the mint's base units per CT. Like the other auction settings it can only be
+    /// chosen before the first bid.
+    pub fn set_payment_mint(ctx: Context<SetPaymentMint>) -> Result<()> {
+        let auction = &mut ctx.accounts.auction;
+
+        require!(auction.kind == AuctionKind::Dutch, CarbonError::InvalidAuctionKind);
+        require!(auction.status == AuctionStatus::Active, CarbonError::AuctionNotActive);
+        require!(auction.participant_count == 0, CarbonError::AuctionHasBids);
+
+        auction.payment_mint = Some(ctx.accounts.payment_mint.key());
+
+        emit!(PaymentMintSet {
+            auction: auction.key(),
+            payment_mint: ctx.accounts.payment_mint.key(),
+            escrow_token: ctx.accounts.escrow_token.key(),
+        });
 
         Ok(())
     }
@@ -189,18 +1324,27 @@ pub mod carbon {
         let clock = Clock::get()?;
 
         require!(industry.verified, CarbonError::NotVerified);
+        require!(auction.kind == AuctionKind::Dutch, CarbonError::InvalidAuctionKind);
         require!(auction.status == AuctionStatus::Active, CarbonError::AuctionNotActive);
         require!(clock.unix_timestamp < auction.end_time, CarbonError::AuctionEnded);
         require!(token_amount <= auction.tokens_remaining, CarbonError::InsufficientTokens);
         require!(token_amount > 0, CarbonError::InvalidTokenAmount);
 
-        let current_price = calculate_current_price(
-            auction.start_price,
-            auction.reserve_price,
-            auction.start_time,
-            auction.end_time,
-            clock.unix_timestamp,
-        )?;
+        ctx.accounts.position.open(auction.key(), ctx.accounts.bidder.key(), ctx.bumps.position);
+        ctx.accounts.position.record(auction, token_amount)?;
+
+        let current_price = auction.price_at(clock.unix_timestamp)?;
+
+        if let Some(action) = industry.approved_action(&ctx.accounts.proposal)? {
+            match *action {
+                IndustryAction::PlaceBid { auction: approved_auction, token_amount: approved_amount, max_price_per_token }
+                    if approved_auction == auction.key() && approved_amount == token_amount =>
+                {
+                    require!(current_price <= max_price_per_token, CarbonError::BidAboveApprovedPrice);
+                }
+                _ => return err!(CarbonError::ProposalMismatch),
+            }
+        }
 
         auction.current_price = current_price;
 
@@ -211,16 +1355,26 @@ pub mod carbon {
             .checked_div(1_000_000_000)
             .ok_or(CarbonError::MathOverflow)? as u64;
 
-        system_program::transfer(
-            CpiContext::new(
-                ctx.accounts.system_program.to_account_info(),
-                system_program::Transfer {
-                    from: ctx.accounts.bidder.to_account_info(),
-                    to: ctx.accounts.escrow.to_account_info(),
-                },
-            ),
-            total_cost,
+        let payment = TokenPayment::resolve(
+            auction,
+            &ctx.accounts.payment_mint,
+            &ctx.accounts.escrow_token,
+            &ctx.accounts.bidder_token,
+            &ctx.accounts.payment_token_program,
         )?;
+        match payment {
+            Some(payment) => payment.collect(ctx.accounts.bidder.to_account_info(), total_cost)?,
+            None => system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.bidder.to_account_info(),
+                        to: ctx.accounts.escrow.to_account_info(),
+                    },
+                ),
+                total_cost,
+            )?,
+        }
 
         bid.auction = auction.key();
         bid.bidder = ctx.accounts.bidder.key();
@@ -230,51 +1384,254 @@ pub mod carbon {
         bid.total_cost = total_cost;
         bid.timestamp = timestamp as i64;
         bid.status = BidStatus::Pending;
+        bid.commitment = [0; 32];
+        bid.collateral = 0;
 
         auction.tokens_remaining -= token_amount;
         auction.total_raised += total_cost;
         auction.participant_count += 1; 
+        industry.open_position()?;
 
         if auction.tokens_remaining == 0 {
             auction.status = AuctionStatus::Completed;
         }
 
+        emit!(BidPlaced {
+            auction: auction.key(),
+            bid: bid.key(),
+            bidder: bid.bidder,
+            industry: bid.industry,
+            token_amount,
+            price_per_token: current_price,
+            total_cost,
+            tokens_remaining: auction.tokens_remaining,
+        });
+
         Ok(())
     }
 
-    pub fn finalize_auction(ctx: Context<FinalizeAuction>) -> Result<()> {
+    pub fn commit_bid(ctx: Context<CommitBid>, commitment: [u8; 32], collateral: u64, timestamp: u64) -> Result<()> {
         let auction = &mut ctx.accounts.auction;
+        let bid = &mut ctx.accounts.bid;
+        let industry = &mut ctx.accounts.industry_account;
         let clock = Clock::get()?;
 
+        require!(industry.verified, CarbonError::NotVerified);
+        // A sealed bid cannot be shown to signers without revealing it, so there is no proposal for one
+        require!(industry.signer_threshold == 0, CarbonError::MultisigGoverned);
+        require!(auction.kind == AuctionKind::SealedBid, CarbonError::InvalidAuctionKind);
+        require!(auction.status == AuctionStatus::Active, CarbonError::AuctionNotActive);
+        require!(clock.unix_timestamp < auction.end_time, CarbonError::AuctionEnded);
+        require!(collateral >= auction.min_collateral, CarbonError::InsufficientCollateral);
+
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.bidder.to_account_info(),
+                    to: ctx.accounts.escrow.to_account_info(),
+                },
+            ),
+            collateral,
+        )?;
+
+        bid.auction = auction.key();
+        bid.bidder = ctx.accounts.bidder.key();
+        bid.industry = industry.key();
+        bid.token_amount = 0;
+        bid.price_per_token = 0;
+        bid.total_cost = collateral;
+        bid.timestamp = timestamp as i64;
+        bid.status = BidStatus::Committed;
+        bid.commitment = commitment;
+        bid.collateral = collateral;
+
+        auction.participant_count += 1;
+        industry.open_position()?;
+
+        emit!(BidCommitted {
+            auction: auction.key(),
+            bid: bid.key(),
+            bidder: bid.bidder,
+            industry: bid.industry,
+            collateral,
+        });
+
+        Ok(())
+    }
+
+    pub fn reveal_bid(ctx: Context<RevealBid>, token_amount: u64, price_per_token: u64, salt: [u8; 32]) -> Result<()> {
+        let auction = &mut ctx.accounts.auction;
+        let bid = &mut ctx.accounts.bid;
+        let now = Clock::get()?.unix_timestamp;
+
+        require!(ctx.accounts.industry_account.signer_threshold == 0, CarbonError::MultisigGoverned);
+        require!(auction.kind == AuctionKind::SealedBid, CarbonError::InvalidAuctionKind);
+        require!(auction.status == AuctionStatus::Active, CarbonError::AuctionNotActive);
+        require!(now >= auction.end_time, CarbonError::RevealNotOpen);
+        require!(now < auction.reveal_end_time, CarbonError::RevealClosed);
+        require!(bid.status == BidStatus::Committed, CarbonError::BidAlreadyProcessed);
         require!(
-            auction.status == AuctionStatus::Active || auction.status == AuctionStatus::Completed,
-            CarbonError::InvalidAuctionStatus
+            sealed_bid_commitment(&bid.bidder, token_amount, price_per_token, &salt) == bid.commitment,
+            CarbonError::CommitmentMismatch
         );
+        require!(token_amount > 0 && token_amount <= auction.total_tokens, CarbonError::InvalidTokenAmount);
+        require!(price_per_token >= auction.reserve_price, CarbonError::BidBelowReserve);
+
+        ctx.accounts.position.open(auction.key(), bid.bidder, ctx.bumps.position);
+        ctx.accounts.position.record(auction, token_amount)?;
+
+        let total_cost = (token_amount as u128)
+            .checked_mul(price_per_token as u128)
+            .ok_or(CarbonError::MathOverflow)?
+            .checked_div(1_000_000_000)
+            .ok_or(CarbonError::MathOverflow)?;
+        let total_cost = u64::try_from(total_cost).map_err(|_| CarbonError::MathOverflow)?;
+
+        // The collateral counts toward payment, so only the remainder is collected now
+        let top_up = total_cost.saturating_sub(bid.collateral);
+        if top_up > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.bidder.to_account_info(),
+                        to: ctx.accounts.escrow.to_account_info(),
+                    },
+                ),
+                top_up,
+            )?;
+        }
+
+        bid.token_amount = token_amount;
+        bid.price_per_token = price_per_token;
+        bid.total_cost = total_cost.max(bid.collateral);
+        bid.status = BidStatus::Pending;
+
+        auction.revealed_count += 1;
+
+        emit!(BidRevealed {
+            auction: auction.key(),
+            bid: bid.key(),
+            bidder: bid.bidder,
+            token_amount,
+            price_per_token,
+            total_cost: bid.total_cost,
+        });
+
+        Ok(())
+    }
+
+    /// Sealed-bid auctions must pass every revealed bid as a writable remaining account so
+    /// the supply can be allocated among them.
+    pub fn finalize_auction<'info>(ctx: Context<'_, '_, 'info, 'info, FinalizeAuction<'info>>) -> Result<()> {
+        let auction = &mut ctx.accounts.auction;
+        let clock = Clock::get()?;
+
         require!(
-            clock.unix_timestamp >= auction.end_time || auction.tokens_remaining == 0,
-            CarbonError::AuctionNotEnded
+            auction.status == AuctionStatus::Active || auction.status == AuctionStatus::Completed,
+            CarbonError::InvalidAuctionStatus
         );
 
-        let clearing_price = if auction.tokens_remaining == 0 {
-            auction.current_price
-        } else {
-            auction.reserve_price
+        let clearing_price = match auction.kind {
+            AuctionKind::Dutch => {
+                require!(
+                    clock.unix_timestamp >= auction.end_time || auction.tokens_remaining == 0,
+                    CarbonError::AuctionNotEnded
+                );
+
+                if auction.tokens_remaining == 0 {
+                    auction.current_price
+                } else {
+                    auction.reserve_price
+                }
+            }
+            AuctionKind::SealedBid => {
+                require!(clock.unix_timestamp >= auction.reveal_end_time, CarbonError::AuctionNotEnded);
+                clear_sealed_bids(auction, ctx.remaining_accounts)?
+            }
         };
 
         auction.tokens_sold = auction.total_tokens - auction.tokens_remaining;
         auction.status = AuctionStatus::Finalized;
         auction.current_price = clearing_price;
 
+        emit!(AuctionFinalized {
+            auction: auction.key(),
+            clearing_price,
+            tokens_sold: auction.tokens_sold,
+            total_raised: auction.total_raised,
+        });
+
         Ok(())
     }
 
+    /// Settles a bid of a finalized auction: CT is minted to the bidder's CT account and any
+    /// overpayment or unforfeited collateral refunded. The bidder claims for itself, but an
+    /// auction operator may settle on its behalf so one absent bidder cannot hold up the
+    /// auction's proceeds; the outcome is the same either way.
     pub fn claim_tokens(ctx: Context<ClaimTokens>) -> Result<()> {
         let auction = &mut ctx.accounts.auction;
         let bid = &mut ctx.accounts.bid;
         let industry = &mut ctx.accounts.industry_account;
 
+        require!(
+            ctx.accounts.authority.key() == bid.bidder || ctx.accounts.role_assignment.is_some(),
+            CarbonError::Unauthorized
+        );
         require!(auction.status == AuctionStatus::Finalized, CarbonError::AuctionNotFinalized);
+
+        let payment = TokenPayment::resolve(
+            auction,
+            &ctx.accounts.payment_mint,
+            &ctx.accounts.escrow_token,
+            &ctx.accounts.bidder_token,
+            &ctx.accounts.payment_token_program,
+        )?;
+
+        ctx.accounts.vintage_holding.open(industry, auction.vintage, ctx.bumps.vintage_holding);
+
+        if bid.status == BidStatus::Committed {
+            // A commitment that was never revealed forfeits part of its collateral
+            let forfeited = (bid.collateral as u128)
+                .checked_mul(SEALED_BID_FORFEIT_BPS as u128)
+                .ok_or(CarbonError::MathOverflow)?
+                .checked_div(10_000)
+                .ok_or(CarbonError::MathOverflow)? as u64;
+            let refund_amount = bid.collateral - forfeited;
+
+            match &payment {
+                Some(payment) => payment.pay(&ctx.accounts.escrow, auction.key(), ctx.bumps.escrow, refund_amount)?,
+                None => pay_from_escrow(
+                    &ctx.accounts.system_program,
+                    &ctx.accounts.escrow,
+                    ctx.accounts.bidder.to_account_info(),
+                    auction.key(),
+                    ctx.bumps.escrow,
+                    refund_amount,
+                )?,
+            }
+
+            auction.total_raised = auction.total_raised
+                .checked_add(forfeited)
+                .ok_or(CarbonError::MathOverflow)?;
+            bid.status = BidStatus::Forfeited;
+            auction.settled_count += 1;
+            industry.settle_position();
+
+            emit!(BidForfeited {
+                auction: auction.key(),
+                bid: bid.key(),
+                bidder: bid.bidder,
+                forfeited,
+                refund_amount,
+            });
+
+            return Ok(());
+        }
+
         require!(bid.status == BidStatus::Pending, CarbonError::BidAlreadyProcessed);
+        settle_bidder_position(&mut ctx.accounts.position, bid, ctx.accounts.bidder.to_account_info())?;
 
         let clearing_price = auction.current_price;
         let final_cost = (bid.token_amount as u128)
@@ -287,41 +1644,140 @@ pub mod carbon {
             .checked_sub(final_cost)
             .ok_or(CarbonError::MathOverflow)?;
 
-        if refund_amount > 0 {
-            let rent_exempt = Rent::get()?.minimum_balance(ctx.accounts.escrow.data_len());
-            let available_balance = ctx.accounts.escrow.lamports()
-                .checked_sub(rent_exempt)
-                .ok_or(CarbonError::InsufficientFunds)?;
-
-            require!(available_balance >= refund_amount, CarbonError::InsufficientFunds);
+        match &payment {
+            Some(payment) => payment.pay(&ctx.accounts.escrow, auction.key(), ctx.bumps.escrow, refund_amount)?,
+            None => pay_from_escrow(
+                &ctx.accounts.system_program,
+                &ctx.accounts.escrow,
+                ctx.accounts.bidder.to_account_info(),
+                auction.key(),
+                ctx.bumps.escrow,
+                refund_amount,
+            )?,
+        }
 
-            let auction_key = auction.key();
-            let signer_seeds: &[&[&[u8]]] = &[&[
-                b"escrow",
-                auction_key.as_ref(),
-                &[ctx.bumps.escrow],
-            ]];
+        // Sealed bids priced out by the clearing price are allocated nothing and only refunded
+        if bid.token_amount == 0 {
+            bid.status = BidStatus::Refunded;
+        } else {
+            let mint_seeds: &[&[u8]] = &[
+                b"mint_authority",
+                &[ctx.bumps.mint_authority],
+            ];
+            let mint_signer: &[&[&[u8]]] = &[mint_seeds];
 
-            system_program::transfer(
+            token_interface::mint_to(
                 CpiContext::new_with_signer(
-                    ctx.accounts.system_program.to_account_info(), 
-                    system_program::Transfer { 
-                        from: ctx.accounts.escrow.to_account_info(), 
-                        to: ctx.accounts.bidder.to_account_info(),
-                    }, 
-                    signer_seeds
-                ), 
-                refund_amount
+                    ctx.accounts.token_program.to_account_info(),
+                    MintTo {
+                        mint: ctx.accounts.ct_mint.to_account_info(),
+                        to: ctx.accounts.industry_ct_account.to_account_info(),
+                        authority: ctx.accounts.mint_authority.to_account_info(),
+                    },
+                    mint_signer,
+                ),
+                bid.token_amount,
             )?;
+
+            industry.sync_ct_balance(&mut ctx.accounts.industry_ct_account)?;
+            industry.total_purchased += bid.token_amount;
+            ctx.accounts.vintage_holding.credit(bid.token_amount)?;
+
+            bid.status = BidStatus::Accepted;
         }
+        auction.settled_count += 1;
+        industry.settle_position();
+
+        emit!(TokensClaimed {
+            auction: auction.key(),
+            bid: bid.key(),
+            industry: industry.key(),
+            vintage: auction.vintage,
+            token_amount: bid.token_amount,
+            clearing_price,
+            final_cost,
+            refund_amount,
+        });
+
+        Ok(())
+    }
+
+    /// Opens a fixed-price sale of reserve allowances when an auction of the open period
+    /// cleared at or above the trigger price. Each auction can trigger one release.
+    pub fn release_reserve(ctx: Context<ReleaseReserve>) -> Result<()> {
+        let auction = &ctx.accounts.auction;
+        let reserve = &ctx.accounts.reserve_config;
+        let schedule = &mut ctx.accounts.cap_schedule;
+
+        require!(auction.status == AuctionStatus::Finalized, CarbonError::AuctionNotFinalized);
+        require!(auction.vintage == ctx.accounts.config.compliance_period, CarbonError::CompliancePeriodClosed);
+        // The trigger is priced in lamports, so only auctions paid in lamports can reach it
+        require!(
+            auction.payment_mint.is_none() && auction.current_price >= reserve.trigger_price,
+            CarbonError::ReserveNotTriggered
+        );
+
+        let total_tokens = reserve.release_amount.min(schedule.reserve_remaining());
+        require!(total_tokens > 0, CarbonError::ReserveExhausted);
+        schedule.issue_reserve(total_tokens)?;
+
+        let sale = &mut ctx.accounts.reserve_sale;
+        sale.trigger_auction = auction.key();
+        sale.vintage = auction.vintage;
+        sale.price_per_token = reserve.sale_price;
+        sale.total_tokens = total_tokens;
+        sale.tokens_remaining = total_tokens;
+        sale.opened_at = Clock::get()?.unix_timestamp;
+        sale.bump = ctx.bumps.reserve_sale;
+
+        emit!(ReserveReleased {
+            sale: sale.key(),
+            trigger_auction: sale.trigger_auction,
+            vintage: sale.vintage,
+            clearing_price: auction.current_price,
+            price_per_token: sale.price_per_token,
+            total_tokens,
+        });
+
+        Ok(())
+    }
+
+    pub fn buy_reserve_allowances(ctx: Context<BuyReserveAllowances>, token_amount: u64) -> Result<()> {
+        let sale = &mut ctx.accounts.reserve_sale;
+        let industry = &mut ctx.accounts.industry_account;
+
+        require!(industry.verified, CarbonError::NotVerified);
+        require!(!industry.suspended, CarbonError::IndustrySuspended);
+        // Unsold reserve allowances lapse with the period they were released for
+        require!(sale.vintage == ctx.accounts.config.compliance_period, CarbonError::ReserveSaleClosed);
+        require!(token_amount > 0 && token_amount <= sale.tokens_remaining, CarbonError::InvalidTokenAmount);
+
+        let total_cost = (token_amount as u128)
+            .checked_mul(sale.price_per_token as u128)
+            .ok_or(CarbonError::MathOverflow)?
+            .checked_div(1_000_000_000) // Divide by 10^9 for token decimals
+            .ok_or(CarbonError::MathOverflow)?;
+        let total_cost = u64::try_from(total_cost).map_err(|_| CarbonError::MathOverflow)?;
+        require!(total_cost > 0, CarbonError::InvalidTokenAmount);
+
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.buyer.to_account_info(),
+                    to: ctx.accounts.treasury.to_account_info(),
+                },
+            ),
+            total_cost,
+        )?;
 
         let mint_seeds: &[&[u8]] = &[
             b"mint_authority",
             &[ctx.bumps.mint_authority],
         ];
-        let mint_signer: &[&[&[u8]]] = &[&mint_seeds[..]];
+        let mint_signer: &[&[&[u8]]] = &[mint_seeds];
 
-        token::mint_to(
+        token_interface::mint_to(
             CpiContext::new_with_signer(
                 ctx.accounts.token_program.to_account_info(),
                 MintTo {
@@ -331,24 +1787,101 @@ pub mod carbon {
                 },
                 mint_signer,
             ),
-            bid.token_amount,
+            token_amount,
         )?;
 
-        industry.ct_balance += bid.token_amount;
-        industry.total_purchased += bid.token_amount;
+        let vintage_holding = &mut ctx.accounts.vintage_holding;
+        vintage_holding.open(industry, sale.vintage, ctx.bumps.vintage_holding);
+        vintage_holding.credit(token_amount)?;
 
-        bid.status = BidStatus::Accepted;
+        sale.tokens_remaining -= token_amount;
+        industry.sync_ct_balance(&mut ctx.accounts.industry_ct_account)?;
+        industry.total_purchased += token_amount;
 
-        Ok(())
+        emit!(ReserveAllowancesPurchased {
+            sale: sale.key(),
+            industry: industry.key(),
+            token_amount,
+            price_per_token: sale.price_per_token,
+            total_cost,
+            tokens_remaining: sale.tokens_remaining,
+        });
+
+        Ok(())
     }
 
     pub fn cancel_auction(ctx: Context<CancelAuction>) -> Result<()> {
         let auction = &mut ctx.accounts.auction;
 
-        require!(auction.status == AuctionStatus::Active, CarbonError::InvalidAuctionStatus);
-        require!(auction.participant_count == 0, CarbonError::HasParticipants);
+        // Bids already placed are not lost: each bidder recovers its payment with `refund_bid`
+        require!(
+            auction.status == AuctionStatus::Active || auction.status == AuctionStatus::Completed,
+            CarbonError::InvalidAuctionStatus
+        );
 
         auction.status = AuctionStatus::Cancelled;
+        // Nothing from a cancelled batch is ever minted, so its allowances go back into the budget
+        let cap_schedule = &mut ctx.accounts.cap_schedule;
+        cap_schedule.auctioned = cap_schedule
+            .auctioned
+            .checked_sub(auction.total_tokens)
+            .ok_or(CarbonError::MathOverflow)?;
+
+        emit!(AuctionCancelled {
+            auction: auction.key(),
+            batch_number: auction.batch_number,
+            participant_count: auction.participant_count,
+        });
+
+        Ok(())
+    }
+
+    /// Returns a bid's payment or collateral once its auction is cancelled. Like a claim, it
+    /// may be signed by the bidder or by an auction operator on the bidder's behalf.
+    pub fn refund_bid(ctx: Context<RefundBid>) -> Result<()> {
+        let auction = &mut ctx.accounts.auction;
+        let bid = &mut ctx.accounts.bid;
+
+        require!(
+            ctx.accounts.authority.key() == bid.bidder || ctx.accounts.role_assignment.is_some(),
+            CarbonError::Unauthorized
+        );
+        require!(auction.status == AuctionStatus::Cancelled, CarbonError::AuctionNotCancelled);
+        require!(
+            bid.status == BidStatus::Pending || bid.status == BidStatus::Committed,
+            CarbonError::BidAlreadyProcessed
+        );
+
+        let payment = TokenPayment::resolve(
+            auction,
+            &ctx.accounts.payment_mint,
+            &ctx.accounts.escrow_token,
+            &ctx.accounts.bidder_token,
+            &ctx.accounts.payment_token_program,
+        )?;
+        match payment {
+            Some(payment) => payment.pay(&ctx.accounts.escrow, auction.key(), ctx.bumps.escrow, bid.total_cost)?,
+            None => pay_from_escrow(
+                &ctx.accounts.system_program,
+                &ctx.accounts.escrow,
+                ctx.accounts.bidder.to_account_info(),
+                auction.key(),
+                ctx.bumps.escrow,
+                bid.total_cost,
+            )?,
+        }
+
+        settle_bidder_position(&mut ctx.accounts.position, bid, ctx.accounts.bidder.to_account_info())?;
+        bid.status = BidStatus::Refunded;
+        auction.settled_count += 1;
+        ctx.accounts.industry_account.settle_position();
+
+        emit!(BidRefunded {
+            auction: auction.key(),
+            bid: bid.key(),
+            bidder: bid.bidder,
+            amount: bid.total_cost,
+        });
 
         Ok(())
     }
@@ -357,6 +1890,35 @@ pub mod carbon {
         let auction = &ctx.accounts.auction;
 
         require!(auction.status == AuctionStatus::Finalized, CarbonError::AuctionNotFinalized);
+        // The escrow still holds the refunds owed to bids that have not been settled; an auction
+        // operator can settle any a bidder leaves unclaimed through `claim_tokens`
+        require!(auction.settled_count == auction.participant_count, CarbonError::BidsOutstanding);
+
+        let payment = TokenPayment::resolve(
+            auction,
+            &ctx.accounts.payment_mint,
+            &ctx.accounts.escrow_token,
+            &ctx.accounts.treasury_token,
+            &ctx.accounts.payment_token_program,
+        )?;
+
+        if let Some(payment) = payment {
+            // With every bid claimed, refunded or forfeited, whatever the escrow still holds is earned
+            let escrow_balance = payment.escrow_token.amount;
+            require!(escrow_balance > 0, CarbonError::InsufficientFunds);
+
+            payment.pay(&ctx.accounts.escrow, auction.key(), ctx.bumps.escrow, escrow_balance)?;
+
+            emit!(ProceedsWithdrawn {
+                auction: auction.key(),
+                escrow: payment.escrow_token.key(),
+                treasury: payment.counterparty.key(),
+                payment_mint: auction.payment_mint,
+                amount: escrow_balance,
+            });
+
+            return Ok(());
+        }
 
         let rent_exempt = Rent::get()?.minimum_balance(ctx.accounts.escrow.data_len());
         let escrow_balance = ctx.accounts.escrow.lamports()
@@ -384,428 +1946,4413 @@ pub mod carbon {
             escrow_balance,
         )?;
 
+        emit!(ProceedsWithdrawn {
+            auction: auction.key(),
+            escrow: ctx.accounts.escrow.key(),
+            treasury: ctx.accounts.treasury.key(),
+            payment_mint: None,
+            amount: escrow_balance,
+        });
+
         Ok(())
     }
-}
 
-fn calculate_current_price(start_price: u64, reserve_price: u64, start_time: i64, end_time: i64, current_time: i64) -> Result<u64> {
-    if current_time >= end_time {
-        return Ok(reserve_price);
-    }
-    if current_time <= start_time {
-        return Ok(start_price);
-    }
+    pub fn close_bid(ctx: Context<CloseBid>) -> Result<()> {
+        let bid = &ctx.accounts.bid;
 
-    let duration = end_time - start_time;
-    let elapsed = current_time - start_time;
-    let price_range = start_price - reserve_price;
+        require!(
+            matches!(bid.status, BidStatus::Accepted | BidStatus::Refunded | BidStatus::Forfeited),
+            CarbonError::BidNotSettled
+        );
 
-    let decay = (price_range as u128)
-        .checked_mul(elapsed as u128)
-        .ok_or(CarbonError::MathOverflow)?
-        .checked_div(duration as u128)
-        .ok_or(CarbonError::MathOverflow)?;
+        emit!(BidClosed {
+            auction: bid.auction,
+            bid: bid.key(),
+            bidder: bid.bidder,
+        });
 
-    let current_price = start_price
-        .checked_sub(decay as u64).ok_or(CarbonError::MathOverflow)?;
-    Ok(current_price.max(reserve_price))
-}
+        Ok(())
+    }
 
-#[derive(Accounts)]
-pub struct InitializeMint<'info> {
-    #[account(mut)]
-    pub authority: Signer<'info>,
+    pub fn close_auction(ctx: Context<CloseAuction>) -> Result<()> {
+        let auction = &ctx.accounts.auction;
 
-    #[account(
-        init,
-        payer = authority,
-        mint::decimals = 9,
-        mint::authority = mint_authority
-    )]
-    pub ct_mint: Account<'info, Mint>,
+        require!(
+            auction.status == AuctionStatus::Finalized || auction.status == AuctionStatus::Cancelled,
+            CarbonError::InvalidAuctionStatus
+        );
+        require!(auction.settled_count == auction.participant_count, CarbonError::BidsOutstanding);
 
-    /// CHECK: PDA authority for minting
-    #[account(
-        seeds = [b"mint_authority"],
-        bump,
-    )]
-    pub mint_authority: UncheckedAccount<'info>,
+        // `withdraw_proceeds` leaves the escrow's rent reserve behind; anything above it is unwithdrawn proceeds
+        let escrow_balance = ctx.accounts.escrow.lamports();
+        let rent_exempt = Rent::get()?.minimum_balance(ctx.accounts.escrow.data_len());
+        require!(escrow_balance <= rent_exempt, CarbonError::ProceedsNotWithdrawn);
 
-    pub token_program: Program<'info, Token>,
-    pub rent: Sysvar<'info, Rent>,
-    pub system_program: Program<'info, System>,
-}
+        if auction.payment_mint.is_some() {
+            let (Some(escrow_token), Some(token_program)) = (&ctx.accounts.escrow_token, &ctx.accounts.payment_token_program) else {
+                return err!(CarbonError::InvalidPaymentMint);
+            };
+            require!(escrow_token.amount == 0, CarbonError::ProceedsNotWithdrawn);
 
-#[derive(Accounts)]
-pub struct RegisterIndustry<'info> {
-    #[account(
-        init,
-        payer = authority,
-        space = 8 + IndustryAccount::INIT_SPACE,
-        seeds = [b"industry", authority.key().as_ref()],
-        bump,
-    )]
-    pub industry_account: Account<'info, IndustryAccount>,
-    #[account(mut)]
-    pub authority: Signer<'info>,
+            let auction_key = auction.key();
+            token_interface::close_account(CpiContext::new_with_signer(
+                token_program.to_account_info(),
+                CloseAccount {
+                    account: escrow_token.to_account_info(),
+                    destination: ctx.accounts.rent_recipient.to_account_info(),
+                    authority: ctx.accounts.escrow.to_account_info(),
+                },
+                &[&[b"escrow", auction_key.as_ref(), &[ctx.bumps.escrow]]],
+            ))?;
+        }
 
-    pub system_program: Program<'info, System>,
-}
+        pay_from_escrow(
+            &ctx.accounts.system_program,
+            &ctx.accounts.escrow,
+            ctx.accounts.treasury.to_account_info(),
+            auction.key(),
+            ctx.bumps.escrow,
+            escrow_balance,
+        )?;
 
-#[derive(Accounts)]
-pub struct VerifyIndustry<'info> {
-    pub admin: Signer<'info>,
+        emit!(AuctionClosed {
+            auction: auction.key(),
+            batch_number: auction.batch_number,
+            rent_recipient: auction.authority,
+            escrow_swept: escrow_balance,
+        });
 
-    #[account(
-        mut,
-        seeds = [b"industry", industry_account.authority.as_ref()],
-        bump = industry_account.bump,
-    )]
-    pub industry_account: Account<'info, IndustryAccount>,
-}
+        Ok(())
+    }
 
-#[derive(Accounts)]
-pub struct DepositBond<'info> {
-    #[account(
-        mut,
-        seeds = [b"industry", authority.key().as_ref()],
-        bump = industry_account.bump,
-    )]
-    pub industry_account: Account<'info, IndustryAccount>,
-    
-    /// CHECK: Bond vault - system account to hold SOL
-    #[account(
-        mut,
-        seeds = [b"bond_vault", industry_account.key().as_ref()],
-        bump,
-    )]
-    pub bond_vault: SystemAccount<'info>,
+    pub fn archive_emission_report(ctx: Context<ArchiveEmissionReport>) -> Result<()> {
+        let report = &ctx.accounts.emission_report;
+        let industry = &ctx.accounts.industry_account;
 
-    #[account(mut)]
-    pub authority: Signer<'info>,
-    pub system_program: Program<'info, System>,
-}
+        require!(report.status != ReportStatus::Pending, CarbonError::ReportNotArchivable);
+        require!(
+            report.compliance_period < ctx.accounts.config.compliance_period,
+            CarbonError::ReportNotArchivable
+        );
+        // Uncovered obligations are carried forward, so this also covers the report's own period
+        require!(industry.outstanding_obligation() == 0, CarbonError::ObligationsOutstanding);
 
-#[derive(Accounts)]
-pub struct BurnCT<'info> {
-    #[account(
-        mut,
-        seeds = [b"industry", authority.key().as_ref()],
-        bump = industry_account.bump,
-        constraint = industry_account.authority == authority.key() 
-    )]
-    pub industry_account: Account<'info, IndustryAccount>,
+        emit!(ReportArchived {
+            report: report.key(),
+            industry: industry.key(),
+            compliance_period: report.compliance_period,
+        });
 
-    #[account(mut)]
-    pub ct_mint: Account<'info, Mint>,
-    
-    #[account(
-        mut,
-        constraint = industry_ct_account.owner == authority.key()
-    )]
-    pub industry_ct_account: Account<'info, TokenAccount>,
+        Ok(())
+    }
 
-    pub authority: Signer<'info>,
-    pub token_program: Program<'info, Token>,
-}
+    /// Mints free allowances to a verified industry, counted against the free-allocation cap.
+    pub fn allocate_free_allowances(ctx: Context<AllocateFreeAllowances>, amount: u64) -> Result<()> {
+        let industry = &mut ctx.accounts.industry_account;
+        let compliance_period = ctx.accounts.config.compliance_period;
 
-#[derive(Accounts)]
-#[instruction(co2_emitted: u64, report_period: String, timestamp: u64)]
-pub struct SubmitEmissionReport<'info> {
-    #[account(
-        mut,
-        seeds = [b"industry", authority.key().as_ref()],
-        bump = industry_account.bump,
-    )]
-    pub industry_account: Account<'info, IndustryAccount>,
+        require!(industry.verified, CarbonError::NotVerified);
+        require!(!industry.suspended, CarbonError::IndustrySuspended);
+        require!(amount > 0, CarbonError::InvalidTokenAmount);
 
-    #[account(
-        init,
-        payer = authority,
-        space = 8 + EmissionReport::INIT_SPACE,
-        seeds = [b"emission_report", industry_account.key().as_ref(), timestamp.to_le_bytes().as_ref()],
-        bump
-    )]
-    pub emission_report: Account<'info, EmissionReport>,
-    
-    #[account(mut)]
-    pub authority: Signer<'info>,
-    pub system_program: Program<'info, System>,
-}
+        ctx.accounts.cap_schedule.issue_allocated(amount)?;
 
-#[derive(Accounts)]
-pub struct WithdrawBond<'info> {
-    #[account(
-        mut,
-        seeds = [b"industry", authority.key().as_ref()],
-        bump = industry_account.bump,
-        constraint = industry_account.authority == authority.key()
-    )]
-    pub industry_account: Account<'info, IndustryAccount>,
+        let mint_seeds: &[&[u8]] = &[
+            b"mint_authority",
+            &[ctx.bumps.mint_authority],
+        ];
+        let mint_signer: &[&[&[u8]]] = &[mint_seeds];
 
-    /// CHECK: Bond vault - system account holding SOL
-    #[account(
-        mut,
-        seeds = [b"bond_vault", industry_account.key().as_ref()],
-        bump = industry_account.bond_vault_bump,
-    )]
-    pub bond_vault: SystemAccount<'info>,
+        token_interface::mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                MintTo {
+                    mint: ctx.accounts.ct_mint.to_account_info(),
+                    to: ctx.accounts.industry_ct_account.to_account_info(),
+                    authority: ctx.accounts.mint_authority.to_account_info(),
+                },
+                mint_signer,
+            ),
+            amount,
+        )?;
 
-    #[account(mut)]
-    pub authority: Signer<'info>,
-    pub system_program: Program<'info, System>,
-}
+        // Free allowances carry the vintage of the period they were allocated for
+        let vintage_holding = &mut ctx.accounts.vintage_holding;
+        vintage_holding.open(industry, compliance_period, ctx.bumps.vintage_holding);
+        vintage_holding.credit(amount)?;
 
-#[derive(Accounts)]
-#[instruction(batch_number: u32)]
-pub struct CreateAuction<'info> {
-    #[account(
-        init,
-        payer = authority,
-        space = 8 + Auction::INIT_SPACE,
-        seeds = [b"auction", batch_number.to_le_bytes().as_ref()],
-        bump,
-    )]
-    pub auction: Account<'info, Auction>,
+        industry.roll_to_period(compliance_period);
+        industry.sync_ct_balance(&mut ctx.accounts.industry_ct_account)?;
+        industry.period_allocated = industry.period_allocated
+            .checked_add(amount)
+            .ok_or(CarbonError::MathOverflow)?;
+        industry.total_allocated = industry.total_allocated
+            .checked_add(amount)
+            .ok_or(CarbonError::MathOverflow)?;
 
-    #[account(mut)]
-    pub authority: Signer<'info>,
+        emit!(FreeAllowancesAllocated {
+            industry: industry.key(),
+            regulator: ctx.accounts.regulator.key(),
+            compliance_period,
+            amount,
+            period_allocated: industry.period_allocated,
+            allocated: ctx.accounts.cap_schedule.allocated,
+        });
 
-    pub system_program: Program<'info, System>,
-}
+        Ok(())
+    }
 
-#[derive(Accounts)]
-#[instruction(token_amount: u64, timestamp: u64)]
-pub struct PlaceBid<'info> {
-    #[account(
-        mut,
-        seeds = [b"auction", &auction.batch_number.to_le_bytes()],
-        bump,
-    )]
-    pub auction: Account<'info, Auction>,
+    pub fn register_offset_project(ctx: Context<RegisterOffsetProject>, project_id: u64, name: String, kind: OffsetKind) -> Result<()> {
+        let project = &mut ctx.accounts.offset_project;
+
+        project.developer = ctx.accounts.developer.key();
+        project.project_id = project_id;
+        project.name = name;
+        project.kind = kind;
+        project.approved = false;
+        project.approved_by = None;
+        project.total_issued = 0;
+        project.created_at = Clock::get()?.unix_timestamp;
+        project.bump = ctx.bumps.offset_project;
+
+        emit!(OffsetProjectRegistered {
+            project: project.key(),
+            developer: project.developer,
+            project_id,
+            kind: project.kind.clone(),
+        });
 
-    #[account(
-        init,
-        payer = bidder,
-        space = 8 + Bid::INIT_SPACE,
-        seeds = [
-            b"bid",
-            auction.key().as_ref(),
-            bidder.key().as_ref(),
-            timestamp.to_le_bytes().as_ref()
-        ],
-        bump,
-    )]
-    pub bid: Account<'info, Bid>,
+        Ok(())
+    }
 
-    #[account(
-        mut,
-        seeds = [b"industry", bidder.key().as_ref()],
-        bump,
-    )]
-    pub industry_account: Account<'info, IndustryAccount>,
+    pub fn approve_offset_project(ctx: Context<ApproveOffsetProject>) -> Result<()> {
+        let project = &mut ctx.accounts.offset_project;
 
-    /// CHECK: Escrow account - PDA to hold SOL
-    #[account(
-        mut,
-        seeds = [b"escrow", auction.key().as_ref()],
-        bump,
-    )]
-    pub escrow: SystemAccount<'info>,
+        require!(!project.approved, CarbonError::ProjectAlreadyApproved);
+        project.approved = true;
+        project.approved_by = Some(ctx.accounts.verifier.key());
 
-    #[account(mut)]
-    pub bidder: Signer<'info>,
+        emit!(OffsetProjectApproved {
+            project: project.key(),
+            developer: project.developer,
+            verifier: ctx.accounts.verifier.key(),
+        });
 
-    pub system_program: Program<'info, System>,
+        Ok(())
+    }
+
+    pub fn submit_removal_report(ctx: Context<SubmitRemovalReport>, co2_removed: u64, _timestamp: u64) -> Result<()> {
+        let project = &ctx.accounts.offset_project;
+        let report = &mut ctx.accounts.removal_report;
+
+        require!(project.approved, CarbonError::ProjectNotApproved);
+        require!(co2_removed > 0, CarbonError::InvalidTokenAmount);
+
+        report.project = project.key();
+        report.beneficiary = ctx.accounts.beneficiary.key();
+        report.co2_removed = co2_removed;
+        report.submitted_at = Clock::get()?.unix_timestamp;
+        report.status = ReportStatus::Pending;
+        report.verifier = None;
+        report.issued_at = 0;
+
+        emit!(RemovalReportSubmitted {
+            report: report.key(),
+            project: report.project,
+            beneficiary: report.beneficiary,
+            co2_removed,
+        });
+
+        Ok(())
+    }
+
+    /// Verifies a removal report and mints its removals as offset credits to the beneficiary.
+    pub fn issue_offset_credits(ctx: Context<IssueOffsetCredits>) -> Result<()> {
+        let project = &mut ctx.accounts.offset_project;
+        let report = &mut ctx.accounts.removal_report;
+        let industry = &mut ctx.accounts.industry_account;
+
+        require!(project.approved, CarbonError::ProjectNotApproved);
+        require!(report.status == ReportStatus::Pending, CarbonError::ReportAlreadyReviewed);
+        require!(industry.verified, CarbonError::NotVerified);
+        require!(!industry.suspended, CarbonError::IndustrySuspended);
+
+        let amount = report.co2_removed;
+        let mint_seeds: &[&[u8]] = &[
+            b"mint_authority",
+            &[ctx.bumps.mint_authority],
+        ];
+        let mint_signer: &[&[&[u8]]] = &[mint_seeds];
+
+        token_interface::mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                MintTo {
+                    mint: ctx.accounts.ct_mint.to_account_info(),
+                    to: ctx.accounts.industry_ct_account.to_account_info(),
+                    authority: ctx.accounts.mint_authority.to_account_info(),
+                },
+                mint_signer,
+            ),
+            amount,
+        )?;
+
+        report.status = ReportStatus::Verified;
+        report.verifier = Some(ctx.accounts.verifier.key());
+        report.issued_at = Clock::get()?.unix_timestamp;
+
+        project.total_issued = project.total_issued
+            .checked_add(amount)
+            .ok_or(CarbonError::MathOverflow)?;
+
+        industry.sync_ct_balance(&mut ctx.accounts.industry_ct_account)?;
+        industry.offset_balance = industry.offset_balance
+            .checked_add(amount)
+            .ok_or(CarbonError::MathOverflow)?;
+
+        emit!(OffsetCreditsIssued {
+            report: report.key(),
+            project: project.key(),
+            industry: industry.key(),
+            verifier: ctx.accounts.verifier.key(),
+            amount,
+            offset_balance: industry.offset_balance,
+        });
+
+        Ok(())
+    }
+
+    pub fn set_offset_limit(ctx: Context<SetOffsetLimit>, max_offset_bps: u16) -> Result<()> {
+        let industry = &mut ctx.accounts.industry_account;
+
+        require!(max_offset_bps <= 10_000, CarbonError::InvalidOffsetLimit);
+        industry.max_offset_bps = max_offset_bps;
+
+        emit!(OffsetLimitUpdated {
+            industry: industry.key(),
+            regulator: ctx.accounts.regulator.key(),
+            max_offset_bps,
+        });
+
+        Ok(())
+    }
+
+    pub fn burn_offset_credits_for_compliance(ctx: Context<BurnOffsetCredits>, amount: u64) -> Result<()> {
+        let industry = &mut ctx.accounts.industry_account;
+
+        require!(industry.verified, CarbonError::NotVerified);
+        require!(industry.signer_threshold == 0, CarbonError::MultisigGoverned);
+        require!(industry.offset_balance >= amount, CarbonError::InsufficientOffsetCredits);
+        require!(ctx.accounts.industry_ct_account.amount >= amount, CarbonError::InsufficientCT);
+
+        industry.roll_to_period(ctx.accounts.config.compliance_period);
+        require!(amount <= industry.outstanding_obligation(), CarbonError::ExceedsObligation);
+        require!(amount <= industry.offset_allowance(), CarbonError::OffsetLimitExceeded);
+
+        token_interface::burn(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Burn {
+                    mint: ctx.accounts.ct_mint.to_account_info(),
+                    from: ctx.accounts.industry_ct_account.to_account_info(),
+                    authority: ctx.accounts.authority.to_account_info()
+                },
+            ),
+            amount,
+        )?;
+
+        industry.sync_ct_balance(&mut ctx.accounts.industry_ct_account)?;
+        industry.offset_balance -= amount;
+        industry.total_burned += amount;
+        industry.period_surrendered += amount;
+        industry.period_offsets_surrendered += amount;
+        industry.refresh_compliance_status();
+
+        emit!(OffsetCreditsBurned {
+            industry: industry.key(),
+            amount,
+            compliance_period: industry.compliance_period,
+            period_obligation: industry.period_obligation,
+            period_surrendered: industry.period_surrendered,
+            period_offsets_surrendered: industry.period_offsets_surrendered,
+            compliance_status: industry.compliance_status.clone(),
+        });
+
+        Ok(())
+    }
+
+    /// Resets `ct_balance` to the amount actually held in the industry's CT account, picking
+    /// up any transfers made outside the program. Anyone may call it.
+    pub fn sync_ct_balance(ctx: Context<SyncCtBalance>) -> Result<()> {
+        let industry = &mut ctx.accounts.industry_account;
+        let previous_balance = industry.ct_balance;
+
+        industry.sync_ct_balance(&mut ctx.accounts.industry_ct_account)?;
+
+        emit!(CtBalanceSynced {
+            industry: industry.key(),
+            previous_balance,
+            ct_balance: industry.ct_balance,
+        });
+
+        Ok(())
+    }
+
+    pub fn set_industry_suspension(ctx: Context<SetIndustrySuspension>, suspended: bool) -> Result<()> {
+        let industry = &mut ctx.accounts.industry_account;
+
+        industry.suspended = suspended;
+
+        emit!(IndustrySuspensionChanged {
+            industry: industry.key(),
+            authority: industry.authority,
+            regulator: ctx.accounts.regulator.key(),
+            suspended,
+        });
+
+        Ok(())
+    }
+
+    pub fn accredit_trader(ctx: Context<AccreditTrader>, trader: Pubkey) -> Result<()> {
+        let accreditation = &mut ctx.accounts.accredited_trader;
+
+        accreditation.trader = trader;
+        accreditation.accredited_by = ctx.accounts.verifier.key();
+        accreditation.accredited_at = Clock::get()?.unix_timestamp;
+        accreditation.bump = ctx.bumps.accredited_trader;
+
+        emit!(TraderAccredited {
+            accredited_trader: accreditation.key(),
+            trader,
+            verifier: accreditation.accredited_by,
+        });
+
+        Ok(())
+    }
+
+    pub fn revoke_trader_accreditation(ctx: Context<RevokeTraderAccreditation>) -> Result<()> {
+        let accreditation = &ctx.accounts.accredited_trader;
+
+        emit!(TraderAccreditationRevoked {
+            accredited_trader: accreditation.key(),
+            trader: accreditation.trader,
+            verifier: ctx.accounts.verifier.key(),
+        });
+
+        Ok(())
+    }
+
+    /// Opens a peer-to-peer offer. A sell offer escrows the CT on offer; a buy offer escrows
+    /// the SOL it will pay. CT held in escrow no longer counts toward the maker's `ct_balance`
+    /// or vintage holding.
+    pub fn create_offer<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CreateOffer<'info>>,
+        offer_id: u64,
+        side: OfferSide,
+        vintage: u32,
+        token_amount: u64,
+        price_per_token: u64,
+    ) -> Result<()> {
+        let industry = &mut ctx.accounts.industry_account;
+
+        require!(industry.verified, CarbonError::NotVerified);
+        require!(!industry.suspended, CarbonError::IndustrySuspended);
+        // Offers move CT and SOL without a proposal, so signer-governed industries cannot trade here
+        require!(industry.signer_threshold == 0, CarbonError::MultisigGoverned);
+        require!(token_amount > 0, CarbonError::InvalidTokenAmount);
+        require!(price_per_token > 0, CarbonError::InvalidOfferPrice);
+
+        let total_price = (token_amount as u128)
+            .checked_mul(price_per_token as u128)
+            .ok_or(CarbonError::MathOverflow)?
+            .checked_div(1_000_000_000) // Divide by 10^9 for token decimals
+            .ok_or(CarbonError::MathOverflow)?;
+        let total_price = u64::try_from(total_price).map_err(|_| CarbonError::MathOverflow)?;
+        require!(total_price > 0, CarbonError::InvalidOfferPrice);
+
+        // Buy offers open the maker's holding up front so accepting only has to credit it
+        ctx.accounts.maker_vintage.open(industry, vintage, ctx.bumps.maker_vintage);
+
+        match side {
+            OfferSide::Sell => {
+                require!(ctx.accounts.maker_ct_account.amount >= token_amount, CarbonError::InsufficientCT);
+                ctx.accounts.maker_vintage.debit(token_amount)?;
+
+                transfer_ct(
+                    &ctx.accounts.token_program,
+                    ctx.accounts.maker_ct_account.to_account_info(),
+                    &ctx.accounts.ct_mint,
+                    ctx.accounts.offer_escrow.to_account_info(),
+                    ctx.accounts.maker.to_account_info(),
+                    ctx.remaining_accounts,
+                    token_amount,
+                    &[],
+                )?;
+
+                industry.sync_ct_balance(&mut ctx.accounts.maker_ct_account)?;
+            }
+            OfferSide::Buy => {
+                system_program::transfer(
+                    CpiContext::new(
+                        ctx.accounts.system_program.to_account_info(),
+                        system_program::Transfer {
+                            from: ctx.accounts.maker.to_account_info(),
+                            to: ctx.accounts.offer.to_account_info(),
+                        },
+                    ),
+                    total_price,
+                )?;
+            }
+        }
+
+        let offer = &mut ctx.accounts.offer;
+        offer.maker = ctx.accounts.maker.key();
+        offer.offer_id = offer_id;
+        offer.side = side;
+        offer.vintage = vintage;
+        offer.token_amount = token_amount;
+        offer.price_per_token = price_per_token;
+        offer.total_price = total_price;
+        offer.created_at = Clock::get()?.unix_timestamp;
+        offer.bump = ctx.bumps.offer;
+        ctx.accounts.industry_account.open_position()?;
+
+        emit!(OfferCreated {
+            offer: offer.key(),
+            maker: offer.maker,
+            side,
+            vintage,
+            token_amount,
+            price_per_token,
+            total_price,
+        });
+
+        Ok(())
+    }
+
+    /// Fills an offer in full: CT and SOL change hands in one instruction and the offer
+    /// and its escrow are closed back to the maker.
+    pub fn accept_offer<'info>(ctx: Context<'_, '_, 'info, 'info, AcceptOffer<'info>>) -> Result<()> {
+        let offer = &ctx.accounts.offer;
+        let maker_industry = &mut ctx.accounts.maker_industry;
+        let taker_industry = &mut ctx.accounts.taker_industry;
+
+        require!(taker_industry.verified, CarbonError::NotVerified);
+        require!(!taker_industry.suspended, CarbonError::IndustrySuspended);
+        require!(taker_industry.signer_threshold == 0, CarbonError::MultisigGoverned);
+        require!(!maker_industry.suspended, CarbonError::IndustrySuspended);
+        require!(offer.maker != ctx.accounts.taker.key(), CarbonError::SelfTrade);
+
+        ctx.accounts.taker_vintage.open(taker_industry, offer.vintage, ctx.bumps.taker_vintage);
+
+        let authority_seeds: &[&[&[u8]]] = &[&[b"offer_authority", &[ctx.bumps.offer_authority]]];
+
+        match offer.side {
+            OfferSide::Sell => {
+                system_program::transfer(
+                    CpiContext::new(
+                        ctx.accounts.system_program.to_account_info(),
+                        system_program::Transfer {
+                            from: ctx.accounts.taker.to_account_info(),
+                            to: ctx.accounts.maker.to_account_info(),
+                        },
+                    ),
+                    offer.total_price,
+                )?;
+
+                transfer_ct(
+                    &ctx.accounts.token_program,
+                    ctx.accounts.offer_escrow.to_account_info(),
+                    &ctx.accounts.ct_mint,
+                    ctx.accounts.taker_ct_account.to_account_info(),
+                    ctx.accounts.offer_authority.to_account_info(),
+                    ctx.remaining_accounts,
+                    offer.token_amount,
+                    authority_seeds,
+                )?;
+
+                taker_industry.sync_ct_balance(&mut ctx.accounts.taker_ct_account)?;
+                taker_industry.total_purchased += offer.token_amount;
+                ctx.accounts.taker_vintage.credit(offer.token_amount)?;
+            }
+            OfferSide::Buy => {
+                // The maker is the one receiving CT here
+                require!(maker_industry.verified, CarbonError::NotVerified);
+                require!(ctx.accounts.taker_ct_account.amount >= offer.token_amount, CarbonError::InsufficientCT);
+                ctx.accounts.taker_vintage.debit(offer.token_amount)?;
+
+                transfer_ct(
+                    &ctx.accounts.token_program,
+                    ctx.accounts.taker_ct_account.to_account_info(),
+                    &ctx.accounts.ct_mint,
+                    ctx.accounts.maker_ct_account.to_account_info(),
+                    ctx.accounts.taker.to_account_info(),
+                    ctx.remaining_accounts,
+                    offer.token_amount,
+                    &[],
+                )?;
+
+                // The offer account is owned by this program, so the escrowed SOL can be paid out directly
+                ctx.accounts.offer.sub_lamports(offer.total_price)?;
+                ctx.accounts.taker.add_lamports(offer.total_price)?;
+
+                taker_industry.sync_ct_balance(&mut ctx.accounts.taker_ct_account)?;
+                maker_industry.sync_ct_balance(&mut ctx.accounts.maker_ct_account)?;
+                maker_industry.total_purchased += offer.token_amount;
+                ctx.accounts.maker_vintage.credit(offer.token_amount)?;
+            }
+        }
+
+        maker_industry.settle_position();
+
+        close_offer_escrow(
+            &ctx.accounts.token_program,
+            &ctx.accounts.offer_escrow,
+            ctx.accounts.maker.to_account_info(),
+            &ctx.accounts.offer_authority,
+            authority_seeds,
+        )?;
+
+        emit!(OfferAccepted {
+            offer: offer.key(),
+            maker: offer.maker,
+            taker: ctx.accounts.taker.key(),
+            side: offer.side,
+            token_amount: offer.token_amount,
+            total_price: offer.total_price,
+        });
+
+        Ok(())
+    }
+
+    /// Withdraws an open offer, returning escrowed CT to the maker's token account and
+    /// escrowed SOL along with the offer's rent.
+    pub fn cancel_offer<'info>(ctx: Context<'_, '_, 'info, 'info, CancelOffer<'info>>) -> Result<()> {
+        let offer = &ctx.accounts.offer;
+        let industry = &mut ctx.accounts.industry_account;
+
+        let authority_seeds: &[&[&[u8]]] = &[&[b"offer_authority", &[ctx.bumps.offer_authority]]];
+
+        if offer.side == OfferSide::Sell {
+            transfer_ct(
+                &ctx.accounts.token_program,
+                ctx.accounts.offer_escrow.to_account_info(),
+                &ctx.accounts.ct_mint,
+                ctx.accounts.maker_ct_account.to_account_info(),
+                ctx.accounts.offer_authority.to_account_info(),
+                ctx.remaining_accounts,
+                offer.token_amount,
+                authority_seeds,
+            )?;
+
+            industry.sync_ct_balance(&mut ctx.accounts.maker_ct_account)?;
+            ctx.accounts.maker_vintage.credit(offer.token_amount)?;
+        }
+        industry.settle_position();
+
+        close_offer_escrow(
+            &ctx.accounts.token_program,
+            &ctx.accounts.offer_escrow,
+            ctx.accounts.maker.to_account_info(),
+            &ctx.accounts.offer_authority,
+            authority_seeds,
+        )?;
+
+        emit!(OfferCancelled {
+            offer: offer.key(),
+            maker: offer.maker,
+            side: offer.side,
+            token_amount: offer.token_amount,
+        });
+
+        Ok(())
+    }
+
+    /// Token-2022 transfer hook for a CT mint created by `initialize_with_transfer_hook`.
+    /// Both sides of the transfer must be owned by a verified, non-suspended industry or an
+    /// accredited trader.
+    #[instruction(discriminator = ExecuteInstruction::SPL_DISCRIMINATOR_SLICE)]
+    pub fn transfer_hook(ctx: Context<TransferHook>, _amount: u64) -> Result<()> {
+        // Token-2022 flags the source account for the duration of the hook; anyone else
+        // calling in directly would find it cleared
+        let source = ctx.accounts.source_token.to_account_info();
+        let source_data = source.try_borrow_data()?;
+        let source_state = StateWithExtensions::<SplTokenAccount>::unpack(&source_data)?;
+        let transferring = source_state.get_extension::<TransferHookAccount>()?.transferring;
+        require!(bool::from(transferring), CarbonError::NotTransferring);
+
+        require!(
+            is_permitted_holder(
+                &ctx.accounts.source_token.owner,
+                &ctx.accounts.source_industry,
+                &ctx.accounts.source_trader,
+            )?,
+            CarbonError::TransferNotPermitted
+        );
+        require!(
+            is_permitted_holder(
+                &ctx.accounts.destination_token.owner,
+                &ctx.accounts.destination_industry,
+                &ctx.accounts.destination_trader,
+            )?,
+            CarbonError::TransferNotPermitted
+        );
+
+        Ok(())
+    }
+}
+
+fn open_config(
+    config: &mut Account<ProgramConfig>,
+    admin: Pubkey,
+    ct_mint: Pubkey,
+    ct_token_program: Pubkey,
+    treasury: Pubkey,
+    bump: u8,
+) {
+    config.admin = admin;
+    config.pending_admin = None;
+    config.ct_mint = ct_mint;
+    config.ct_token_program = ct_token_program;
+    config.treasury = treasury;
+    config.compliance_period = 1;
+    config.period_deadline = 0;
+    config.penalty_per_tonne = 0;
+    config.banking_periods = None;
+    config.exit_ct_policy = ExitCtPolicy::Burn;
+    config.bump = bump;
+
+    emit!(ProgramInitialized {
+        config: config.key(),
+        admin,
+        ct_mint,
+        ct_token_program,
+        treasury,
+    });
+}
+
+/// Extra accounts Token-2022 resolves for `transfer_hook`: the industry record and the
+/// trader accreditation of each side, derived from the owner field (bytes 32..64) of the
+/// source (account 0) and destination (account 2) token accounts.
+fn transfer_hook_account_metas() -> Result<Vec<ExtraAccountMeta>> {
+    let owner_seed = |account_index: u8| Seed::AccountData { account_index, data_index: 32, length: 32 };
+    let pda = |prefix: &[u8], account_index: u8| {
+        ExtraAccountMeta::new_with_seeds(
+            &[Seed::Literal { bytes: prefix.to_vec() }, owner_seed(account_index)],
+            false,
+            false,
+        )
+    };
+
+    Ok(vec![
+        pda(b"industry", 0)?,
+        pda(b"industry", 2)?,
+        pda(b"trader", 0)?,
+        pda(b"trader", 2)?,
+    ])
+}
+
+/// Whether a token account owner whose industry and trader PDAs are given may hold CT.
+/// CT escrowed in open offers is held by the program itself.
+fn is_permitted_holder(owner: &Pubkey, industry: &AccountInfo, trader: &AccountInfo) -> Result<bool> {
+    if *owner == Pubkey::find_program_address(&[b"offer_authority"], &crate::ID).0 {
+        return Ok(true);
+    }
+
+    if industry.owner == &crate::ID && !industry.data_is_empty() {
+        let industry = IndustryAccount::try_deserialize(&mut &industry.try_borrow_data()?[..])?;
+        if industry.verified && !industry.suspended {
+            return Ok(true);
+        }
+    }
+
+    Ok(trader.owner == &crate::ID && !trader.data_is_empty())
+}
+
+/// Moves CT with `transfer_checked`, forwarding `extra_accounts` so that a Token-2022
+/// mint's transfer hook can resolve the accounts it needs.
+#[allow(clippy::too_many_arguments)]
+fn transfer_ct<'info>(
+    token_program: &Interface<'info, TokenInterface>,
+    from: AccountInfo<'info>,
+    mint: &InterfaceAccount<'info, Mint>,
+    to: AccountInfo<'info>,
+    authority: AccountInfo<'info>,
+    extra_accounts: &[AccountInfo<'info>],
+    amount: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    spl_token_2022::onchain::invoke_transfer_checked(
+        token_program.key,
+        from,
+        mint.to_account_info(),
+        to,
+        authority,
+        extra_accounts,
+        amount,
+        mint.decimals,
+        signer_seeds,
+    )?;
+
+    Ok(())
+}
+
+/// The only CT account the program tracks for `owner`: its associated token account for the
+/// configured mint, so `ct_balance` cannot be split across several accounts.
+fn ct_account_address(owner: &Pubkey, ct_mint: &InterfaceAccount<Mint>, token_program: &Interface<TokenInterface>) -> Pubkey {
+    get_associated_token_address_with_program_id(owner, &ct_mint.key(), &token_program.key())
+}
+
+/// Settles a placed or revealed bid against its bidder's position, closing the position to the
+/// bidder with its last bid so a later auction for the same batch starts from nothing.
+/// Unrevealed commitments were never counted and leave the position alone.
+fn settle_bidder_position<'info>(
+    position: &mut Option<Account<'info, BidderPosition>>,
+    bid: &Bid,
+    bidder: AccountInfo<'info>,
+) -> Result<()> {
+    if bid.status != BidStatus::Pending {
+        return Ok(());
+    }
+    let Some(position) = position else {
+        return err!(CarbonError::BidderPositionRequired);
+    };
+
+    position.open_bids = position.open_bids.saturating_sub(1);
+    if position.open_bids == 0 {
+        position.close(bidder)?;
+    }
+    Ok(())
+}
+
+fn close_offer_escrow<'info>(
+    token_program: &Interface<'info, TokenInterface>,
+    offer_escrow: &InterfaceAccount<'info, TokenAccount>,
+    rent_recipient: AccountInfo<'info>,
+    offer_authority: &UncheckedAccount<'info>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    token_interface::close_account(CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        CloseAccount {
+            account: offer_escrow.to_account_info(),
+            destination: rent_recipient,
+            authority: offer_authority.to_account_info(),
+        },
+        signer_seeds,
+    ))
+}
+
+/// Hash an industry commits to when bidding in a sealed-bid auction. The bidder is part of
+/// the preimage so one industry cannot copy another's commitment and reveal it as its own.
+pub fn sealed_bid_commitment(bidder: &Pubkey, token_amount: u64, price_per_token: u64, salt: &[u8; 32]) -> [u8; 32] {
+    anchor_lang::solana_program::hash::hashv(&[
+        bidder.as_ref(),
+        &token_amount.to_le_bytes(),
+        &price_per_token.to_le_bytes(),
+        salt,
+    ])
+    .to_bytes()
+}
+
+/// Allocates a sealed-bid auction's supply to its revealed bids, highest price first, and
+/// returns the uniform clearing price. As with Dutch auctions, a sold-out auction clears at
+/// the lowest price that was allocated tokens and an undersubscribed one at the reserve.
+/// Bids tied at the margin share what is left pro rata.
+fn clear_sealed_bids<'info>(auction: &mut Account<'info, Auction>, bid_infos: &'info [AccountInfo<'info>]) -> Result<u64> {
+    require!(bid_infos.len() == auction.revealed_count as usize, CarbonError::IncompleteBidSet);
+
+    let mut bids: Vec<Account<'info, Bid>> = Vec::with_capacity(bid_infos.len());
+    for info in bid_infos {
+        let bid = Account::<Bid>::try_from(info)?;
+        require!(
+            info.is_writable
+                && bid.auction == auction.key()
+                && bid.status == BidStatus::Pending
+                && bids.iter().all(|other| other.key() != bid.key()),
+            CarbonError::IncompleteBidSet
+        );
+        bids.push(bid);
+    }
+    bids.sort_by_key(|bid| std::cmp::Reverse(bid.price_per_token));
+
+    let mut supply = auction.total_tokens;
+    let mut tokens_sold = 0u64;
+    let mut clearing_price = auction.reserve_price;
+    let mut tier_start = 0;
+    while tier_start < bids.len() {
+        let price = bids[tier_start].price_per_token;
+        let tier_end = tier_start + bids[tier_start..].iter().take_while(|bid| bid.price_per_token == price).count();
+        let tier = &mut bids[tier_start..tier_end];
+        let demand = tier
+            .iter()
+            .try_fold(0u64, |total, bid| total.checked_add(bid.token_amount))
+            .ok_or(CarbonError::MathOverflow)?;
+
+        if demand < supply {
+            supply -= demand;
+            tokens_sold += demand;
+        } else {
+            // This tier exhausts the supply and sets the price; rounding dust stays unsold
+            if supply > 0 {
+                clearing_price = price;
+            }
+            for bid in tier.iter_mut() {
+                bid.token_amount = (bid.token_amount as u128 * supply as u128 / demand as u128) as u64;
+                tokens_sold += bid.token_amount;
+            }
+            supply = 0;
+        }
+        tier_start = tier_end;
+    }
+
+    for bid in &bids {
+        bid.exit(&crate::ID)?;
+    }
+
+    auction.tokens_remaining = auction.total_tokens - tokens_sold;
+    auction.total_raised = ((tokens_sold as u128)
+        .checked_mul(clearing_price as u128)
+        .ok_or(CarbonError::MathOverflow)?
+        / 1_000_000_000) as u64;
+
+    Ok(clearing_price)
+}
+
+/// Pays `amount` out of an auction's escrow PDA. The escrow must either stay rent exempt or be
+/// emptied completely, as happens when the last bid of a cancelled auction is refunded.
+fn pay_from_escrow<'info>(
+    system_program: &Program<'info, System>,
+    escrow: &SystemAccount<'info>,
+    recipient: AccountInfo<'info>,
+    auction: Pubkey,
+    escrow_bump: u8,
+    amount: u64,
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+
+    let rent_exempt = Rent::get()?.minimum_balance(escrow.data_len());
+    let remaining_balance = escrow.lamports()
+        .checked_sub(amount)
+        .ok_or(CarbonError::InsufficientFunds)?;
+
+    require!(remaining_balance == 0 || remaining_balance >= rent_exempt, CarbonError::InsufficientFunds);
+
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"escrow",
+        auction.as_ref(),
+        &[escrow_bump],
+    ]];
+
+    anchor_lang::system_program::transfer(
+        CpiContext::new_with_signer(
+            system_program.to_account_info(),
+            anchor_lang::system_program::Transfer {
+                from: escrow.to_account_info(),
+                to: recipient,
+            },
+            signer_seeds,
+        ),
+        amount,
+    )
+}
+
+/// Token accounts a payment for an auction denominated in an SPL payment mint moves between:
+/// the auction's escrow token account and the bidder's or treasury's account on the other side.
+struct TokenPayment<'a, 'info> {
+    mint: &'a InterfaceAccount<'info, Mint>,
+    escrow_token: &'a InterfaceAccount<'info, TokenAccount>,
+    counterparty: &'a InterfaceAccount<'info, TokenAccount>,
+    token_program: &'a Interface<'info, TokenInterface>,
+}
+
+impl<'a, 'info> TokenPayment<'a, 'info> {
+    /// Checks the optional payment accounts an instruction was given against how `auction` is
+    /// denominated. Auctions paid in lamports take none of them and resolve to `None`.
+    fn resolve(
+        auction: &Auction,
+        mint: &'a Option<InterfaceAccount<'info, Mint>>,
+        escrow_token: &'a Option<InterfaceAccount<'info, TokenAccount>>,
+        counterparty: &'a Option<InterfaceAccount<'info, TokenAccount>>,
+        token_program: &'a Option<Interface<'info, TokenInterface>>,
+    ) -> Result<Option<Self>> {
+        match (auction.payment_mint, mint, escrow_token, counterparty, token_program) {
+            (None, None, None, None, None) => Ok(None),
+            (Some(payment_mint), Some(mint), Some(escrow_token), Some(counterparty), Some(token_program)) => {
+                require_keys_eq!(mint.key(), payment_mint, CarbonError::InvalidPaymentMint);
+                Ok(Some(Self { mint, escrow_token, counterparty, token_program }))
+            }
+            _ => err!(CarbonError::InvalidPaymentMint),
+        }
+    }
+
+    /// Moves `amount` from the counterparty into the escrow, signed by the counterparty's owner.
+    fn collect(&self, owner: AccountInfo<'info>, amount: u64) -> Result<()> {
+        token_interface::transfer_checked(
+            CpiContext::new(
+                self.token_program.to_account_info(),
+                TransferChecked {
+                    from: self.counterparty.to_account_info(),
+                    mint: self.mint.to_account_info(),
+                    to: self.escrow_token.to_account_info(),
+                    authority: owner,
+                },
+            ),
+            amount,
+            self.mint.decimals,
+        )
+    }
+
+    /// Pays `amount` out of the escrow to the counterparty, signed by the auction's escrow PDA.
+    fn pay(&self, escrow: &SystemAccount<'info>, auction: Pubkey, escrow_bump: u8, amount: u64) -> Result<()> {
+        if amount == 0 {
+            return Ok(());
+        }
+
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                TransferChecked {
+                    from: self.escrow_token.to_account_info(),
+                    mint: self.mint.to_account_info(),
+                    to: self.counterparty.to_account_info(),
+                    authority: escrow.to_account_info(),
+                },
+                &[&[b"escrow", auction.as_ref(), &[escrow_bump]]],
+            ),
+            amount,
+            self.mint.decimals,
+        )
+    }
+}
+
+#[derive(Accounts)]
+pub struct InitializeMint<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + ProgramConfig::INIT_SPACE,
+        seeds = [b"config"],
+        bump,
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(
+        init,
+        payer = authority,
+        mint::decimals = 9,
+        mint::authority = mint_authority,
+        mint::token_program = token_program,
+    )]
+    pub ct_mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: PDA authority for minting
+    #[account(
+        seeds = [b"mint_authority"],
+        bump,
+    )]
+    pub mint_authority: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub rent: Sysvar<'info, Rent>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeHookedMint<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + ProgramConfig::INIT_SPACE,
+        seeds = [b"config"],
+        bump,
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(
+        init,
+        payer = authority,
+        mint::decimals = 9,
+        mint::authority = mint_authority,
+        mint::token_program = token_program,
+        extensions::transfer_hook::authority = mint_authority,
+        extensions::transfer_hook::program_id = crate::ID,
+    )]
+    pub ct_mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: Validation account Token-2022 reads to resolve the hook's extra accounts
+    #[account(
+        init,
+        payer = authority,
+        space = ExtraAccountMetaList::size_of(transfer_hook_account_metas()?.len())?,
+        seeds = [b"extra-account-metas", ct_mint.key().as_ref()],
+        bump,
+    )]
+    pub extra_account_meta_list: UncheckedAccount<'info>,
+
+    /// CHECK: PDA authority for minting
+    #[account(
+        seeds = [b"mint_authority"],
+        bump,
+    )]
+    pub mint_authority: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token2022>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ProposeAdmin<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ CarbonError::Unauthorized,
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    pub new_admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateComplianceTerms<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ CarbonError::Unauthorized,
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(compliance_period: u32)]
+pub struct SetCapSchedule<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ CarbonError::Unauthorized,
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = 8 + CapSchedule::INIT_SPACE,
+        seeds = [b"cap_schedule", compliance_period.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub cap_schedule: Account<'info, CapSchedule>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetReserveConfig<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ CarbonError::Unauthorized,
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = 8 + ReserveConfig::INIT_SPACE,
+        seeds = [b"reserve_config"],
+        bump,
+    )]
+    pub reserve_config: Account<'info, ReserveConfig>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(role: Role, holder: Pubkey)]
+pub struct GrantRole<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ CarbonError::Unauthorized,
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + RoleAssignment::INIT_SPACE,
+        seeds = [b"role", [role as u8].as_ref(), holder.as_ref()],
+        bump,
+    )]
+    pub role_assignment: Account<'info, RoleAssignment>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeRole<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ CarbonError::Unauthorized,
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(
+        mut,
+        close = admin,
+        seeds = [b"role", [role_assignment.role as u8].as_ref(), role_assignment.holder.as_ref()],
+        bump = role_assignment.bump,
+    )]
+    pub role_assignment: Account<'info, RoleAssignment>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RegisterIndustry<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + IndustryAccount::INIT_SPACE,
+        seeds = [b"industry", authority.key().as_ref()],
+        bump,
+    )]
+    pub industry_account: Account<'info, IndustryAccount>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateIndustryProfile<'info> {
+    #[account(
+        mut,
+        seeds = [b"industry", authority.key().as_ref()],
+        bump = industry_account.bump,
+    )]
+    pub industry_account: Account<'info, IndustryAccount>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ProposeIndustryAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [b"industry", authority.key().as_ref()],
+        bump = industry_account.bump,
+        constraint = industry_account.pending_authority.is_none() @ CarbonError::AuthorityTransferPending,
+    )]
+    pub industry_account: Account<'info, IndustryAccount>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = ct_mint @ CarbonError::InvalidMint,
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(mint::token_program = token_program)]
+    pub ct_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        address = ct_account_address(&authority.key(), &ct_mint, &token_program) @ CarbonError::InvalidTokenAccount,
+    )]
+    pub industry_ct_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        token::mint = ct_mint,
+        token::authority = offer_authority,
+        token::token_program = token_program,
+        seeds = [b"transfer_escrow", industry_account.key().as_ref()],
+        bump,
+    )]
+    pub transfer_escrow: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: PDA that owns CT held in escrow, here the industry's CT awaiting its new authority
+    #[account(
+        seeds = [b"offer_authority"],
+        bump,
+    )]
+    pub offer_authority: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelIndustryAuthorityTransfer<'info> {
+    #[account(
+        mut,
+        seeds = [b"industry", authority.key().as_ref()],
+        bump = industry_account.bump,
+    )]
+    pub industry_account: Account<'info, IndustryAccount>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = ct_mint @ CarbonError::InvalidMint,
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(mint::token_program = token_program)]
+    pub ct_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        address = ct_account_address(&authority.key(), &ct_mint, &token_program) @ CarbonError::InvalidTokenAccount,
+    )]
+    pub industry_ct_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"transfer_escrow", industry_account.key().as_ref()],
+        bump,
+    )]
+    pub transfer_escrow: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: PDA that owns CT held in escrow, here the industry's CT awaiting its new authority
+    #[account(
+        seeds = [b"offer_authority"],
+        bump,
+    )]
+    pub offer_authority: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptIndustryAuthority<'info> {
+    #[account(
+        mut,
+        close = new_authority,
+        seeds = [b"industry", previous_industry.authority.as_ref()],
+        bump = previous_industry.bump,
+    )]
+    pub previous_industry: Account<'info, IndustryAccount>,
+
+    /// CHECK: Bond vault of the previous record, emptied into the new one
+    #[account(
+        mut,
+        seeds = [b"bond_vault", previous_industry.key().as_ref()],
+        bump,
+    )]
+    pub previous_bond_vault: SystemAccount<'info>,
+
+    #[account(
+        init,
+        payer = new_authority,
+        space = 8 + IndustryAccount::INIT_SPACE,
+        seeds = [b"industry", new_authority.key().as_ref()],
+        bump,
+    )]
+    pub industry_account: Account<'info, IndustryAccount>,
+
+    /// CHECK: Bond vault of the new record
+    #[account(
+        mut,
+        seeds = [b"bond_vault", industry_account.key().as_ref()],
+        bump,
+    )]
+    pub bond_vault: SystemAccount<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = ct_mint @ CarbonError::InvalidMint,
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(mint::token_program = token_program)]
+    pub ct_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"transfer_escrow", previous_industry.key().as_ref()],
+        bump,
+    )]
+    pub transfer_escrow: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        address = ct_account_address(&new_authority.key(), &ct_mint, &token_program) @ CarbonError::InvalidTokenAccount,
+    )]
+    pub industry_ct_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: PDA that owns CT held in escrow, here the industry's CT awaiting its new authority
+    #[account(
+        seeds = [b"offer_authority"],
+        bump,
+    )]
+    pub offer_authority: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub new_authority: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct DeregisterIndustry<'info> {
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"industry", authority.key().as_ref()],
+        bump = industry_account.bump,
+    )]
+    pub industry_account: Account<'info, IndustryAccount>,
+
+    /// CHECK: Bond vault - system account holding SOL, emptied back to the authority
+    #[account(
+        mut,
+        seeds = [b"bond_vault", industry_account.key().as_ref()],
+        bump,
+    )]
+    pub bond_vault: SystemAccount<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + IndustryTombstone::INIT_SPACE,
+        seeds = [
+            b"tombstone",
+            industry_account.key().as_ref(),
+            industry_account.created_at.to_le_bytes().as_ref()
+        ],
+        bump,
+    )]
+    pub tombstone: Account<'info, IndustryTombstone>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = ct_mint @ CarbonError::InvalidMint,
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(
+        mut,
+        mint::token_program = token_program,
+    )]
+    pub ct_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        address = ct_account_address(&authority.key(), &ct_mint, &token_program) @ CarbonError::InvalidTokenAccount,
+    )]
+    pub industry_ct_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Treasury's CT account; only needed when leftover CT is surrendered rather than burned
+    #[account(
+        mut,
+        address = ct_account_address(&config.treasury, &ct_mint, &token_program) @ CarbonError::InvalidTokenAccount,
+    )]
+    pub treasury_ct_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetIndustrySigners<'info> {
+    #[account(
+        mut,
+        seeds = [b"industry", authority.key().as_ref()],
+        bump = industry_account.bump,
+    )]
+    pub industry_account: Account<'info, IndustryAccount>,
+
+    /// Approved `UpdateSigners` proposal; required once the industry has a signer set
+    #[account(
+        mut,
+        close = authority,
+        constraint = proposal.industry == industry_account.key() @ CarbonError::InvalidProposal,
+    )]
+    pub proposal: Option<Account<'info, IndustryProposal>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(proposal_id: u64)]
+pub struct ProposeIndustryAction<'info> {
+    #[account(
+        seeds = [b"industry", industry_account.authority.as_ref()],
+        bump = industry_account.bump,
+    )]
+    pub industry_account: Account<'info, IndustryAccount>,
+
+    #[account(
+        init,
+        payer = proposer,
+        space = 8 + IndustryProposal::INIT_SPACE,
+        seeds = [b"industry_proposal", industry_account.key().as_ref(), proposal_id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub proposal: Account<'info, IndustryProposal>,
+
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ApproveIndustryAction<'info> {
+    #[account(
+        seeds = [b"industry", industry_account.authority.as_ref()],
+        bump = industry_account.bump,
+    )]
+    pub industry_account: Account<'info, IndustryAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"industry_proposal", industry_account.key().as_ref(), proposal.proposal_id.to_le_bytes().as_ref()],
+        bump = proposal.bump,
+    )]
+    pub proposal: Account<'info, IndustryProposal>,
+
+    pub signer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CancelIndustryAction<'info> {
+    #[account(
+        mut,
+        close = proposer,
+        has_one = proposer @ CarbonError::Unauthorized,
+        seeds = [b"industry_proposal", proposal.industry.as_ref(), proposal.proposal_id.to_le_bytes().as_ref()],
+        bump = proposal.bump,
+    )]
+    pub proposal: Account<'info, IndustryProposal>,
+
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateVintageHolding<'info> {
+    #[account(
+        mut,
+        seeds = [b"industry", authority.key().as_ref()],
+        bump = industry_account.bump,
+    )]
+    pub industry_account: Account<'info, IndustryAccount>,
+
+    #[account(
+        mut,
+        close = authority,
+        constraint = industry_account.migrated_from == Some(previous_holding.industry) @ CarbonError::InvalidVintageHolding,
+        seeds = [b"vintage", previous_holding.industry.as_ref(), previous_holding.vintage.to_le_bytes().as_ref()],
+        bump = previous_holding.bump,
+    )]
+    pub previous_holding: Account<'info, VintageHolding>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + VintageHolding::INIT_SPACE,
+        seeds = [b"vintage", industry_account.key().as_ref(), previous_holding.vintage.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub vintage_holding: Account<'info, VintageHolding>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct VerifyIndustry<'info> {
+    #[account(
+        seeds = [b"role", [Role::Verifier as u8].as_ref(), verifier.key().as_ref()],
+        bump = role_assignment.bump,
+    )]
+    pub role_assignment: Account<'info, RoleAssignment>,
+
+    pub verifier: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"industry", industry_account.authority.as_ref()],
+        bump = industry_account.bump,
+    )]
+    pub industry_account: Account<'info, IndustryAccount>,
+}
+
+#[derive(Accounts)]
+pub struct DepositBond<'info> {
+    #[account(
+        mut,
+        seeds = [b"industry", authority.key().as_ref()],
+        bump = industry_account.bump,
+    )]
+    pub industry_account: Account<'info, IndustryAccount>,
+    
+    /// CHECK: Bond vault - system account to hold SOL
+    #[account(
+        mut,
+        seeds = [b"bond_vault", industry_account.key().as_ref()],
+        bump,
+    )]
+    pub bond_vault: SystemAccount<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct BurnCT<'info> {
+    #[account(
+        mut,
+        seeds = [b"industry", authority.key().as_ref()],
+        bump = industry_account.bump,
+        constraint = industry_account.authority == authority.key() 
+    )]
+    pub industry_account: Account<'info, IndustryAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"vintage", industry_account.key().as_ref(), vintage_holding.vintage.to_le_bytes().as_ref()],
+        bump = vintage_holding.bump,
+    )]
+    pub vintage_holding: Account<'info, VintageHolding>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = ct_mint @ CarbonError::InvalidMint,
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(
+        mut,
+        mint::token_program = token_program,
+    )]
+    pub ct_mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        mut,
+        address = ct_account_address(&authority.key(), &ct_mint, &token_program) @ CarbonError::InvalidTokenAccount,
+    )]
+    pub industry_ct_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Approved proposal authorising this action; required once the industry has a signer set
+    #[account(
+        mut,
+        close = authority,
+        constraint = proposal.industry == industry_account.key() @ CarbonError::InvalidProposal,
+    )]
+    pub proposal: Option<Account<'info, IndustryProposal>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+#[instruction(co2_emitted: u64, report_period: String, timestamp: u64)]
+pub struct SubmitEmissionReport<'info> {
+    #[account(
+        mut,
+        seeds = [b"industry", authority.key().as_ref()],
+        bump = industry_account.bump,
+    )]
+    pub industry_account: Account<'info, IndustryAccount>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + EmissionReport::INIT_SPACE,
+        seeds = [b"emission_report", industry_account.key().as_ref(), timestamp.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub emission_report: Account<'info, EmissionReport>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ReviewEmissionReport<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(
+        seeds = [b"role", [Role::Verifier as u8].as_ref(), verifier.key().as_ref()],
+        bump = role_assignment.bump,
+    )]
+    pub role_assignment: Account<'info, RoleAssignment>,
+
+    pub verifier: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = emission_report.industry == industry_account.key() @ CarbonError::InvalidEmissionReport,
+    )]
+    pub emission_report: Account<'info, EmissionReport>,
+
+    #[account(
+        mut,
+        seeds = [b"industry", industry_account.authority.as_ref()],
+        bump = industry_account.bump,
+    )]
+    pub industry_account: Account<'info, IndustryAccount>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawBond<'info> {
+    #[account(
+        mut,
+        seeds = [b"industry", authority.key().as_ref()],
+        bump = industry_account.bump,
+        constraint = industry_account.authority == authority.key()
+    )]
+    pub industry_account: Account<'info, IndustryAccount>,
+
+    /// CHECK: Bond vault - system account holding SOL
+    #[account(
+        mut,
+        seeds = [b"bond_vault", industry_account.key().as_ref()],
+        bump = industry_account.bond_vault_bump,
+    )]
+    pub bond_vault: SystemAccount<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    /// Approved proposal authorising this action; required once the industry has a signer set
+    #[account(
+        mut,
+        close = authority,
+        constraint = proposal.industry == industry_account.key() @ CarbonError::InvalidProposal,
+    )]
+    pub proposal: Option<Account<'info, IndustryProposal>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SlashBond<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(
+        seeds = [b"role", [Role::Regulator as u8].as_ref(), regulator.key().as_ref()],
+        bump = role_assignment.bump,
+    )]
+    pub role_assignment: Account<'info, RoleAssignment>,
+
+    pub regulator: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"industry", industry_account.authority.as_ref()],
+        bump = industry_account.bump,
+    )]
+    pub industry_account: Account<'info, IndustryAccount>,
+
+    /// CHECK: Bond vault - system account holding SOL
+    #[account(
+        mut,
+        seeds = [b"bond_vault", industry_account.key().as_ref()],
+        bump = industry_account.bond_vault_bump,
+    )]
+    pub bond_vault: SystemAccount<'info>,
+
+    /// CHECK: Treasury account to receive the penalty
+    #[account(
+        mut,
+        address = config.treasury @ CarbonError::InvalidTreasury,
+    )]
+    pub treasury: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SettlePeriod<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(
+        seeds = [b"role", [Role::Regulator as u8].as_ref(), regulator.key().as_ref()],
+        bump = role_assignment.bump,
+    )]
+    pub role_assignment: Account<'info, RoleAssignment>,
+
+    pub regulator: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"industry", industry_account.authority.as_ref()],
+        bump = industry_account.bump,
+    )]
+    pub industry_account: Account<'info, IndustryAccount>,
+
+    /// CHECK: Bond vault - system account holding SOL
+    #[account(
+        mut,
+        seeds = [b"bond_vault", industry_account.key().as_ref()],
+        bump = industry_account.bond_vault_bump,
+    )]
+    pub bond_vault: SystemAccount<'info>,
+
+    /// CHECK: Treasury account to receive the penalty
+    #[account(
+        mut,
+        address = config.treasury @ CarbonError::InvalidTreasury,
+    )]
+    pub treasury: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(batch_number: u32)]
+pub struct CreateAuction<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Auction::INIT_SPACE,
+        seeds = [b"auction", batch_number.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub auction: Account<'info, Auction>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"cap_schedule", config.compliance_period.to_le_bytes().as_ref()],
+        bump = cap_schedule.bump,
+    )]
+    pub cap_schedule: Account<'info, CapSchedule>,
+
+    #[account(
+        seeds = [b"role", [Role::AuctionOperator as u8].as_ref(), authority.key().as_ref()],
+        bump = role_assignment.bump,
+    )]
+    pub role_assignment: Account<'info, RoleAssignment>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetBidderCap<'info> {
+    #[account(
+        mut,
+        seeds = [b"auction", &auction.batch_number.to_le_bytes()],
+        bump,
+    )]
+    pub auction: Account<'info, Auction>,
+
+    #[account(
+        seeds = [b"role", [Role::AuctionOperator as u8].as_ref(), authority.key().as_ref()],
+        bump = role_assignment.bump,
+    )]
+    pub role_assignment: Account<'info, RoleAssignment>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetPriceCurve<'info> {
+    #[account(
+        mut,
+        seeds = [b"auction", &auction.batch_number.to_le_bytes()],
+        bump,
+    )]
+    pub auction: Account<'info, Auction>,
+
+    #[account(
+        seeds = [b"role", [Role::AuctionOperator as u8].as_ref(), authority.key().as_ref()],
+        bump = role_assignment.bump,
+    )]
+    pub role_assignment: Account<'info, RoleAssignment>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetPaymentMint<'info> {
+    #[account(
+        mut,
+        seeds = [b"auction", &auction.batch_number.to_le_bytes()],
+        bump,
+    )]
+    pub auction: Account<'info, Auction>,
+
+    #[account(mint::token_program = token_program)]
+    pub payment_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = authority,
+        token::mint = payment_mint,
+        token::authority = escrow,
+        token::token_program = token_program,
+        seeds = [b"escrow_token", auction.key().as_ref()],
+        bump,
+    )]
+    pub escrow_token: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Escrow PDA that owns the escrow token account
+    #[account(
+        seeds = [b"escrow", auction.key().as_ref()],
+        bump,
+    )]
+    pub escrow: SystemAccount<'info>,
+
+    #[account(
+        seeds = [b"role", [Role::AuctionOperator as u8].as_ref(), authority.key().as_ref()],
+        bump = role_assignment.bump,
+    )]
+    pub role_assignment: Account<'info, RoleAssignment>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(token_amount: u64, timestamp: u64)]
+pub struct PlaceBid<'info> {
+    #[account(
+        mut,
+        seeds = [b"auction", &auction.batch_number.to_le_bytes()],
+        bump,
+    )]
+    pub auction: Account<'info, Auction>,
+
+    #[account(
+        init,
+        payer = bidder,
+        space = 8 + Bid::INIT_SPACE,
+        seeds = [
+            b"bid",
+            auction.key().as_ref(),
+            bidder.key().as_ref(),
+            timestamp.to_le_bytes().as_ref()
+        ],
+        bump,
+    )]
+    pub bid: Account<'info, Bid>,
+
+    #[account(
+        init_if_needed,
+        payer = bidder,
+        space = 8 + BidderPosition::INIT_SPACE,
+        seeds = [b"position", auction.key().as_ref(), bidder.key().as_ref()],
+        bump,
+    )]
+    pub position: Account<'info, BidderPosition>,
+
+    #[account(
+        mut,
+        seeds = [b"industry", bidder.key().as_ref()],
+        bump,
+    )]
+    pub industry_account: Account<'info, IndustryAccount>,
+
+    /// CHECK: Escrow account - PDA to hold SOL
+    #[account(
+        mut,
+        seeds = [b"escrow", auction.key().as_ref()],
+        bump,
+    )]
+    pub escrow: SystemAccount<'info>,
+
+    /// Payment mint of an auction denominated in one; omitted for auctions paid in lamports
+    pub payment_mint: Option<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [b"escrow_token", auction.key().as_ref()],
+        bump,
+    )]
+    pub escrow_token: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::authority = bidder,
+    )]
+    pub bidder_token: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub payment_token_program: Option<Interface<'info, TokenInterface>>,
+
+    /// Approved proposal authorising this action; required once the industry has a signer set
+    #[account(
+        mut,
+        close = bidder,
+        constraint = proposal.industry == industry_account.key() @ CarbonError::InvalidProposal,
+    )]
+    pub proposal: Option<Account<'info, IndustryProposal>>,
+
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(commitment: [u8; 32], collateral: u64, timestamp: u64)]
+pub struct CommitBid<'info> {
+    #[account(
+        mut,
+        seeds = [b"auction", &auction.batch_number.to_le_bytes()],
+        bump,
+    )]
+    pub auction: Account<'info, Auction>,
+
+    #[account(
+        init,
+        payer = bidder,
+        space = 8 + Bid::INIT_SPACE,
+        seeds = [
+            b"bid",
+            auction.key().as_ref(),
+            bidder.key().as_ref(),
+            timestamp.to_le_bytes().as_ref()
+        ],
+        bump,
+    )]
+    pub bid: Account<'info, Bid>,
+
+    #[account(
+        mut,
+        seeds = [b"industry", bidder.key().as_ref()],
+        bump = industry_account.bump,
+    )]
+    pub industry_account: Account<'info, IndustryAccount>,
+
+    /// CHECK: Escrow account - PDA to hold SOL
+    #[account(
+        mut,
+        seeds = [b"escrow", auction.key().as_ref()],
+        bump,
+    )]
+    pub escrow: SystemAccount<'info>,
+
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RevealBid<'info> {
+    #[account(
+        mut,
+        seeds = [b"auction", &auction.batch_number.to_le_bytes()],
+        bump,
+    )]
+    pub auction: Account<'info, Auction>,
+
+    #[account(
+        mut,
+        constraint = bid.auction == auction.key() @ CarbonError::InvalidBid,
+        constraint = bid.bidder == bidder.key() @ CarbonError::InvalidBid,
+    )]
+    pub bid: Account<'info, Bid>,
+
+    #[account(
+        seeds = [b"industry", bidder.key().as_ref()],
+        bump = industry_account.bump,
+    )]
+    pub industry_account: Account<'info, IndustryAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = bidder,
+        space = 8 + BidderPosition::INIT_SPACE,
+        seeds = [b"position", auction.key().as_ref(), bidder.key().as_ref()],
+        bump,
+    )]
+    pub position: Account<'info, BidderPosition>,
+
+    /// CHECK: Escrow account - PDA to hold SOL
+    #[account(
+        mut,
+        seeds = [b"escrow", auction.key().as_ref()],
+        bump,
+    )]
+    pub escrow: SystemAccount<'info>,
+
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FinalizeAuction<'info> {
+    #[account(
+        mut,
+        seeds = [b"auction", &auction.batch_number.to_le_bytes()],
+        bump,
+    )]
+    pub auction: Account<'info, Auction>,
+
+    #[account(
+        seeds = [b"role", [Role::AuctionOperator as u8].as_ref(), authority.key().as_ref()],
+        bump = role_assignment.bump,
+    )]
+    pub role_assignment: Account<'info, RoleAssignment>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimTokens<'info> {
+    #[account(
+        mut,
+        seeds = [b"auction", &auction.batch_number.to_le_bytes()],
+        bump,
+    )]
+    pub auction: Account<'info, Auction>,
+
+    #[account(
+        mut,
+        constraint = bid.bidder == bidder.key(),
+        constraint = bid.auction == auction.key() @ CarbonError::InvalidBid,
+    )]
+    pub bid: Account<'info, Bid>,
+
+    #[account(
+        mut,
+        seeds = [b"industry", bid.bidder.as_ref()],
+        bump,
+    )]
+    pub industry_account: Account<'info, IndustryAccount>,
+
+    /// Required for placed and revealed bids; closed to the bidder with its last open bid
+    #[account(
+        mut,
+        seeds = [b"position", auction.key().as_ref(), bid.bidder.as_ref()],
+        bump = position.bump,
+    )]
+    pub position: Option<Account<'info, BidderPosition>>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + VintageHolding::INIT_SPACE,
+        seeds = [b"vintage", industry_account.key().as_ref(), auction.vintage.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub vintage_holding: Account<'info, VintageHolding>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = ct_mint @ CarbonError::InvalidMint,
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(
+        mut,
+        mint::token_program = token_program,
+    )]
+    pub ct_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        address = ct_account_address(&industry_account.authority, &ct_mint, &token_program) @ CarbonError::InvalidTokenAccount,
+    )]
+    pub industry_ct_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Mint authority PDA
+    #[account(
+        seeds = [b"mint_authority"],
+        bump,
+    )]
+    pub mint_authority: UncheckedAccount<'info>,
+
+    /// CHECK: Escrow account - PDA holding SOL
+    #[account(
+        mut,
+        seeds = [b"escrow", auction.key().as_ref()],
+        bump,
+    )]
+    pub escrow: SystemAccount<'info>,
+
+    /// Payment mint of an auction denominated in one; omitted for auctions paid in lamports
+    pub payment_mint: Option<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [b"escrow_token", auction.key().as_ref()],
+        bump,
+    )]
+    pub escrow_token: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::authority = bidder,
+    )]
+    pub bidder_token: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub payment_token_program: Option<Interface<'info, TokenInterface>>,
+
+    /// Wallet that placed the bid; receives its refund and the rent of a closed position
+    #[account(mut)]
+    pub bidder: SystemAccount<'info>,
+
+    /// Auction operator role of `authority`; only needed when someone other than the bidder signs
+    #[account(
+        seeds = [b"role", [Role::AuctionOperator as u8].as_ref(), authority.key().as_ref()],
+        bump = role_assignment.bump,
+    )]
+    pub role_assignment: Option<Account<'info, RoleAssignment>>,
+
+    /// The bidder, or an auction operator settling a bid its bidder has left unclaimed
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ReleaseReserve<'info> {
+    #[account(
+        seeds = [b"auction", &auction.batch_number.to_le_bytes()],
+        bump,
+    )]
+    pub auction: Account<'info, Auction>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + ReserveSale::INIT_SPACE,
+        seeds = [b"reserve_sale", auction.key().as_ref()],
+        bump,
+    )]
+    pub reserve_sale: Account<'info, ReserveSale>,
+
+    #[account(
+        seeds = [b"reserve_config"],
+        bump = reserve_config.bump,
+    )]
+    pub reserve_config: Account<'info, ReserveConfig>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"cap_schedule", auction.vintage.to_le_bytes().as_ref()],
+        bump = cap_schedule.bump,
+    )]
+    pub cap_schedule: Account<'info, CapSchedule>,
+
+    #[account(
+        seeds = [b"role", [Role::AuctionOperator as u8].as_ref(), authority.key().as_ref()],
+        bump = role_assignment.bump,
+    )]
+    pub role_assignment: Account<'info, RoleAssignment>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct BuyReserveAllowances<'info> {
+    #[account(
+        mut,
+        seeds = [b"reserve_sale", reserve_sale.trigger_auction.as_ref()],
+        bump = reserve_sale.bump,
+    )]
+    pub reserve_sale: Account<'info, ReserveSale>,
+
+    #[account(
+        mut,
+        seeds = [b"industry", buyer.key().as_ref()],
+        bump = industry_account.bump,
+    )]
+    pub industry_account: Account<'info, IndustryAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        space = 8 + VintageHolding::INIT_SPACE,
+        seeds = [b"vintage", industry_account.key().as_ref(), reserve_sale.vintage.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub vintage_holding: Account<'info, VintageHolding>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = ct_mint @ CarbonError::InvalidMint,
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(
+        mut,
+        mint::token_program = token_program,
+    )]
+    pub ct_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        address = ct_account_address(&buyer.key(), &ct_mint, &token_program) @ CarbonError::InvalidTokenAccount,
+    )]
+    pub industry_ct_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Mint authority PDA
+    #[account(
+        seeds = [b"mint_authority"],
+        bump,
+    )]
+    pub mint_authority: UncheckedAccount<'info>,
+
+    /// CHECK: Treasury account to receive the sale proceeds
+    #[account(
+        mut,
+        address = config.treasury @ CarbonError::InvalidTreasury,
+    )]
+    pub treasury: SystemAccount<'info>,
+
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelAuction<'info> {
+    #[account(
+        mut,
+        seeds = [b"auction", &auction.batch_number.to_le_bytes()],
+        bump,
+    )]
+    pub auction: Account<'info, Auction>,
+
+    #[account(
+        mut,
+        seeds = [b"cap_schedule", auction.vintage.to_le_bytes().as_ref()],
+        bump = cap_schedule.bump,
+    )]
+    pub cap_schedule: Account<'info, CapSchedule>,
+
+    #[account(
+        seeds = [b"role", [Role::AuctionOperator as u8].as_ref(), authority.key().as_ref()],
+        bump = role_assignment.bump,
+    )]
+    pub role_assignment: Account<'info, RoleAssignment>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RefundBid<'info> {
+    #[account(
+        mut,
+        seeds = [b"auction", &auction.batch_number.to_le_bytes()],
+        bump,
+    )]
+    pub auction: Account<'info, Auction>,
+
+    #[account(
+        mut,
+        constraint = bid.auction == auction.key() @ CarbonError::InvalidBid,
+        constraint = bid.bidder == bidder.key() @ CarbonError::InvalidBid,
+    )]
+    pub bid: Account<'info, Bid>,
+
+    #[account(
+        mut,
+        seeds = [b"industry", bidder.key().as_ref()],
+        bump = industry_account.bump,
+    )]
+    pub industry_account: Account<'info, IndustryAccount>,
+
+    /// Required for placed and revealed bids; closed to the bidder with its last open bid
+    #[account(
+        mut,
+        seeds = [b"position", auction.key().as_ref(), bidder.key().as_ref()],
+        bump = position.bump,
+    )]
+    pub position: Option<Account<'info, BidderPosition>>,
+
+    /// CHECK: Escrow account - PDA holding SOL
+    #[account(
+        mut,
+        seeds = [b"escrow", auction.key().as_ref()],
+        bump,
+    )]
+    pub escrow: SystemAccount<'info>,
+
+    /// Payment mint of an auction denominated in one; omitted for auctions paid in lamports
+    pub payment_mint: Option<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [b"escrow_token", auction.key().as_ref()],
+        bump,
+    )]
+    pub escrow_token: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::authority = bidder,
+    )]
+    pub bidder_token: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub payment_token_program: Option<Interface<'info, TokenInterface>>,
+
+    /// Wallet that placed the bid; receives its refund and the rent of a closed position
+    #[account(mut)]
+    pub bidder: SystemAccount<'info>,
+
+    /// Auction operator role of `authority`; only needed when someone other than the bidder signs
+    #[account(
+        seeds = [b"role", [Role::AuctionOperator as u8].as_ref(), authority.key().as_ref()],
+        bump = role_assignment.bump,
+    )]
+    pub role_assignment: Option<Account<'info, RoleAssignment>>,
+
+    /// The bidder, or an auction operator refunding a bid its bidder has left behind
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawProceeds<'info> {
+    #[account(
+        seeds = [b"auction", &auction.batch_number.to_le_bytes()],
+        bump,
+    )]
+    pub auction: Account<'info, Auction>,
+
+    /// CHECK: Escrow account - PDA holding SOL
+    #[account(
+        mut,
+        seeds = [b"escrow", auction.key().as_ref()],
+        bump,
+    )]
+    pub escrow: SystemAccount<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(
+        seeds = [b"role", [Role::Treasurer as u8].as_ref(), authority.key().as_ref()],
+        bump = role_assignment.bump,
+    )]
+    pub role_assignment: Account<'info, RoleAssignment>,
+
+    /// CHECK: Treasury account to receive SOL
+    #[account(
+        mut,
+        address = config.treasury @ CarbonError::InvalidTreasury,
+    )]
+    pub treasury: SystemAccount<'info>,
+
+    /// Payment mint of an auction denominated in one; omitted for auctions paid in lamports
+    pub payment_mint: Option<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [b"escrow_token", auction.key().as_ref()],
+        bump,
+    )]
+    pub escrow_token: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = treasury_token.owner == config.treasury @ CarbonError::InvalidTreasury,
+    )]
+    pub treasury_token: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub payment_token_program: Option<Interface<'info, TokenInterface>>,
+
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AllocateFreeAllowances<'info> {
+    #[account(
+        seeds = [b"role", [Role::Regulator as u8].as_ref(), regulator.key().as_ref()],
+        bump = role_assignment.bump,
+    )]
+    pub role_assignment: Account<'info, RoleAssignment>,
+
+    #[account(mut)]
+    pub regulator: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"industry", industry_account.authority.as_ref()],
+        bump = industry_account.bump,
+    )]
+    pub industry_account: Account<'info, IndustryAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = regulator,
+        space = 8 + VintageHolding::INIT_SPACE,
+        seeds = [b"vintage", industry_account.key().as_ref(), config.compliance_period.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub vintage_holding: Account<'info, VintageHolding>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = ct_mint @ CarbonError::InvalidMint,
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"cap_schedule", config.compliance_period.to_le_bytes().as_ref()],
+        bump = cap_schedule.bump,
+    )]
+    pub cap_schedule: Account<'info, CapSchedule>,
+
+    #[account(
+        mut,
+        mint::token_program = token_program,
+    )]
+    pub ct_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        address = ct_account_address(&industry_account.authority, &ct_mint, &token_program) @ CarbonError::InvalidTokenAccount,
+    )]
+    pub industry_ct_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Mint authority PDA
+    #[account(
+        seeds = [b"mint_authority"],
+        bump,
+    )]
+    pub mint_authority: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(project_id: u64)]
+pub struct RegisterOffsetProject<'info> {
+    #[account(
+        init,
+        payer = developer,
+        space = 8 + OffsetProject::INIT_SPACE,
+        seeds = [b"offset_project", developer.key().as_ref(), project_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub offset_project: Account<'info, OffsetProject>,
+
+    #[account(mut)]
+    pub developer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ApproveOffsetProject<'info> {
+    #[account(
+        seeds = [b"role", [Role::Verifier as u8].as_ref(), verifier.key().as_ref()],
+        bump = role_assignment.bump,
+    )]
+    pub role_assignment: Account<'info, RoleAssignment>,
+
+    pub verifier: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"offset_project", offset_project.developer.as_ref(), offset_project.project_id.to_le_bytes().as_ref()],
+        bump = offset_project.bump,
+    )]
+    pub offset_project: Account<'info, OffsetProject>,
+}
+
+#[derive(Accounts)]
+#[instruction(co2_removed: u64, timestamp: u64)]
+pub struct SubmitRemovalReport<'info> {
+    #[account(
+        seeds = [b"offset_project", developer.key().as_ref(), offset_project.project_id.to_le_bytes().as_ref()],
+        bump = offset_project.bump,
+    )]
+    pub offset_project: Account<'info, OffsetProject>,
+
+    #[account(
+        seeds = [b"industry", beneficiary.authority.as_ref()],
+        bump = beneficiary.bump,
+    )]
+    pub beneficiary: Account<'info, IndustryAccount>,
+
+    #[account(
+        init,
+        payer = developer,
+        space = 8 + RemovalReport::INIT_SPACE,
+        seeds = [b"removal_report", offset_project.key().as_ref(), timestamp.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub removal_report: Account<'info, RemovalReport>,
+
+    #[account(mut)]
+    pub developer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct IssueOffsetCredits<'info> {
+    #[account(
+        seeds = [b"role", [Role::Verifier as u8].as_ref(), verifier.key().as_ref()],
+        bump = role_assignment.bump,
+    )]
+    pub role_assignment: Account<'info, RoleAssignment>,
+
+    pub verifier: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"offset_project", offset_project.developer.as_ref(), offset_project.project_id.to_le_bytes().as_ref()],
+        bump = offset_project.bump,
+    )]
+    pub offset_project: Account<'info, OffsetProject>,
+
+    #[account(
+        mut,
+        constraint = removal_report.project == offset_project.key() @ CarbonError::InvalidRemovalReport,
+        constraint = removal_report.beneficiary == industry_account.key() @ CarbonError::InvalidRemovalReport,
+    )]
+    pub removal_report: Account<'info, RemovalReport>,
+
+    #[account(
+        mut,
+        seeds = [b"industry", industry_account.authority.as_ref()],
+        bump = industry_account.bump,
+    )]
+    pub industry_account: Account<'info, IndustryAccount>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = ct_mint @ CarbonError::InvalidMint,
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(
+        mut,
+        mint::token_program = token_program,
+    )]
+    pub ct_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        address = ct_account_address(&industry_account.authority, &ct_mint, &token_program) @ CarbonError::InvalidTokenAccount,
+    )]
+    pub industry_ct_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Mint authority PDA
+    #[account(
+        seeds = [b"mint_authority"],
+        bump,
+    )]
+    pub mint_authority: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct SetOffsetLimit<'info> {
+    #[account(
+        seeds = [b"role", [Role::Regulator as u8].as_ref(), regulator.key().as_ref()],
+        bump = role_assignment.bump,
+    )]
+    pub role_assignment: Account<'info, RoleAssignment>,
+
+    pub regulator: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"industry", industry_account.authority.as_ref()],
+        bump = industry_account.bump,
+    )]
+    pub industry_account: Account<'info, IndustryAccount>,
+}
+
+#[derive(Accounts)]
+pub struct BurnOffsetCredits<'info> {
+    #[account(
+        mut,
+        seeds = [b"industry", authority.key().as_ref()],
+        bump = industry_account.bump,
+        constraint = industry_account.authority == authority.key()
+    )]
+    pub industry_account: Account<'info, IndustryAccount>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = ct_mint @ CarbonError::InvalidMint,
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(
+        mut,
+        mint::token_program = token_program,
+    )]
+    pub ct_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        address = ct_account_address(&authority.key(), &ct_mint, &token_program) @ CarbonError::InvalidTokenAccount,
+    )]
+    pub industry_ct_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub authority: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct SyncCtBalance<'info> {
+    #[account(
+        mut,
+        seeds = [b"industry", industry_account.authority.as_ref()],
+        bump = industry_account.bump,
+    )]
+    pub industry_account: Account<'info, IndustryAccount>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = ct_mint @ CarbonError::InvalidMint,
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(mint::token_program = token_program)]
+    pub ct_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        address = ct_account_address(&industry_account.authority, &ct_mint, &token_program) @ CarbonError::InvalidTokenAccount,
+    )]
+    pub industry_ct_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct SetIndustrySuspension<'info> {
+    #[account(
+        seeds = [b"role", [Role::Regulator as u8].as_ref(), regulator.key().as_ref()],
+        bump = role_assignment.bump,
+    )]
+    pub role_assignment: Account<'info, RoleAssignment>,
+
+    pub regulator: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"industry", industry_account.authority.as_ref()],
+        bump = industry_account.bump,
+    )]
+    pub industry_account: Account<'info, IndustryAccount>,
+}
+
+#[derive(Accounts)]
+#[instruction(trader: Pubkey)]
+pub struct AccreditTrader<'info> {
+    #[account(
+        seeds = [b"role", [Role::Verifier as u8].as_ref(), verifier.key().as_ref()],
+        bump = role_assignment.bump,
+    )]
+    pub role_assignment: Account<'info, RoleAssignment>,
+
+    #[account(
+        init,
+        payer = verifier,
+        space = 8 + AccreditedTrader::INIT_SPACE,
+        seeds = [b"trader", trader.as_ref()],
+        bump,
+    )]
+    pub accredited_trader: Account<'info, AccreditedTrader>,
+
+    #[account(mut)]
+    pub verifier: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeTraderAccreditation<'info> {
+    #[account(
+        seeds = [b"role", [Role::Verifier as u8].as_ref(), verifier.key().as_ref()],
+        bump = role_assignment.bump,
+    )]
+    pub role_assignment: Account<'info, RoleAssignment>,
+
+    #[account(
+        mut,
+        close = verifier,
+        seeds = [b"trader", accredited_trader.trader.as_ref()],
+        bump = accredited_trader.bump,
+    )]
+    pub accredited_trader: Account<'info, AccreditedTrader>,
+
+    #[account(mut)]
+    pub verifier: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(offer_id: u64, side: OfferSide, vintage: u32)]
+pub struct CreateOffer<'info> {
+    #[account(
+        mut,
+        seeds = [b"industry", maker.key().as_ref()],
+        bump = industry_account.bump,
+    )]
+    pub industry_account: Account<'info, IndustryAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = maker,
+        space = 8 + VintageHolding::INIT_SPACE,
+        seeds = [b"vintage", industry_account.key().as_ref(), vintage.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub maker_vintage: Account<'info, VintageHolding>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = ct_mint @ CarbonError::InvalidMint,
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(mint::token_program = token_program)]
+    pub ct_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = maker,
+        space = 8 + Offer::INIT_SPACE,
+        seeds = [b"offer", maker.key().as_ref(), offer_id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub offer: Account<'info, Offer>,
+
+    #[account(
+        init,
+        payer = maker,
+        token::mint = ct_mint,
+        token::authority = offer_authority,
+        token::token_program = token_program,
+        seeds = [b"offer_escrow", offer.key().as_ref()],
+        bump,
+    )]
+    pub offer_escrow: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        address = ct_account_address(&maker.key(), &ct_mint, &token_program) @ CarbonError::InvalidTokenAccount,
+    )]
+    pub maker_ct_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: PDA that owns every offer's escrowed CT
+    #[account(
+        seeds = [b"offer_authority"],
+        bump,
+    )]
+    pub offer_authority: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptOffer<'info> {
+    #[account(
+        mut,
+        close = maker,
+        seeds = [b"offer", offer.maker.as_ref(), offer.offer_id.to_le_bytes().as_ref()],
+        bump = offer.bump,
+    )]
+    pub offer: Account<'info, Offer>,
+
+    #[account(
+        mut,
+        seeds = [b"industry", offer.maker.as_ref()],
+        bump = maker_industry.bump,
+    )]
+    pub maker_industry: Account<'info, IndustryAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"vintage", maker_industry.key().as_ref(), offer.vintage.to_le_bytes().as_ref()],
+        bump = maker_vintage.bump,
+    )]
+    pub maker_vintage: Account<'info, VintageHolding>,
+
+    #[account(
+        mut,
+        seeds = [b"industry", taker.key().as_ref()],
+        bump = taker_industry.bump,
+    )]
+    pub taker_industry: Account<'info, IndustryAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = taker,
+        space = 8 + VintageHolding::INIT_SPACE,
+        seeds = [b"vintage", taker_industry.key().as_ref(), offer.vintage.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub taker_vintage: Account<'info, VintageHolding>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = ct_mint @ CarbonError::InvalidMint,
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(mint::token_program = token_program)]
+    pub ct_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"offer_escrow", offer.key().as_ref()],
+        bump,
+    )]
+    pub offer_escrow: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        address = ct_account_address(&offer.maker, &ct_mint, &token_program) @ CarbonError::InvalidTokenAccount,
+    )]
+    pub maker_ct_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        address = ct_account_address(&taker.key(), &ct_mint, &token_program) @ CarbonError::InvalidTokenAccount,
+    )]
+    pub taker_ct_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: PDA that owns every offer's escrowed CT
+    #[account(
+        seeds = [b"offer_authority"],
+        bump,
+    )]
+    pub offer_authority: UncheckedAccount<'info>,
+
+    /// CHECK: Maker of the offer, receiving payment and rent
+    #[account(
+        mut,
+        address = offer.maker @ CarbonError::InvalidRentRecipient,
+    )]
+    pub maker: SystemAccount<'info>,
+
+    #[account(mut)]
+    pub taker: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelOffer<'info> {
+    #[account(
+        mut,
+        close = maker,
+        has_one = maker @ CarbonError::Unauthorized,
+        seeds = [b"offer", offer.maker.as_ref(), offer.offer_id.to_le_bytes().as_ref()],
+        bump = offer.bump,
+    )]
+    pub offer: Account<'info, Offer>,
+
+    #[account(
+        mut,
+        seeds = [b"industry", maker.key().as_ref()],
+        bump = industry_account.bump,
+    )]
+    pub industry_account: Account<'info, IndustryAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"vintage", industry_account.key().as_ref(), offer.vintage.to_le_bytes().as_ref()],
+        bump = maker_vintage.bump,
+    )]
+    pub maker_vintage: Account<'info, VintageHolding>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = ct_mint @ CarbonError::InvalidMint,
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(mint::token_program = token_program)]
+    pub ct_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"offer_escrow", offer.key().as_ref()],
+        bump,
+    )]
+    pub offer_escrow: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        address = ct_account_address(&maker.key(), &ct_mint, &token_program) @ CarbonError::InvalidTokenAccount,
+    )]
+    pub maker_ct_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: PDA that owns every offer's escrowed CT
+    #[account(
+        seeds = [b"offer_authority"],
+        bump,
+    )]
+    pub offer_authority: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Accounts in the order Token-2022 passes them to a transfer hook, followed by the
+/// extra accounts described by `transfer_hook_account_metas`.
+#[derive(Accounts)]
+pub struct TransferHook<'info> {
+    #[account(token::mint = mint)]
+    pub source_token: InterfaceAccount<'info, TokenAccount>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(token::mint = mint)]
+    pub destination_token: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Owner or delegate of the source account, already authorized by Token-2022
+    pub owner: UncheckedAccount<'info>,
+
+    /// CHECK: Validation account listing the extra accounts below
+    #[account(
+        seeds = [b"extra-account-metas", mint.key().as_ref()],
+        bump,
+    )]
+    pub extra_account_meta_list: UncheckedAccount<'info>,
+
+    /// CHECK: Industry record of the source owner, which may not exist
+    #[account(
+        seeds = [b"industry", source_token.owner.as_ref()],
+        bump,
+    )]
+    pub source_industry: UncheckedAccount<'info>,
+
+    /// CHECK: Industry record of the destination owner, which may not exist
+    #[account(
+        seeds = [b"industry", destination_token.owner.as_ref()],
+        bump,
+    )]
+    pub destination_industry: UncheckedAccount<'info>,
+
+    /// CHECK: Accreditation of the source owner, which may not exist
+    #[account(
+        seeds = [b"trader", source_token.owner.as_ref()],
+        bump,
+    )]
+    pub source_trader: UncheckedAccount<'info>,
+
+    /// CHECK: Accreditation of the destination owner, which may not exist
+    #[account(
+        seeds = [b"trader", destination_token.owner.as_ref()],
+        bump,
+    )]
+    pub destination_trader: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseBid<'info> {
+    #[account(
+        mut,
+        close = bidder,
+        constraint = bid.bidder == bidder.key() @ CarbonError::InvalidBid,
+    )]
+    pub bid: Account<'info, Bid>,
+
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseAuction<'info> {
+    #[account(
+        mut,
+        close = rent_recipient,
+        seeds = [b"auction", &auction.batch_number.to_le_bytes()],
+        bump,
+    )]
+    pub auction: Account<'info, Auction>,
+
+    /// CHECK: Escrow account - PDA holding SOL
+    #[account(
+        mut,
+        seeds = [b"escrow", auction.key().as_ref()],
+        bump,
+    )]
+    pub escrow: SystemAccount<'info>,
+
+    /// Escrow token account of an auction denominated in a payment mint, closed with it
+    #[account(
+        mut,
+        seeds = [b"escrow_token", auction.key().as_ref()],
+        bump,
+    )]
+    pub escrow_token: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub payment_token_program: Option<Interface<'info, TokenInterface>>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(
+        seeds = [b"role", [Role::AuctionOperator as u8].as_ref(), authority.key().as_ref()],
+        bump = role_assignment.bump,
+    )]
+    pub role_assignment: Account<'info, RoleAssignment>,
+
+    /// CHECK: Treasury account to receive whatever is left in the escrow
+    #[account(
+        mut,
+        address = config.treasury @ CarbonError::InvalidTreasury,
+    )]
+    pub treasury: SystemAccount<'info>,
+
+    /// Operator that created the auction and paid its rent
+    #[account(
+        mut,
+        address = auction.authority @ CarbonError::InvalidRentRecipient,
+    )]
+    pub rent_recipient: SystemAccount<'info>,
+
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ArchiveEmissionReport<'info> {
+    #[account(
+        seeds = [b"industry", authority.key().as_ref()],
+        bump = industry_account.bump,
+    )]
+    pub industry_account: Account<'info, IndustryAccount>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(
+        mut,
+        close = authority,
+        constraint = emission_report.industry == industry_account.key() @ CarbonError::InvalidEmissionReport,
+    )]
+    pub emission_report: Account<'info, EmissionReport>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct ProgramConfig {
+    pub admin: Pubkey,
+    pub pending_admin: Option<Pubkey>,
+    pub ct_mint: Pubkey,
+    pub ct_token_program: Pubkey,
+    pub treasury: Pubkey,
+    pub compliance_period: u32,
+    pub period_deadline: i64,
+    pub penalty_per_tonne: u64,
+    /// How many periods a vintage stays surrenderable after its own; `None` banks without limit.
+    pub banking_periods: Option<u32>,
+    pub exit_ct_policy: ExitCtPolicy,
+    pub bump: u8,
+}
+
+impl ProgramConfig {
+    /// Whether CT of `vintage` may be surrendered in the open period. Vintages from later
+    /// periods can never be borrowed against, and banked ones lapse after `banking_periods`.
+    pub fn vintage_surrenderable(&self, vintage: u32) -> bool {
+        vintage <= self.compliance_period
+            && self.banking_periods
+                .is_none_or(|periods| vintage as u64 + periods as u64 >= self.compliance_period as u64)
+    }
+}
+
+/// Allowance budget for one compliance period. Auctions reserve their whole batch against
+/// `auction_cap` when created; free allocations count against `free_allocation_cap`, and
+/// `reserve_cap` is held back for cost containment sales.
+#[account]
+#[derive(InitSpace)]
+pub struct CapSchedule {
+    pub compliance_period: u32,
+    pub auction_cap: u64,
+    pub free_allocation_cap: u64,
+    pub auctioned: u64,
+    pub allocated: u64,
+    pub reserve_cap: u64,
+    pub reserve_released: u64,
+    pub bump: u8,
+}
+
+impl CapSchedule {
+    pub fn total_cap(&self) -> u64 {
+        self.auction_cap + self.free_allocation_cap + self.reserve_cap
+    }
+
+    pub fn reserve_remaining(&self) -> u64 {
+        self.reserve_cap.saturating_sub(self.reserve_released)
+    }
+
+    pub fn issue_auctioned(&mut self, amount: u64) -> Result<()> {
+        self.auctioned = Self::issue(self.auctioned, amount, self.auction_cap)?;
+        Ok(())
+    }
+
+    pub fn issue_allocated(&mut self, amount: u64) -> Result<()> {
+        self.allocated = Self::issue(self.allocated, amount, self.free_allocation_cap)?;
+        Ok(())
+    }
+
+    pub fn issue_reserve(&mut self, amount: u64) -> Result<()> {
+        self.reserve_released = Self::issue(self.reserve_released, amount, self.reserve_cap)?;
+        Ok(())
+    }
+
+    fn issue(issued: u64, amount: u64, cap: u64) -> Result<u64> {
+        let issued = issued
+            .checked_add(amount)
+            .ok_or(CarbonError::MathOverflow)?;
+        require!(issued <= cap, CarbonError::CapExceeded);
+        Ok(issued)
+    }
+}
+
+/// Cost containment terms: once an auction clears at or above `trigger_price`, up to
+/// `release_amount` reserve allowances are offered at `sale_price`.
+#[account]
+#[derive(InitSpace)]
+pub struct ReserveConfig {
+    pub trigger_price: u64,
+    pub sale_price: u64,
+    pub release_amount: u64,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct ReserveSale {
+    pub trigger_auction: Pubkey,
+    pub vintage: u32,
+    pub price_per_token: u64,
+    pub total_tokens: u64,
+    pub tokens_remaining: u64,
+    pub opened_at: i64,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct RoleAssignment {
+    pub role: Role,
+    pub holder: Pubkey,
+    pub granted_by: Pubkey,
+    pub granted_at: i64,
+    pub bump: u8,
+}
+
+/// Open peer-to-peer offer. Closed as soon as it is accepted or cancelled.
+#[account]
+#[derive(InitSpace)]
+pub struct Offer {
+    pub maker: Pubkey,
+    pub offer_id: u64,
+    pub side: OfferSide,
+    pub vintage: u32,
+    pub token_amount: u64,
+    pub price_per_token: u64,
+    pub total_price: u64,
+    pub created_at: i64,
+    pub bump: u8,
+}
+
+/// CT an industry holds from one vintage. Holdings move with CT through claims, offers and
+/// burns; CT transferred outside the program leaves its vintage behind in the sender's holding.
+#[account]
+#[derive(InitSpace)]
+pub struct VintageHolding {
+    pub industry: Pubkey,
+    pub vintage: u32,
+    pub amount: u64,
+    pub bump: u8,
+}
+
+impl VintageHolding {
+    /// Stamps a freshly created holding and counts it against `industry`; a no-op for one
+    /// that already exists.
+    pub fn open(&mut self, industry: &mut Account<IndustryAccount>, vintage: u32, bump: u8) {
+        if self.industry == Pubkey::default() {
+            self.industry = industry.key();
+            self.vintage = vintage;
+            self.amount = 0;
+            self.bump = bump;
+            industry.vintage_holdings += 1;
+        }
+    }
+
+    pub fn credit(&mut self, amount: u64) -> Result<()> {
+        self.amount = self.amount
+            .checked_add(amount)
+            .ok_or(CarbonError::MathOverflow)?;
+        Ok(())
+    }
+
+    pub fn debit(&mut self, amount: u64) -> Result<()> {
+        require!(self.amount >= amount, CarbonError::InsufficientVintageBalance);
+        self.amount -= amount;
+        Ok(())
+    }
+}
+
+/// Allowlist entry letting a non-industry wallet hold and trade a transfer-restricted CT mint.
+#[account]
+#[derive(InitSpace)]
+pub struct AccreditedTrader {
+    pub trader: Pubkey,
+    pub accredited_by: Pubkey,
+    pub accredited_at: i64,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct IndustryAccount {
+    pub authority: Pubkey,
+    #[max_len(100)]
+    pub company_name: String,
+    pub bond_amount: u64,
+    pub verified: bool,
+    pub suspended: bool,
+    pub ct_balance: u64,
+    pub total_purchased: u64,
+    pub total_allocated: u64,
+    pub total_burned: u64,
+    pub verified_emissions: u64,
+    pub compliance_period: u32,
+    pub period_obligation: u64,
+    /// Part of `period_obligation` carried over as shortfall from earlier periods.
+    pub period_carried_over: u64,
+    pub period_surrendered: u64,
+    pub period_allocated: u64,
+    pub offset_balance: u64,
+    pub period_offsets_surrendered: u64,
+    pub max_offset_bps: u16,
+    pub total_slashed: u64,
+    pub slash_count: u32,
+    pub last_slashed_at: i64,
+    pub last_slashed_period: u32,
+    /// Part of this period's own shortfall already penalised by `slash_bond`, which
+    /// settlement does not charge again.
+    pub period_slashed_shortfall: u64,
+    /// Shortfall from closed periods that `settle_period` has not yet penalised.
+    pub unsettled_shortfall: u64,
+    pub settled_through_period: u32,
+    /// Penalties assessed at settlement that the bond vault could not cover.
+    pub penalty_debt: u64,
+    /// Bids, offers and emission reports still waiting to settle against this record.
+    pub open_positions: u32,
+    /// Vintage holdings opened under this record, all of which close with it on deregistration.
+    pub vintage_holdings: u32,
+    /// Wallet proposed to take over the industry, which must accept before anything moves.
+    pub pending_authority: Option<Pubkey>,
+    /// Record this one took over from, whose vintage holdings can still be migrated.
+    pub migrated_from: Option<Pubkey>,
+    /// Wallets whose approvals govern bond withdrawals, burns and bids; empty when the
+    /// authority acts alone.
+    #[max_len(10)]
+    pub signers: Vec<Pubkey>,
+    /// Approvals an `IndustryProposal` needs before the authority may carry it out.
+    pub signer_threshold: u8,
+    pub compliance_status: ComplianceStatus,
+    pub created_at: i64,
+    pub bump: u8,
+    pub bond_vault_bump: u8,
+}
+
+impl IndustryAccount {
+    /// Verified emissions in the open period that have not yet been covered by burned CT.
+    pub fn outstanding_obligation(&self) -> u64 {
+        self.period_obligation.saturating_sub(self.period_surrendered)
+    }
+
+    /// Offset credits that may still be surrendered this period under `max_offset_bps`.
+    pub fn offset_allowance(&self) -> u64 {
+        let limit = (self.period_obligation as u128 * self.max_offset_bps as u128 / 10_000) as u64;
+        limit.saturating_sub(self.period_offsets_surrendered)
+    }
+
+    /// Part of `outstanding_obligation` that comes from this period's own emissions. Burns
+    /// cover carried-over shortfall first, and shortfall carried in has already been penalised.
+    pub fn period_shortfall(&self) -> u64 {
+        let carried_outstanding = self.period_carried_over.saturating_sub(self.period_surrendered);
+        self.outstanding_obligation().saturating_sub(carried_outstanding)
+    }
+
+    /// Opens the ledger for `period`, carrying any uncovered obligation forward. Only this
+    /// period's own shortfall that `slash_bond` has not already penalised is queued for
+    /// settlement.
+    pub fn roll_to_period(&mut self, period: u32) {
+        if period > self.compliance_period {
+            let outstanding = self.outstanding_obligation();
+            self.unsettled_shortfall = self.unsettled_shortfall
+                .saturating_add(self.period_shortfall().saturating_sub(self.period_slashed_shortfall));
+            self.period_obligation = outstanding;
+            self.period_carried_over = outstanding;
+            self.period_surrendered = 0;
+            self.period_allocated = 0;
+            self.period_offsets_surrendered = 0;
+            self.period_slashed_shortfall = 0;
+            self.compliance_period = period;
+        }
+    }
+
+    /// The action `proposal` authorises for a multisig-governed industry, or `None` when the
+    /// authority acts alone. Only approvals from current signers count.
+    pub fn approved_action<'a>(&self, proposal: &'a Option<Account<IndustryProposal>>) -> Result<Option<&'a IndustryAction>> {
+        if self.signer_threshold == 0 {
+            return Ok(None);
+        }
+
+        let proposal = proposal.as_ref().ok_or(CarbonError::ProposalRequired)?;
+        let approvals = proposal.approvals.iter().filter(|signer| self.signers.contains(signer)).count();
+        require!(approvals >= self.signer_threshold as usize, CarbonError::InsufficientApprovals);
+
+        Ok(Some(&proposal.action))
+    }
+
+    /// Counts a new bid, offer or report against the industry. None may be opened while an
+    /// authority transfer is pending, since the transfer closes the record they point at.
+    pub fn open_position(&mut self) -> Result<()> {
+        require!(self.pending_authority.is_none(), CarbonError::AuthorityTransferPending);
+        self.open_positions += 1;
+        Ok(())
+    }
+
+    pub fn settle_position(&mut self) {
+        self.open_positions = self.open_positions.saturating_sub(1);
+    }
+
+    /// Mirrors the balance of the industry's canonical CT account into `ct_balance`.
+    pub fn sync_ct_balance(&mut self, ct_account: &mut InterfaceAccount<TokenAccount>) -> Result<()> {
+        ct_account.reload()?;
+        self.ct_balance = ct_account.amount;
+        Ok(())
+    }
+
+    pub fn refresh_compliance_status(&mut self) {
+        self.compliance_status = if !self.verified {
+            ComplianceStatus::Pending
+        } else if self.outstanding_obligation() == 0 {
+            ComplianceStatus::Compliant
+        } else {
+            ComplianceStatus::NonCompliant
+        };
+    }
+}
+
+/// Action of a multisig-governed industry awaiting its signers' approval.
+#[account]
+#[derive(InitSpace)]
+pub struct IndustryProposal {
+    pub industry: Pubkey,
+    pub proposal_id: u64,
+    pub proposer: Pubkey,
+    pub action: IndustryAction,
+    #[max_len(10)]
+    pub approvals: Vec<Pubkey>,
+    pub created_at: i64,
+    pub bump: u8,
+}
+
+/// Registry entry left behind by `deregister_industry`. No instruction modifies or closes it.
+#[account]
+#[derive(InitSpace)]
+pub struct IndustryTombstone {
+    pub industry: Pubkey,
+    pub authority: Pubkey,
+    #[max_len(100)]
+    pub company_name: String,
+    pub registered_at: i64,
+    pub deregistered_at: i64,
+    pub last_compliance_period: u32,
+    pub verified_emissions: u64,
+    pub total_purchased: u64,
+    pub total_allocated: u64,
+    pub total_burned: u64,
+    pub total_slashed: u64,
+    pub bond_returned: u64,
+    pub ct_retired: u64,
+    pub ct_policy: ExitCtPolicy,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct EmissionReport {
+    pub industry: Pubkey,
+    pub co2_emitted: u64,
+    #[max_len(20)]
+    pub report_period: String,
+    pub compliance_period: u32,
+    pub submitted_at: i64,
+    pub verified: bool,
+    pub status: ReportStatus,
+    pub verifier: Option<Pubkey>,
+    pub verified_at: i64,
+    pub adjusted_co2_emitted: Option<u64>,
+}
+
+impl EmissionReport {
+    /// Emissions that count toward the obligation: the verifier's adjustment if any, otherwise the submitted figure.
+    pub fn effective_co2_emitted(&self) -> u64 {
+        self.adjusted_co2_emitted.unwrap_or(self.co2_emitted)
+    }
+}
+
+/// A removal project (reforestation, methane capture, ...) whose verified removals are issued
+/// as offset credits.
+#[account]
+#[derive(InitSpace)]
+pub struct OffsetProject {
+    pub developer: Pubkey,
+    pub project_id: u64,
+    #[max_len(100)]
+    pub name: String,
+    pub kind: OffsetKind,
+    pub approved: bool,
+    pub approved_by: Option<Pubkey>,
+    pub total_issued: u64,
+    pub created_at: i64,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct RemovalReport {
+    pub project: Pubkey,
+    /// Industry account the offset credits are issued to.
+    pub beneficiary: Pubkey,
+    pub co2_removed: u64,
+    pub submitted_at: i64,
+    pub status: ReportStatus,
+    pub verifier: Option<Pubkey>,
+    pub issued_at: i64,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Auction {
+    pub authority: Pubkey,
+    pub batch_number: u32,
+    pub total_tokens: u64,
+    pub tokens_remaining: u64,
+    pub start_price: u64,
+    pub current_price: u64,
+    pub reserve_price: u64,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub status: AuctionStatus,
+    pub total_raised: u64,
+    pub participant_count: u32,
+    pub tokens_sold: u64,
+    pub kind: AuctionKind,
+    pub reveal_end_time: i64,
+    pub min_collateral: u64,
+    pub revealed_count: u32,
+    pub max_tokens_per_bidder: u64,
+    pub max_bidder_share_bps: u16,
+    pub settled_count: u32,
+    /// Compliance period the auctioned allowances belong to.
+    pub vintage: u32,
+    pub curve: PriceCurve,
+    /// SPL mint bids are paid in; `None` for an auction paid in lamports.
+    pub payment_mint: Option<Pubkey>,
+}
+
+impl Auction {
+    /// Dutch auction price at `current_time` along the auction's decay curve.
+    pub fn price_at(&self, current_time: i64) -> Result<u64> {
+        self.curve.price_at(self.start_price, self.reserve_price, self.start_time, self.end_time, current_time)
+    }
+
+    /// Most CT a single bidder may bid for in this auction; `u64::MAX` when uncapped.
+    pub fn bidder_cap(&self) -> u64 {
+        let absolute_cap = match self.max_tokens_per_bidder {
+            0 => u64::MAX,
+            cap => cap,
+        };
+        let share_cap = match self.max_bidder_share_bps {
+            0 => u64::MAX,
+            bps => (self.total_tokens as u128 * bps as u128 / 10_000) as u64,
+        };
+        absolute_cap.min(share_cap)
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Bid {
+    pub auction: Pubkey,
+    pub bidder: Pubkey,
+    pub industry: Pubkey,
+    pub token_amount: u64,
+    pub price_per_token: u64,
+    pub total_cost: u64,
+    pub timestamp: i64,
+    pub status: BidStatus,
+    pub commitment: [u8; 32],
+    pub collateral: u64,
+}
+
+/// Running total of what one bidder has bid for in one auction, checked against the auction's cap.
+#[account]
+#[derive(InitSpace)]
+pub struct BidderPosition {
+    pub auction: Pubkey,
+    pub bidder: Pubkey,
+    pub tokens_bid: u64,
+    /// Bids counted here that have not been claimed or refunded; the position closes with the last.
+    pub open_bids: u32,
+    pub bump: u8,
+}
+
+impl BidderPosition {
+    /// Stamps a freshly created position; a no-op for one that already exists.
+    pub fn open(&mut self, auction: Pubkey, bidder: Pubkey, bump: u8) {
+        if self.bidder == Pubkey::default() {
+            self.auction = auction;
+            self.bidder = bidder;
+            self.tokens_bid = 0;
+            self.open_bids = 0;
+            self.bump = bump;
+        }
+    }
+
+    pub fn record(&mut self, auction: &Auction, token_amount: u64) -> Result<()> {
+        let tokens_bid = self.tokens_bid
+            .checked_add(token_amount)
+            .ok_or(CarbonError::MathOverflow)?;
+        require!(tokens_bid <= auction.bidder_cap(), CarbonError::BidderCapExceeded);
+
+        self.tokens_bid = tokens_bid;
+        self.open_bids += 1;
+        Ok(())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum Role {
+    Verifier,
+    AuctionOperator,
+    Treasurer,
+    Regulator,
+}
+
+/// Actions of a multisig-governed industry that need its signers' approval, with the
+/// parameters the authority must carry them out with.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq, InitSpace)]
+pub enum IndustryAction {
+    WithdrawBond { amount: u64 },
+    BurnCt { vintage: u32, amount: u64 },
+    /// Bids `token_amount` in `auction`, provided the price has fallen to `max_price_per_token`.
+    PlaceBid { auction: Pubkey, token_amount: u64, max_price_per_token: u64 },
+    UpdateSigners {
+        #[max_len(10)]
+        signers: Vec<Pubkey>,
+        threshold: u8,
+    },
+}
+
+/// What happens to CT an industry still holds when it deregisters.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum ExitCtPolicy {
+    /// Retire it by burning, taking the allowances out of circulation.
+    Burn,
+    /// Hand it to the treasury's CT account, returning the allowances to the regulator.
+    Surrender,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq, InitSpace)]
+pub enum ComplianceStatus {
+    Pending,
+    Compliant,
+    NonCompliant,
+    Warning,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq, InitSpace)]
+pub enum ReportStatus {
+    Pending,
+    Verified,
+    Rejected,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq, InitSpace)]
+pub enum OffsetKind {
+    Reforestation,
+    MethaneCapture,
+    Other,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq, InitSpace)]
+pub enum AuctionStatus {
+    Active,
+    Completed,
+    Finalized,
+    Cancelled
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum AuctionKind {
+    Dutch,
+    SealedBid,
+}
+
+/// How a Dutch auction's price falls from `start_price` towards `reserve_price`. Every curve
+/// starts at `start_price`, never drops below `reserve_price` and reaches it at `end_time`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum PriceCurve {
+    /// Falls at a constant rate over the auction.
+    Linear,
+    /// Distance above the reserve halves every `half_life_seconds`.
+    Exponential { half_life_seconds: i64 },
+    /// Drops by `decrement` lamports every `step_seconds`.
+    Stepwise { step_seconds: i64, decrement: u64 },
+}
+
+impl PriceCurve {
+    pub fn validate(&self) -> Result<()> {
+        let valid = match *self {
+            PriceCurve::Linear => true,
+            PriceCurve::Exponential { half_life_seconds } => half_life_seconds > 0,
+            PriceCurve::Stepwise { step_seconds, decrement } => step_seconds > 0 && decrement > 0,
+        };
+        require!(valid, CarbonError::InvalidPriceCurve);
+        Ok(())
+    }
+
+    pub fn price_at(&self, start_price: u64, reserve_price: u64, start_time: i64, end_time: i64, current_time: i64) -> Result<u64> {
+        if current_time >= end_time {
+            return Ok(reserve_price);
+        }
+        if current_time <= start_time {
+            return Ok(start_price);
+        }
+
+        let duration = (end_time - start_time) as u128;
+        let elapsed = (current_time - start_time) as u128;
+        let price_range = start_price
+            .checked_sub(reserve_price)
+            .ok_or(CarbonError::MathOverflow)? as u128;
+
+        let decay = match *self {
+            PriceCurve::Linear => price_range
+                .checked_mul(elapsed)
+                .ok_or(CarbonError::MathOverflow)?
+                .checked_div(duration)
+                .ok_or(CarbonError::MathOverflow)?,
+            PriceCurve::Exponential { half_life_seconds } => {
+                let half_life = half_life_seconds as u128;
+                let halvings = elapsed
+                    .checked_div(half_life)
+                    .ok_or(CarbonError::MathOverflow)?;
+                // Whole half-lives halve the range; within one it falls linearly to the next halving
+                let remaining = match u32::try_from(halvings) {
+                    Ok(halvings) => price_range.checked_shr(halvings).unwrap_or(0),
+                    Err(_) => 0,
+                };
+                let partial = (remaining / 2)
+                    .checked_mul(elapsed % half_life)
+                    .ok_or(CarbonError::MathOverflow)?
+                    .checked_div(half_life)
+                    .ok_or(CarbonError::MathOverflow)?;
+                price_range - remaining + partial
+            }
+            PriceCurve::Stepwise { step_seconds, decrement } => {
+                let steps = elapsed
+                    .checked_div(step_seconds as u128)
+                    .ok_or(CarbonError::MathOverflow)?;
+                steps.saturating_mul(decrement as u128)
+            }
+        };
+
+        let current_price = (start_price as u128).saturating_sub(decay) as u64;
+        Ok(current_price.max(reserve_price))
+    }
+}
+
+/// Whether the maker of an offer is selling CT for SOL or buying CT with SOL.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum OfferSide {
+    Sell,
+    Buy,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq, InitSpace)]
+pub enum BidStatus {
+    Pending,
+    Accepted,
+    Refunded,
+    Committed,
+    Forfeited,
+}
+
+#[event]
+pub struct ProgramInitialized {
+    pub config: Pubkey,
+    pub admin: Pubkey,
+    pub ct_mint: Pubkey,
+    pub ct_token_program: Pubkey,
+    pub treasury: Pubkey,
+}
+
+#[event]
+pub struct CompliancePeriodAdvanced {
+    pub compliance_period: u32,
+    pub period_deadline: i64,
+}
+
+#[event]
+pub struct ComplianceTermsUpdated {
+    pub compliance_period: u32,
+    pub period_deadline: i64,
+    pub penalty_per_tonne: u64,
+}
+
+#[event]
+pub struct BankingRulesUpdated {
+    pub compliance_period: u32,
+    pub banking_periods: Option<u32>,
+}
+
+#[event]
+pub struct ExitCtPolicyUpdated {
+    pub exit_ct_policy: ExitCtPolicy,
+}
+
+#[event]
+pub struct CapScheduleSet {
+    pub compliance_period: u32,
+    pub auction_cap: u64,
+    pub free_allocation_cap: u64,
+    pub reserve_cap: u64,
+    pub auctioned: u64,
+    pub allocated: u64,
+}
+
+#[event]
+pub struct ReserveConfigured {
+    pub trigger_price: u64,
+    pub sale_price: u64,
+    pub release_amount: u64,
+}
+
+#[event]
+pub struct ReserveReleased {
+    pub sale: Pubkey,
+    pub trigger_auction: Pubkey,
+    pub vintage: u32,
+    pub clearing_price: u64,
+    pub price_per_token: u64,
+    pub total_tokens: u64,
+}
+
+#[event]
+pub struct ReserveAllowancesPurchased {
+    pub sale: Pubkey,
+    pub industry: Pubkey,
+    pub token_amount: u64,
+    pub price_per_token: u64,
+    pub total_cost: u64,
+    pub tokens_remaining: u64,
+}
+
+#[event]
+pub struct AdminProposed {
+    pub admin: Pubkey,
+    pub pending_admin: Pubkey,
+}
+
+#[event]
+pub struct AdminTransferred {
+    pub previous_admin: Pubkey,
+    pub new_admin: Pubkey,
+}
+
+#[event]
+pub struct RoleGranted {
+    pub role_assignment: Pubkey,
+    pub role: Role,
+    pub holder: Pubkey,
+    pub granted_by: Pubkey,
+}
+
+#[event]
+pub struct RoleRevoked {
+    pub role_assignment: Pubkey,
+    pub role: Role,
+    pub holder: Pubkey,
+    pub revoked_by: Pubkey,
+}
+
+#[event]
+pub struct IndustryRegistered {
+    pub industry: Pubkey,
+    pub authority: Pubkey,
+    pub company_name: String,
+    pub bond_amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct IndustryProfileUpdated {
+    pub industry: Pubkey,
+    pub authority: Pubkey,
+    pub previous_name: String,
+    pub company_name: String,
+}
+
+#[event]
+pub struct IndustrySignersUpdated {
+    pub industry: Pubkey,
+    pub signers: Vec<Pubkey>,
+    pub threshold: u8,
+}
+
+#[event]
+pub struct IndustryActionProposed {
+    pub industry: Pubkey,
+    pub proposal: Pubkey,
+    pub proposer: Pubkey,
+    pub action: IndustryAction,
 }
 
-#[derive(Accounts)]
-pub struct FinalizeAuction<'info> {
-    #[account(
-        mut,
-        seeds = [b"auction", &auction.batch_number.to_le_bytes()],
-        bump,
-    )]
-    pub auction: Account<'info, Auction>,
+#[event]
+pub struct IndustryActionApproved {
+    pub industry: Pubkey,
+    pub proposal: Pubkey,
+    pub signer: Pubkey,
+    pub approvals: u8,
+    pub threshold: u8,
+}
 
-    /// CHECK: Admin authority
-    pub authority: Signer<'info>,
+#[event]
+pub struct IndustryActionCancelled {
+    pub industry: Pubkey,
+    pub proposal: Pubkey,
+    pub proposer: Pubkey,
 }
 
-#[derive(Accounts)]
-pub struct ClaimTokens<'info> {
-    #[account(
-        mut,
-        seeds = [b"auction", &auction.batch_number.to_le_bytes()],
-        bump,
-    )]
-    pub auction: Account<'info, Auction>,
+#[event]
+pub struct IndustryDeregistered {
+    pub industry: Pubkey,
+    pub authority: Pubkey,
+    pub tombstone: Pubkey,
+    pub bond_returned: u64,
+    pub ct_retired: u64,
+    pub ct_policy: ExitCtPolicy,
+    pub timestamp: i64,
+}
 
-    #[account(
-        mut,
-        constraint = bid.bidder == bidder.key()
-    )]
-    pub bid: Account<'info, Bid>,
+#[event]
+pub struct IndustryAuthorityProposed {
+    pub industry: Pubkey,
+    pub authority: Pubkey,
+    pub pending_authority: Pubkey,
+    pub ct_escrowed: u64,
+}
 
-    #[account(
-        mut,
-        seeds = [b"industry", bid.bidder.as_ref()],
-        bump,
-    )]
-    pub industry_account: Account<'info, IndustryAccount>,
+#[event]
+pub struct IndustryAuthorityTransferCancelled {
+    pub industry: Pubkey,
+    pub authority: Pubkey,
+    pub pending_authority: Pubkey,
+    pub ct_returned: u64,
+}
 
-    #[account(mut)]
-    pub ct_mint: Account<'info, Mint>,
+#[event]
+pub struct IndustryAuthorityTransferred {
+    pub previous_industry: Pubkey,
+    pub industry: Pubkey,
+    pub previous_authority: Pubkey,
+    pub authority: Pubkey,
+    pub bond_migrated: u64,
+    pub ct_migrated: u64,
+}
 
-    #[account(
-        mut,
-        constraint = industry_ct_account.mint == ct_mint.key() @ CarbonError::InvalidTokenAccount,
-        constraint = industry_ct_account.owner == industry_account.authority @ CarbonError::InvalidTokenAccount,
-    )]
-    pub industry_ct_account: Account<'info, TokenAccount>,
+#[event]
+pub struct VintageHoldingMigrated {
+    pub industry: Pubkey,
+    pub previous_industry: Pubkey,
+    pub vintage: u32,
+    pub amount: u64,
+}
 
-    /// CHECK: Mint authority PDA
-    #[account(
-        seeds = [b"mint_authority"],
-        bump,
-    )]
-    pub mint_authority: UncheckedAccount<'info>,
+#[event]
+pub struct IndustryVerified {
+    pub industry: Pubkey,
+    pub authority: Pubkey,
+    pub verifier: Pubkey,
+}
 
-    /// CHECK: Escrow account - PDA holding SOL
-    #[account(
-        mut,
-        seeds = [b"escrow", auction.key().as_ref()],
-        bump,
-    )]
-    pub escrow: SystemAccount<'info>,
+#[event]
+pub struct CtBalanceSynced {
+    pub industry: Pubkey,
+    pub previous_balance: u64,
+    pub ct_balance: u64,
+}
 
-    #[account(mut)]
-    pub bidder: Signer<'info>,
+#[event]
+pub struct IndustrySuspensionChanged {
+    pub industry: Pubkey,
+    pub authority: Pubkey,
+    pub regulator: Pubkey,
+    pub suspended: bool,
+}
 
-    pub token_program: Program<'info, Token>,
-    pub associated_token_program: Program<'info, AssociatedToken>,
-    pub system_program: Program<'info, System>,
+#[event]
+pub struct TraderAccredited {
+    pub accredited_trader: Pubkey,
+    pub trader: Pubkey,
+    pub verifier: Pubkey,
 }
 
-#[derive(Accounts)]
-pub struct CancelAuction<'info> {
-    #[account(
-        mut,
-        seeds = [b"auction", &auction.batch_number.to_le_bytes()],
-        bump,
-    )]
-    pub auction: Account<'info, Auction>,
+#[event]
+pub struct TraderAccreditationRevoked {
+    pub accredited_trader: Pubkey,
+    pub trader: Pubkey,
+    pub verifier: Pubkey,
+}
 
-    /// CHECK: Admin authority
-    pub authority: Signer<'info>,
+#[event]
+pub struct OfferCreated {
+    pub offer: Pubkey,
+    pub maker: Pubkey,
+    pub side: OfferSide,
+    pub vintage: u32,
+    pub token_amount: u64,
+    pub price_per_token: u64,
+    pub total_price: u64,
 }
 
-#[derive(Accounts)]
-pub struct WithdrawProceeds<'info> {
-    #[account(
-        seeds = [b"auction", &auction.batch_number.to_le_bytes()],
-        bump,
-    )]
-    pub auction: Account<'info, Auction>,
+#[event]
+pub struct OfferAccepted {
+    pub offer: Pubkey,
+    pub maker: Pubkey,
+    pub taker: Pubkey,
+    pub side: OfferSide,
+    pub token_amount: u64,
+    pub total_price: u64,
+}
 
-    /// CHECK: Escrow account - PDA holding SOL
-    #[account(
-        mut,
-        seeds = [b"escrow", auction.key().as_ref()],
-        bump,
-    )]
-    pub escrow: SystemAccount<'info>,
+#[event]
+pub struct OfferCancelled {
+    pub offer: Pubkey,
+    pub maker: Pubkey,
+    pub side: OfferSide,
+    pub token_amount: u64,
+}
 
-    /// CHECK: Treasury account to receive SOL
-    #[account(mut)]
-    pub treasury: SystemAccount<'info>,
+#[event]
+pub struct BondDeposited {
+    pub industry: Pubkey,
+    pub bond_vault: Pubkey,
+    pub amount: u64,
+    pub bond_amount: u64,
+}
 
-    /// CHECK: Admin authority
-    pub authority: Signer<'info>,
+#[event]
+pub struct BondWithdrawn {
+    pub industry: Pubkey,
+    pub bond_vault: Pubkey,
+    pub amount: u64,
+    pub bond_amount: u64,
+}
 
-    pub system_program: Program<'info, System>,
+#[event]
+pub struct BondSlashed {
+    pub industry: Pubkey,
+    pub bond_vault: Pubkey,
+    pub treasury: Pubkey,
+    pub penalty: u64,
+    pub compliance_period: u32,
+    pub outstanding_obligation: u64,
 }
 
-#[account]
-#[derive(InitSpace)]
-pub struct IndustryAccount {
-    pub authority: Pubkey,
-    #[max_len(100)]
-    pub company_name: String,
-    pub bond_amount: u64,
-    pub verified: bool,
-    pub ct_balance: u64,
-    pub total_purchased: u64,
-    pub total_burned: u64,
-    pub compliance_status: ComplianceStatus,
-    pub created_at: i64,
-    pub bump: u8,
-    pub bond_vault_bump: u8,
+#[event]
+pub struct PeriodSettled {
+    pub industry: Pubkey,
+    pub compliance_period: u32,
+    pub shortfall: u64,
+    pub penalty: u64,
+    pub collected: u64,
+    pub penalty_debt: u64,
+    pub carried_obligation: u64,
 }
 
-#[account]
-#[derive(InitSpace)]
-pub struct EmissionReport {
+#[event]
+pub struct ReportSubmitted {
+    pub report: Pubkey,
     pub industry: Pubkey,
     pub co2_emitted: u64,
-    #[max_len(20)]
-    pub report_period: String,
-    pub submitted_at: i64,
-    pub verified: bool,
+    pub compliance_period: u32,
+    pub timestamp: i64,
 }
 
-#[account]
-#[derive(InitSpace)]
-pub struct Auction {
-    pub authority: Pubkey,
+#[event]
+pub struct ReportVerified {
+    pub report: Pubkey,
+    pub industry: Pubkey,
+    pub verifier: Pubkey,
+    pub co2_emitted: u64,
+    pub period_obligation: u64,
+}
+
+#[event]
+pub struct ReportRejected {
+    pub report: Pubkey,
+    pub industry: Pubkey,
+    pub verifier: Pubkey,
+}
+
+#[event]
+pub struct AuctionCreated {
+    pub auction: Pubkey,
     pub batch_number: u32,
+    pub kind: AuctionKind,
+    pub vintage: u32,
     pub total_tokens: u64,
-    pub tokens_remaining: u64,
     pub start_price: u64,
-    pub current_price: u64,
     pub reserve_price: u64,
     pub start_time: i64,
     pub end_time: i64,
-    pub status: AuctionStatus,
-    pub total_raised: u64,
-    pub participant_count: u32,
-    pub tokens_sold: u64,
 }
 
-#[account]
-#[derive(InitSpace)]
-pub struct Bid {
+#[event]
+pub struct PriceCurveSet {
+    pub auction: Pubkey,
+    pub curve: PriceCurve,
+}
+
+#[event]
+pub struct PaymentMintSet {
+    pub auction: Pubkey,
+    pub payment_mint: Pubkey,
+    pub escrow_token: Pubkey,
+}
+
+#[event]
+pub struct BidderCapSet {
+    pub auction: Pubkey,
+    pub max_tokens_per_bidder: u64,
+    pub max_bidder_share_bps: u16,
+    pub bidder_cap: u64,
+}
+
+#[event]
+pub struct BidPlaced {
     pub auction: Pubkey,
+    pub bid: Pubkey,
     pub bidder: Pubkey,
     pub industry: Pubkey,
     pub token_amount: u64,
     pub price_per_token: u64,
     pub total_cost: u64,
-    pub timestamp: i64,
-    pub status: BidStatus,
+    pub tokens_remaining: u64,
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace)]
-pub enum ComplianceStatus {
-    Pending,
-    Compliant,
-    NonCompliant,
-    Warning,
+#[event]
+pub struct BidCommitted {
+    pub auction: Pubkey,
+    pub bid: Pubkey,
+    pub bidder: Pubkey,
+    pub industry: Pubkey,
+    pub collateral: u64,
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace)]
-pub enum AuctionStatus {
-    Active,
-    Completed,
-    Finalized,
-    Cancelled
+#[event]
+pub struct BidRevealed {
+    pub auction: Pubkey,
+    pub bid: Pubkey,
+    pub bidder: Pubkey,
+    pub token_amount: u64,
+    pub price_per_token: u64,
+    pub total_cost: u64,
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace)]
-pub enum BidStatus {
-    Pending,
-    Accepted,
-    Refunded,
+#[event]
+pub struct BidForfeited {
+    pub auction: Pubkey,
+    pub bid: Pubkey,
+    pub bidder: Pubkey,
+    pub forfeited: u64,
+    pub refund_amount: u64,
+}
+
+#[event]
+pub struct AuctionFinalized {
+    pub auction: Pubkey,
+    pub clearing_price: u64,
+    pub tokens_sold: u64,
+    pub total_raised: u64,
+}
+
+#[event]
+pub struct TokensClaimed {
+    pub auction: Pubkey,
+    pub bid: Pubkey,
+    pub industry: Pubkey,
+    pub vintage: u32,
+    pub token_amount: u64,
+    pub clearing_price: u64,
+    pub final_cost: u64,
+    pub refund_amount: u64,
+}
+
+#[event]
+pub struct FreeAllowancesAllocated {
+    pub industry: Pubkey,
+    pub regulator: Pubkey,
+    pub compliance_period: u32,
+    pub amount: u64,
+    pub period_allocated: u64,
+    pub allocated: u64,
+}
+
+#[event]
+pub struct OffsetProjectRegistered {
+    pub project: Pubkey,
+    pub developer: Pubkey,
+    pub project_id: u64,
+    pub kind: OffsetKind,
+}
+
+#[event]
+pub struct OffsetProjectApproved {
+    pub project: Pubkey,
+    pub developer: Pubkey,
+    pub verifier: Pubkey,
+}
+
+#[event]
+pub struct RemovalReportSubmitted {
+    pub report: Pubkey,
+    pub project: Pubkey,
+    pub beneficiary: Pubkey,
+    pub co2_removed: u64,
+}
+
+#[event]
+pub struct OffsetCreditsIssued {
+    pub report: Pubkey,
+    pub project: Pubkey,
+    pub industry: Pubkey,
+    pub verifier: Pubkey,
+    pub amount: u64,
+    pub offset_balance: u64,
+}
+
+#[event]
+pub struct OffsetLimitUpdated {
+    pub industry: Pubkey,
+    pub regulator: Pubkey,
+    pub max_offset_bps: u16,
+}
+
+#[event]
+pub struct OffsetCreditsBurned {
+    pub industry: Pubkey,
+    pub amount: u64,
+    pub compliance_period: u32,
+    pub period_obligation: u64,
+    pub period_surrendered: u64,
+    pub period_offsets_surrendered: u64,
+    pub compliance_status: ComplianceStatus,
+}
+
+#[event]
+pub struct CreditsBurned {
+    pub industry: Pubkey,
+    pub amount: u64,
+    pub vintage: u32,
+    pub compliance_period: u32,
+    pub period_obligation: u64,
+    pub period_surrendered: u64,
+    pub compliance_status: ComplianceStatus,
+}
+
+#[event]
+pub struct ProceedsWithdrawn {
+    pub auction: Pubkey,
+    pub escrow: Pubkey,
+    pub treasury: Pubkey,
+    pub payment_mint: Option<Pubkey>,
+    pub amount: u64,
+}
+
+#[event]
+pub struct AuctionCancelled {
+    pub auction: Pubkey,
+    pub batch_number: u32,
+    pub participant_count: u32,
+}
+
+#[event]
+pub struct BidRefunded {
+    pub auction: Pubkey,
+    pub bid: Pubkey,
+    pub bidder: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct BidClosed {
+    pub auction: Pubkey,
+    pub bid: Pubkey,
+    pub bidder: Pubkey,
+}
+
+#[event]
+pub struct AuctionClosed {
+    pub auction: Pubkey,
+    pub batch_number: u32,
+    pub rent_recipient: Pubkey,
+    pub escrow_swept: u64,
+}
+
+#[event]
+pub struct ReportArchived {
+    pub report: Pubkey,
+    pub industry: Pubkey,
+    pub compliance_period: u32,
 }
 
 #[error_code]
@@ -842,10 +6389,152 @@ pub enum CarbonError {
     AuctionNotFinalized,
     #[msg("Bid has already been processed.")]
     BidAlreadyProcessed,
+    // Unused since `cancel_auction` started refunding bids instead of refusing them. Kept so
+    // the codes of the variants after it stay stable for existing clients.
     #[msg("Cannot cancel auction with participants.")]
     HasParticipants,
     #[msg("Insufficient funds in account.")]
     InsufficientFunds,
     #[msg("Invalid token account.")]
     InvalidTokenAccount,
+    #[msg("Signer is not authorized for this operation.")]
+    Unauthorized,
+    #[msg("Signer is not the pending admin.")]
+    NotPendingAdmin,
+    #[msg("CT mint does not match the program config.")]
+    InvalidMint,
+    #[msg("Treasury does not match the program config.")]
+    InvalidTreasury,
+    #[msg("Emission report has already been reviewed.")]
+    ReportAlreadyReviewed,
+    #[msg("Emission report does not belong to this industry.")]
+    InvalidEmissionReport,
+    #[msg("Burn amount exceeds the outstanding compliance obligation.")]
+    ExceedsObligation,
+    #[msg("Compliance deadline must be in the future.")]
+    InvalidDeadline,
+    #[msg("Industry is not non-compliant; bond cannot be slashed.")]
+    NotSlashable,
+    #[msg("Compliance deadline has not passed yet.")]
+    DeadlineNotPassed,
+    #[msg("Bond has already been slashed for this compliance period.")]
+    AlreadySlashed,
+    #[msg("Operation is not supported by this kind of auction.")]
+    InvalidAuctionKind,
+    #[msg("Collateral is below the auction minimum.")]
+    InsufficientCollateral,
+    #[msg("Bid does not belong to this auction or bidder.")]
+    InvalidBid,
+    #[msg("Reveal window has not opened yet.")]
+    RevealNotOpen,
+    #[msg("Reveal window has closed.")]
+    RevealClosed,
+    #[msg("Revealed bid does not match its commitment.")]
+    CommitmentMismatch,
+    #[msg("Bid price is below the reserve price.")]
+    BidBelowReserve,
+    #[msg("Every revealed bid must be supplied exactly once to finalize.")]
+    IncompleteBidSet,
+    #[msg("Auction has not been cancelled.")]
+    AuctionNotCancelled,
+    #[msg("Bid would exceed the per-bidder purchase cap.")]
+    BidderCapExceeded,
+    #[msg("Bidder share cap cannot exceed 10000 basis points.")]
+    InvalidBidderCap,
+    #[msg("Bid has not been settled yet.")]
+    BidNotSettled,
+    #[msg("Auction still has unsettled bids.")]
+    BidsOutstanding,
+    #[msg("Auction proceeds have not been withdrawn.")]
+    ProceedsNotWithdrawn,
+    #[msg("Rent must be returned to the account that paid it.")]
+    InvalidRentRecipient,
+    #[msg("Only reviewed reports from a past compliance period can be archived.")]
+    ReportNotArchivable,
+    #[msg("Industry has outstanding compliance obligations.")]
+    ObligationsOutstanding,
+    #[msg("Transfer hook can only run as part of a Token-2022 transfer.")]
+    NotTransferring,
+    #[msg("CT can only move between verified, non-suspended industries and accredited traders.")]
+    TransferNotPermitted,
+    #[msg("Industry is suspended.")]
+    IndustrySuspended,
+    #[msg("Offer price must be greater than zero.")]
+    InvalidOfferPrice,
+    #[msg("Offer cannot be accepted by its maker.")]
+    SelfTrade,
+    #[msg("CT of this vintage cannot be surrendered in the current compliance period.")]
+    VintageNotSurrenderable,
+    #[msg("Not enough CT of this vintage.")]
+    InsufficientVintageBalance,
+    #[msg("Issuing these allowances would exceed the compliance period's cap.")]
+    CapExceeded,
+    #[msg("Cap cannot be set below the allowances already issued.")]
+    CapBelowIssued,
+    #[msg("Compliance period has already closed.")]
+    CompliancePeriodClosed,
+    #[msg("Offset project has not been approved.")]
+    ProjectNotApproved,
+    #[msg("Offset project is already approved.")]
+    ProjectAlreadyApproved,
+    #[msg("Removal report does not belong to this project and beneficiary.")]
+    InvalidRemovalReport,
+    #[msg("Offset limit cannot exceed 10000 basis points.")]
+    InvalidOffsetLimit,
+    #[msg("Not enough offset credits.")]
+    InsufficientOffsetCredits,
+    #[msg("Offsets would cover more of the obligation than the industry's limit allows.")]
+    OffsetLimitExceeded,
+    #[msg("Industry has no closed compliance period left to settle.")]
+    PeriodAlreadySettled,
+    #[msg("Reserve trigger price, sale price and release amount must be greater than zero.")]
+    InvalidReserveConfig,
+    #[msg("Auction did not clear at or above the reserve trigger price.")]
+    ReserveNotTriggered,
+    #[msg("Cost containment reserve for this period is exhausted.")]
+    ReserveExhausted,
+    #[msg("Reserve sale has closed with its compliance period.")]
+    ReserveSaleClosed,
+    #[msg("Price curve parameters must be greater than zero.")]
+    InvalidPriceCurve,
+    #[msg("Payment accounts do not match the auction's payment mint.")]
+    InvalidPaymentMint,
+    #[msg("Company name must be between 1 and 100 bytes.")]
+    InvalidCompanyName,
+    #[msg("Industry already has an authority transfer pending.")]
+    AuthorityTransferPending,
+    #[msg("Signer is not the pending industry authority.")]
+    NotPendingAuthority,
+    #[msg("New authority must differ from the current one.")]
+    InvalidNewAuthority,
+    #[msg("Industry still has open bids, offers or pending emission reports.")]
+    PositionsOpen,
+    #[msg("Vintage holding does not belong to the record this industry migrated from.")]
+    InvalidVintageHolding,
+    #[msg("Signer set must hold distinct wallets, at most 10, with a threshold between 1 and its size, or be empty with a zero threshold.")]
+    InvalidSignerSet,
+    #[msg("Signer is not in the industry's signer set.")]
+    NotIndustrySigner,
+    #[msg("Signer has already approved this proposal.")]
+    AlreadyApproved,
+    #[msg("Industry is governed by a signer set; an approved proposal is required.")]
+    ProposalRequired,
+    #[msg("Proposal belongs to another industry.")]
+    InvalidProposal,
+    #[msg("Proposal does not authorise this action.")]
+    ProposalMismatch,
+    #[msg("Proposal does not have enough approvals.")]
+    InsufficientApprovals,
+    #[msg("Current price is above the price the signers approved.")]
+    BidAboveApprovedPrice,
+    #[msg("Industry is governed by a signer set, which must be removed first.")]
+    MultisigGoverned,
+    #[msg("Auction settings cannot change once bids have been placed.")]
+    AuctionHasBids,
+    #[msg("Bidder position must be passed to settle a placed or revealed bid.")]
+    BidderPositionRequired,
+    #[msg("Every vintage holding of the industry must be passed to close with it.")]
+    VintageHoldingsOpen,
+    #[msg("Vintage holding belongs to a different industry.")]
+    ForeignVintageHolding,
 }
\ No newline at end of file
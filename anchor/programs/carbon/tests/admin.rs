@@ -0,0 +1,178 @@
+mod common;
+
+use anchor_lang::{error::ErrorCode, prelude::Pubkey};
+use carbon::{AdminTransferred, CarbonError, ProgramInitialized, Role, RoleGranted, RoleRevoked};
+use common::*;
+
+#[test]
+fn initialize_creates_config_and_mint() {
+    let mut protocol = Protocol::uninitialized();
+    let admin = protocol.admin;
+
+    protocol.send(protocol.initialize_ix(), &[admin, protocol.ct_mint]).unwrap();
+
+    let config = protocol.config();
+    assert_eq!(config.admin, admin);
+    assert_eq!(config.pending_admin, None);
+    assert_eq!(config.ct_mint, protocol.ct_mint);
+    assert_eq!(config.ct_token_program, anchor_spl::token::ID);
+    assert_eq!(config.treasury, protocol.treasury);
+    assert_eq!(config.compliance_period, 1);
+
+    let mint: anchor_spl::token::Mint = protocol.ctx.account(&protocol.ct_mint);
+    assert_eq!(mint.mint_authority.unwrap(), mint_authority_pda());
+    assert_eq!(mint.decimals, 9);
+
+    let events = protocol.ctx.events::<ProgramInitialized>();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].treasury, protocol.treasury);
+}
+
+#[test]
+fn initialize_only_runs_once() {
+    let mut protocol = Protocol::new();
+    protocol.ct_mint = Pubkey::new_unique();
+    let admin = protocol.admin;
+
+    assert!(protocol.send(protocol.initialize_ix(), &[admin, protocol.ct_mint]).is_err());
+}
+
+#[test]
+fn admin_handover_is_two_step() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let successor = protocol.ctx.funded_wallet(1);
+
+    protocol.send(protocol.propose_admin_ix(&admin, &successor), &[admin]).unwrap();
+    assert_eq!(protocol.config().admin, admin);
+    assert_eq!(protocol.config().pending_admin, Some(successor));
+
+    protocol.send(protocol.accept_admin_ix(&successor), &[successor]).unwrap();
+    let config = protocol.config();
+    assert_eq!(config.admin, successor);
+    assert_eq!(config.pending_admin, None);
+
+    let events = protocol.ctx.events::<AdminTransferred>();
+    assert_eq!(events[0].previous_admin, admin);
+    assert_eq!(events[0].new_admin, successor);
+
+    // The previous admin has lost its privileges
+    let result = protocol.send(protocol.propose_admin_ix(&admin, &admin), &[admin]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::Unauthorized));
+}
+
+#[test]
+fn propose_admin_requires_admin() {
+    let mut protocol = Protocol::new();
+    let intruder = protocol.ctx.funded_wallet(1);
+
+    let result = protocol.send(protocol.propose_admin_ix(&intruder, &intruder), &[intruder]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::Unauthorized));
+}
+
+#[test]
+fn accept_admin_requires_pending_admin() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let successor = protocol.ctx.funded_wallet(1);
+    let intruder = protocol.ctx.funded_wallet(1);
+
+    let result = protocol.send(protocol.accept_admin_ix(&successor), &[successor]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::NotPendingAdmin));
+
+    protocol.send(protocol.propose_admin_ix(&admin, &successor), &[admin]).unwrap();
+    let result = protocol.send(protocol.accept_admin_ix(&intruder), &[intruder]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::NotPendingAdmin));
+}
+
+#[test]
+fn grant_and_revoke_role() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let auditor = protocol.ctx.funded_wallet(1);
+
+    protocol.send(protocol.grant_role_ix(Role::Verifier, &auditor), &[admin]).unwrap();
+    let assignment: carbon::RoleAssignment = protocol.ctx.account(&role_pda(Role::Verifier, &auditor));
+    assert_eq!(assignment.role, Role::Verifier);
+    assert_eq!(assignment.holder, auditor);
+    assert_eq!(assignment.granted_by, admin);
+    assert_eq!(protocol.ctx.events::<RoleGranted>()[0].holder, auditor);
+
+    protocol.send(protocol.revoke_role_ix(Role::Verifier, &auditor), &[admin]).unwrap();
+    assert!(!protocol.ctx.exists(&role_pda(Role::Verifier, &auditor)));
+    assert_eq!(protocol.ctx.events::<RoleRevoked>()[0].role, Role::Verifier);
+}
+
+#[test]
+fn grant_role_requires_admin() {
+    let mut protocol = Protocol::new();
+    let intruder = protocol.ctx.funded_wallet(1);
+    let mut instruction = protocol.grant_role_ix(Role::Verifier, &intruder);
+    instruction.accounts[2].pubkey = intruder;
+
+    let result = protocol.send(instruction, &[intruder]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::Unauthorized));
+}
+
+#[test]
+fn roles_are_not_interchangeable() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let operator = protocol.ctx.funded_wallet(1);
+    protocol.send(protocol.grant_role_ix(Role::AuctionOperator, &operator), &[admin]).unwrap();
+    let wallet = protocol.ctx.funded_wallet(10);
+    let industry = protocol.industry(&wallet);
+    protocol.send(protocol.register_industry_ix(&industry, "Acme Steel", MIN_BOND), &[industry.authority]).unwrap();
+
+    // An auction operator holds no verifier assignment
+    let result = protocol.send(protocol.verify_industry_ix(&operator, &industry), &[operator]);
+    assert_eq!(result.unwrap_err(), anchor_error(ErrorCode::AccountNotInitialized));
+
+    // Nor can it pass off its own assignment as a verifier one
+    let mut instruction = protocol.verify_industry_ix(&operator, &industry);
+    instruction.accounts[0].pubkey = role_pda(Role::AuctionOperator, &operator);
+    let result = protocol.send(instruction, &[operator]);
+    assert_eq!(result.unwrap_err(), anchor_error(ErrorCode::ConstraintSeeds));
+}
+
+#[test]
+fn advance_compliance_period_requires_future_deadline() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let now = protocol.ctx.now();
+
+    let result = protocol.send(protocol.advance_compliance_period_ix(now), &[admin]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::InvalidDeadline));
+
+    protocol.send(protocol.advance_compliance_period_ix(now + 3_600), &[admin]).unwrap();
+    let config = protocol.config();
+    assert_eq!(config.compliance_period, 2);
+    assert_eq!(config.period_deadline, now + 3_600);
+}
+
+#[test]
+fn compliance_terms_require_future_deadline() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let now = protocol.ctx.now();
+
+    // A deadline already behind us would make every non-compliant industry slashable at once
+    for deadline in [0, now - 1, now] {
+        let result = protocol.send(protocol.set_compliance_terms_ix(deadline, 1), &[admin]);
+        assert_eq!(result.unwrap_err(), carbon_error(CarbonError::InvalidDeadline));
+    }
+
+    protocol.send(protocol.set_compliance_terms_ix(now + 3_600, 1), &[admin]).unwrap();
+    assert_eq!(protocol.config().period_deadline, now + 3_600);
+}
+
+#[test]
+fn compliance_terms_require_admin() {
+    let mut protocol = Protocol::new();
+    let intruder = protocol.ctx.funded_wallet(1);
+    let mut instruction = protocol.set_compliance_terms_ix(protocol.ctx.now() + 3_600, 1);
+    instruction.accounts[1].pubkey = intruder;
+
+    let result = protocol.send(instruction, &[intruder]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::Unauthorized));
+}
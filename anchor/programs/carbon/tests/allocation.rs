@@ -0,0 +1,118 @@
+mod common;
+
+use anchor_lang::error::ErrorCode;
+use carbon::{CapSchedule, CarbonError, FreeAllowancesAllocated, VintageHolding};
+use common::*;
+
+fn allocated(protocol: &Protocol, compliance_period: u32) -> u64 {
+    protocol.ctx.account::<CapSchedule>(&cap_schedule_pda(compliance_period)).allocated
+}
+
+#[test]
+fn allocation_mints_free_allowances() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let industry = protocol.verified_industry("Acme Steel");
+
+    protocol.send(protocol.allocate_free_allowances_ix(&admin, &industry, 40 * CT), &[admin]).unwrap();
+
+    assert_eq!(protocol.ctx.token_balance(&industry.ct_account), 40 * CT);
+    let state = protocol.industry_state(&industry);
+    assert_eq!(state.ct_balance, 40 * CT);
+    assert_eq!(state.period_allocated, 40 * CT);
+    assert_eq!(state.total_allocated, 40 * CT);
+    assert_eq!(state.total_purchased, 0);
+    assert_eq!(allocated(&protocol, 1), 40 * CT);
+
+    let holding: VintageHolding = protocol.ctx.account(&vintage_pda(&industry.account, 1));
+    assert_eq!(holding.amount, 40 * CT);
+
+    let events = protocol.ctx.events::<FreeAllowancesAllocated>();
+    assert_eq!(events[0].industry, industry.account);
+    assert_eq!(events[0].compliance_period, 1);
+    assert_eq!(events[0].amount, 40 * CT);
+}
+
+#[test]
+fn allocations_are_recorded_per_period() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let industry = protocol.verified_industry("Acme Steel");
+    protocol.send(protocol.allocate_free_allowances_ix(&admin, &industry, 40 * CT), &[admin]).unwrap();
+
+    let deadline = protocol.ctx.now() + 3_600;
+    protocol.send(protocol.advance_compliance_period_ix(deadline), &[admin]).unwrap();
+    protocol.open_cap_schedule(2);
+    protocol.send(protocol.allocate_free_allowances_ix(&admin, &industry, 15 * CT), &[admin]).unwrap();
+
+    let state = protocol.industry_state(&industry);
+    assert_eq!(state.compliance_period, 2);
+    assert_eq!(state.period_allocated, 15 * CT);
+    assert_eq!(state.total_allocated, 55 * CT);
+    assert_eq!(allocated(&protocol, 1), 40 * CT);
+    assert_eq!(allocated(&protocol, 2), 15 * CT);
+    assert_eq!(protocol.ctx.account::<VintageHolding>(&vintage_pda(&industry.account, 2)).amount, 15 * CT);
+}
+
+#[test]
+fn allocation_counts_against_the_free_allocation_cap() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let industry = protocol.verified_industry("Acme Steel");
+    protocol.send(protocol.set_cap_schedule_ix(1, DEFAULT_CAP, 50 * CT, 0), &[admin]).unwrap();
+
+    protocol.send(protocol.allocate_free_allowances_ix(&admin, &industry, 30 * CT), &[admin]).unwrap();
+    let result = protocol.send(protocol.allocate_free_allowances_ix(&admin, &industry, 21 * CT), &[admin]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::CapExceeded));
+    protocol.send(protocol.allocate_free_allowances_ix(&admin, &industry, 20 * CT), &[admin]).unwrap();
+
+    // Allocated allowances cannot be given back by lowering the cap
+    let result = protocol.send(protocol.set_cap_schedule_ix(1, DEFAULT_CAP, 40 * CT, 0), &[admin]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::CapBelowIssued));
+}
+
+#[test]
+fn allocation_requires_an_eligible_industry() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+
+    let wallet = protocol.ctx.funded_wallet(10);
+    let unverified = protocol.industry(&wallet);
+    protocol.send(protocol.register_industry_ix(&unverified, "Initech", MIN_BOND), &[wallet]).unwrap();
+    protocol.send(protocol.create_ct_account_ix(&wallet, &wallet), &[wallet]).unwrap();
+    let result = protocol.send(protocol.allocate_free_allowances_ix(&admin, &unverified, CT), &[admin]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::NotVerified));
+
+    let industry = protocol.verified_industry("Acme Steel");
+    let result = protocol.send(protocol.allocate_free_allowances_ix(&admin, &industry, 0), &[admin]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::InvalidTokenAmount));
+
+    protocol.send(protocol.set_industry_suspension_ix(&admin, &industry, true), &[admin]).unwrap();
+    let result = protocol.send(protocol.allocate_free_allowances_ix(&admin, &industry, CT), &[admin]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::IndustrySuspended));
+}
+
+#[test]
+fn allocation_requires_regulator() {
+    let mut protocol = Protocol::new();
+    let industry = protocol.verified_industry("Acme Steel");
+    let intruder = protocol.ctx.funded_wallet(1);
+
+    let result = protocol.send(protocol.allocate_free_allowances_ix(&intruder, &industry, CT), &[intruder]);
+    assert_eq!(result.unwrap_err(), anchor_error(ErrorCode::AccountNotInitialized));
+}
+
+#[test]
+fn allocated_allowances_can_be_surrendered() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let industry = protocol.verified_industry("Acme Steel");
+    protocol.send(protocol.allocate_free_allowances_ix(&admin, &industry, 40 * CT), &[admin]).unwrap();
+    protocol.verified_report(&industry, 25 * CT);
+
+    protocol.send(protocol.burn_ix(&industry, 25 * CT), &[industry.authority]).unwrap();
+
+    let state = protocol.industry_state(&industry);
+    assert_eq!(state.ct_balance, 15 * CT);
+    assert_eq!(state.outstanding_obligation(), 0);
+}
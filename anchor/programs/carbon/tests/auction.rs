@@ -0,0 +1,493 @@
+mod common;
+
+use anchor_lang::{
+    error::ErrorCode,
+    prelude::{Pubkey, Rent},
+};
+use carbon::{
+    AuctionCancelled, AuctionCreated, AuctionFinalized, AuctionStatus, BidPlaced, BidRefunded, BidStatus, BidderCapSet,
+    CarbonError,
+    ProceedsWithdrawn, Role, TokensClaimed, VintageHolding,
+};
+use common::*;
+
+const BATCH: u32 = 1;
+
+/// Creates the standard auction for `BATCH` and returns its start time.
+fn open_auction(protocol: &mut Protocol) -> i64 {
+    let admin = protocol.admin;
+    protocol.send(protocol.create_auction_ix(&admin, AuctionParams::standard(BATCH)), &[admin]).unwrap();
+    protocol.ctx.now()
+}
+
+/// Places a bid for `token_amount` CT and returns the bid address.
+fn bid(protocol: &mut Protocol, industry: &Industry, token_amount: u64, timestamp: u64) -> Pubkey {
+    protocol.send(protocol.place_bid_ix(BATCH, industry, token_amount, timestamp), &[industry.authority]).unwrap();
+    bid_pda(&auction_pda(BATCH), &industry.authority, timestamp)
+}
+
+#[test]
+fn create_auction() {
+    let mut protocol = Protocol::new();
+    let start = open_auction(&mut protocol);
+
+    let auction = protocol.auction_state(BATCH);
+    assert_eq!(auction.status, AuctionStatus::Active);
+    assert_eq!(auction.total_tokens, 1_000 * CT);
+    assert_eq!(auction.tokens_remaining, 1_000 * CT);
+    assert_eq!(auction.current_price, 10_000_000);
+    assert_eq!(auction.start_time, start);
+    assert_eq!(auction.end_time, start + 100);
+    assert_eq!(protocol.ctx.events::<AuctionCreated>()[0].batch_number, BATCH);
+}
+
+#[test]
+fn create_auction_validates_parameters() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let cases = [
+        (AuctionParams { reserve_price: 10_000_000, ..AuctionParams::standard(BATCH) }, CarbonError::InvalidPricing),
+        (AuctionParams { duration_seconds: 0, ..AuctionParams::standard(BATCH) }, CarbonError::InvalidDuration),
+        (AuctionParams { total_tokens: 0, ..AuctionParams::standard(BATCH) }, CarbonError::InvalidTokenAmount),
+        (AuctionParams { duration_seconds: i64::MAX, ..AuctionParams::standard(BATCH) }, CarbonError::MathOverflow),
+    ];
+
+    for (params, error) in cases {
+        let result = protocol.send(protocol.create_auction_ix(&admin, params), &[admin]);
+        assert_eq!(result.unwrap_err(), carbon_error(error));
+    }
+}
+
+#[test]
+fn price_decays_linearly_to_reserve() {
+    let mut protocol = Protocol::new();
+    let industry = protocol.verified_industry("Acme Steel");
+    let start = open_auction(&mut protocol);
+
+    for (elapsed, expected_price) in [(0, 10_000_000), (25, 8_750_000), (50, 7_500_000), (99, 5_050_000)] {
+        protocol.ctx.warp_to(start + elapsed);
+        let bid = bid(&mut protocol, &industry, CT, elapsed as u64);
+
+        let state: carbon::Bid = protocol.ctx.account(&bid);
+        assert_eq!(state.price_per_token, expected_price);
+        assert_eq!(state.total_cost, expected_price);
+        assert_eq!(protocol.auction_state(BATCH).current_price, expected_price);
+    }
+}
+
+#[test]
+fn place_bid_escrows_payment() {
+    let mut protocol = Protocol::new();
+    let industry = protocol.verified_industry("Acme Steel");
+    let start = open_auction(&mut protocol);
+    protocol.ctx.warp_to(start + 25);
+
+    let balance_before = protocol.ctx.lamports(&industry.authority);
+    let bid = bid(&mut protocol, &industry, 100 * CT, 1);
+
+    assert_eq!(protocol.ctx.lamports(&escrow_pda(&auction_pda(BATCH))), 875_000_000);
+    assert!(protocol.ctx.lamports(&industry.authority) < balance_before - 875_000_000);
+
+    let state: carbon::Bid = protocol.ctx.account(&bid);
+    assert_eq!(state.bidder, industry.authority);
+    assert_eq!(state.industry, industry.account);
+    assert_eq!(state.token_amount, 100 * CT);
+    assert_eq!(state.status, BidStatus::Pending);
+
+    let auction = protocol.auction_state(BATCH);
+    assert_eq!(auction.tokens_remaining, 900 * CT);
+    assert_eq!(auction.total_raised, 875_000_000);
+    assert_eq!(auction.participant_count, 1);
+
+    let events = protocol.ctx.events::<BidPlaced>();
+    assert_eq!(events[0].bid, bid);
+    assert_eq!(events[0].total_cost, 875_000_000);
+}
+
+#[test]
+fn place_bid_validates_bidder_and_amount() {
+    let mut protocol = Protocol::new();
+    let industry = protocol.verified_industry("Acme Steel");
+    let wallet = protocol.ctx.funded_wallet(10);
+    let unverified = protocol.industry(&wallet);
+    protocol.send(protocol.register_industry_ix(&unverified, "Globex Cement", MIN_BOND), &[wallet]).unwrap();
+    open_auction(&mut protocol);
+
+    let result = protocol.send(protocol.place_bid_ix(BATCH, &unverified, CT, 1), &[wallet]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::NotVerified));
+
+    let result = protocol.send(protocol.place_bid_ix(BATCH, &industry, 0, 1), &[industry.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::InvalidTokenAmount));
+
+    let result = protocol.send(protocol.place_bid_ix(BATCH, &industry, 1_001 * CT, 1), &[industry.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::InsufficientTokens));
+}
+
+#[test]
+fn place_bid_rejected_after_end() {
+    let mut protocol = Protocol::new();
+    let industry = protocol.verified_industry("Acme Steel");
+    let start = open_auction(&mut protocol);
+    protocol.ctx.warp_to(start + 100);
+
+    let result = protocol.send(protocol.place_bid_ix(BATCH, &industry, CT, 1), &[industry.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::AuctionEnded));
+}
+
+#[test]
+fn bidder_cap_limits_cumulative_bids() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let acme = protocol.verified_industry("Acme Steel");
+    let globex = protocol.verified_industry("Globex Cement");
+    open_auction(&mut protocol);
+    protocol.send(protocol.set_bidder_cap_ix(&admin, BATCH, 300 * CT, 0), &[admin]).unwrap();
+    assert_eq!(protocol.ctx.events::<BidderCapSet>()[0].bidder_cap, 300 * CT);
+
+    bid(&mut protocol, &acme, 200 * CT, 1);
+    bid(&mut protocol, &acme, 100 * CT, 2);
+    let position: carbon::BidderPosition = protocol.ctx.account(&position_pda(&auction_pda(BATCH), &acme.authority));
+    assert_eq!(position.bidder, acme.authority);
+    assert_eq!(position.tokens_bid, 300 * CT);
+
+    let result = protocol.send(protocol.place_bid_ix(BATCH, &acme, 1, 3), &[acme.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::BidderCapExceeded));
+
+    // The cap is per bidder, so others can still buy
+    bid(&mut protocol, &globex, 300 * CT, 1);
+}
+
+#[test]
+fn bidder_share_cap_applies_with_absolute_cap() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let industry = protocol.verified_industry("Acme Steel");
+    open_auction(&mut protocol);
+
+    // 25% of 1000 CT is stricter than the absolute 400 CT
+    protocol.send(protocol.set_bidder_cap_ix(&admin, BATCH, 400 * CT, 2_500), &[admin]).unwrap();
+    assert_eq!(protocol.auction_state(BATCH).bidder_cap(), 250 * CT);
+
+    let result = protocol.send(protocol.place_bid_ix(BATCH, &industry, 251 * CT, 1), &[industry.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::BidderCapExceeded));
+    bid(&mut protocol, &industry, 250 * CT, 1);
+}
+
+#[test]
+fn bidder_cap_is_fixed_once_bidding_starts() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let industry = protocol.verified_industry("Acme Steel");
+    open_auction(&mut protocol);
+
+    let result = protocol.send(protocol.set_bidder_cap_ix(&admin, BATCH, 0, 10_001), &[admin]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::InvalidBidderCap));
+
+    bid(&mut protocol, &industry, CT, 1);
+    let result = protocol.send(protocol.set_bidder_cap_ix(&admin, BATCH, CT, 0), &[admin]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::AuctionHasBids));
+}
+
+#[test]
+fn sold_out_auction_completes_early() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let industry = protocol.verified_industry("Acme Steel");
+    let start = open_auction(&mut protocol);
+    protocol.ctx.warp_to(start + 50);
+
+    bid(&mut protocol, &industry, 1_000 * CT, 1);
+    assert_eq!(protocol.auction_state(BATCH).status, AuctionStatus::Completed);
+
+    let result = protocol.send(protocol.place_bid_ix(BATCH, &industry, CT, 2), &[industry.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::AuctionNotActive));
+
+    // No need to wait for the end time once every token is sold; the last price clears
+    protocol.send(protocol.finalize_auction_ix(&admin, BATCH), &[admin]).unwrap();
+    let auction = protocol.auction_state(BATCH);
+    assert_eq!(auction.status, AuctionStatus::Finalized);
+    assert_eq!(auction.current_price, 7_500_000);
+    assert_eq!(auction.tokens_sold, 1_000 * CT);
+}
+
+#[test]
+fn finalize_waits_for_end_time() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let industry = protocol.verified_industry("Acme Steel");
+    let start = open_auction(&mut protocol);
+    bid(&mut protocol, &industry, 100 * CT, 1);
+
+    protocol.ctx.warp_to(start + 99);
+    let result = protocol.send(protocol.finalize_auction_ix(&admin, BATCH), &[admin]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::AuctionNotEnded));
+
+    protocol.ctx.warp_to(start + 100);
+    protocol.send(protocol.finalize_auction_ix(&admin, BATCH), &[admin]).unwrap();
+
+    let auction = protocol.auction_state(BATCH);
+    assert_eq!(auction.status, AuctionStatus::Finalized);
+    assert_eq!(auction.current_price, 5_000_000);
+    assert_eq!(auction.tokens_sold, 100 * CT);
+
+    let events = protocol.ctx.events::<AuctionFinalized>();
+    assert_eq!(events[0].clearing_price, 5_000_000);
+    assert_eq!(events[0].total_raised, 1_000_000_000);
+
+    let result = protocol.send(protocol.finalize_auction_ix(&admin, BATCH), &[admin]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::InvalidAuctionStatus));
+}
+
+#[test]
+fn claim_mints_tokens_and_refunds_difference() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let industry = protocol.verified_industry("Acme Steel");
+    let start = open_auction(&mut protocol);
+    protocol.ctx.warp_to(start + 25);
+    let bid = bid(&mut protocol, &industry, 100 * CT, 1);
+
+    let result = protocol.send(protocol.claim_tokens_ix(BATCH, &industry, &bid), &[industry.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::AuctionNotFinalized));
+
+    protocol.ctx.warp_to(start + 100);
+    protocol.send(protocol.finalize_auction_ix(&admin, BATCH), &[admin]).unwrap();
+
+    // Paid 0.875 SOL at 0.00875 per CT, clears at the 0.005 reserve
+    let balance_before = protocol.ctx.lamports(&industry.authority);
+    protocol.send(protocol.claim_tokens_ix(BATCH, &industry, &bid), &[industry.authority]).unwrap();
+    assert_eq!(protocol.ctx.lamports(&industry.authority), balance_before + 375_000_000 + bidder_position_rent() - vintage_holding_rent());
+    assert_eq!(protocol.ctx.token_balance(&industry.ct_account), 100 * CT);
+
+    let holding: VintageHolding = protocol.ctx.account(&vintage_pda(&industry.account, 1));
+    assert_eq!(holding.vintage, 1);
+    assert_eq!(holding.amount, 100 * CT);
+
+    let state = protocol.industry_state(&industry);
+    assert_eq!(state.ct_balance, 100 * CT);
+    assert_eq!(state.total_purchased, 100 * CT);
+    assert_eq!(protocol.ctx.account::<carbon::Bid>(&bid).status, BidStatus::Accepted);
+
+    let events = protocol.ctx.events::<TokensClaimed>();
+    assert_eq!(events[0].final_cost, 500_000_000);
+    assert_eq!(events[0].refund_amount, 375_000_000);
+
+    let result = protocol.send(protocol.claim_tokens_ix(BATCH, &industry, &bid), &[industry.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::BidAlreadyProcessed));
+}
+
+#[test]
+fn claim_requires_bidders_token_account() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let industry = protocol.verified_industry("Acme Steel");
+    let other = protocol.verified_industry("Globex Cement");
+    let start = open_auction(&mut protocol);
+    let bid = bid(&mut protocol, &industry, 100 * CT, 1);
+    protocol.ctx.warp_to(start + 100);
+    protocol.send(protocol.finalize_auction_ix(&admin, BATCH), &[admin]).unwrap();
+
+    let mut instruction = protocol.claim_tokens_ix(BATCH, &industry, &bid);
+    instruction.accounts[7].pubkey = other.ct_account;
+    let result = protocol.send(instruction, &[industry.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::InvalidTokenAccount));
+}
+
+#[test]
+fn cancel_auction_without_bids() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let industry = protocol.verified_industry("Acme Steel");
+    open_auction(&mut protocol);
+
+    protocol.send(protocol.cancel_auction_ix(&admin, BATCH), &[admin]).unwrap();
+    assert_eq!(protocol.auction_state(BATCH).status, AuctionStatus::Cancelled);
+    assert_eq!(protocol.ctx.events::<AuctionCancelled>()[0].batch_number, BATCH);
+
+    let result = protocol.send(protocol.place_bid_ix(BATCH, &industry, CT, 1), &[industry.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::AuctionNotActive));
+
+    let result = protocol.send(protocol.cancel_auction_ix(&admin, BATCH), &[admin]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::InvalidAuctionStatus));
+}
+
+#[test]
+fn cancelled_auction_refunds_bids() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let acme = protocol.verified_industry("Acme Steel");
+    let globex = protocol.verified_industry("Globex Cement");
+    let start = open_auction(&mut protocol);
+    let acme_bid = bid(&mut protocol, &acme, 100 * CT, 1);
+    protocol.ctx.warp_to(start + 50);
+    let globex_bid = bid(&mut protocol, &globex, 900 * CT, 1);
+    assert_eq!(protocol.auction_state(BATCH).status, AuctionStatus::Completed);
+
+    let result = protocol.send(protocol.refund_bid_ix(BATCH, &acme, &acme_bid), &[acme.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::AuctionNotCancelled));
+
+    // Even a sold-out auction can be aborted before it is finalized
+    protocol.send(protocol.cancel_auction_ix(&admin, BATCH), &[admin]).unwrap();
+    assert_eq!(protocol.ctx.events::<AuctionCancelled>()[0].participant_count, 2);
+
+    let result = protocol.send(protocol.claim_tokens_ix(BATCH, &acme, &acme_bid), &[acme.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::AuctionNotFinalized));
+
+    let result = protocol.send(protocol.refund_bid_ix(BATCH, &globex, &acme_bid), &[globex.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::InvalidBid));
+
+    for (industry, bid, total_cost) in [(acme, acme_bid, 1_000_000_000), (globex, globex_bid, 6_750_000_000)] {
+        let balance_before = protocol.ctx.lamports(&industry.authority);
+        protocol.send(protocol.refund_bid_ix(BATCH, &industry, &bid), &[industry.authority]).unwrap();
+        assert_eq!(protocol.ctx.lamports(&industry.authority), balance_before + total_cost + bidder_position_rent());
+        assert_eq!(protocol.ctx.account::<carbon::Bid>(&bid).status, BidStatus::Refunded);
+        assert_eq!(protocol.ctx.events::<BidRefunded>()[0].amount, total_cost);
+    }
+    assert_eq!(protocol.ctx.lamports(&escrow_pda(&auction_pda(BATCH))), 0);
+
+    let result = protocol.send(protocol.refund_bid_ix(BATCH, &acme, &acme_bid), &[acme.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::BidAlreadyProcessed));
+
+    let result = protocol.send(protocol.withdraw_proceeds_ix(&admin, BATCH, &protocol.treasury), &[admin]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::AuctionNotFinalized));
+}
+
+#[test]
+fn finalized_auction_cannot_be_cancelled() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let industry = protocol.verified_industry("Acme Steel");
+    let start = open_auction(&mut protocol);
+    let bid = bid(&mut protocol, &industry, CT, 1);
+    protocol.ctx.warp_to(start + 100);
+    protocol.send(protocol.finalize_auction_ix(&admin, BATCH), &[admin]).unwrap();
+
+    let result = protocol.send(protocol.cancel_auction_ix(&admin, BATCH), &[admin]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::InvalidAuctionStatus));
+
+    let result = protocol.send(protocol.refund_bid_ix(BATCH, &industry, &bid), &[industry.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::AuctionNotCancelled));
+}
+
+#[test]
+fn withdraw_proceeds_to_treasury() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let treasury = protocol.treasury;
+    let industry = protocol.verified_industry("Acme Steel");
+    let start = open_auction(&mut protocol);
+    let bid = bid(&mut protocol, &industry, 100 * CT, 1);
+
+    let result = protocol.send(protocol.withdraw_proceeds_ix(&admin, BATCH, &treasury), &[admin]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::AuctionNotFinalized));
+
+    protocol.ctx.warp_to(start + 100);
+    protocol.send(protocol.finalize_auction_ix(&admin, BATCH), &[admin]).unwrap();
+    protocol.send(protocol.claim_tokens_ix(BATCH, &industry, &bid), &[industry.authority]).unwrap();
+
+    let result = protocol.send(protocol.withdraw_proceeds_ix(&admin, BATCH, &admin), &[admin]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::InvalidTreasury));
+
+    protocol.send(protocol.withdraw_proceeds_ix(&admin, BATCH, &treasury), &[admin]).unwrap();
+    let escrow = escrow_pda(&auction_pda(BATCH));
+    let withdrawn = protocol.ctx.lamports(&treasury);
+    assert_eq!(withdrawn, 500_000_000 - Rent::default().minimum_balance(0));
+    assert_eq!(protocol.ctx.events::<ProceedsWithdrawn>()[0].amount, withdrawn);
+    assert_eq!(protocol.ctx.events::<ProceedsWithdrawn>()[0].escrow, escrow);
+
+    let result = protocol.send(protocol.withdraw_proceeds_ix(&admin, BATCH, &treasury), &[admin]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::InsufficientFunds));
+}
+
+#[test]
+fn proceeds_wait_for_every_bid_to_settle() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let treasury = protocol.treasury;
+    let industry = protocol.verified_industry("Acme Steel");
+    let start = open_auction(&mut protocol);
+    protocol.ctx.warp_to(start + 25);
+    let bid = bid(&mut protocol, &industry, 100 * CT, 1);
+    protocol.ctx.warp_to(start + 100);
+    protocol.send(protocol.finalize_auction_ix(&admin, BATCH), &[admin]).unwrap();
+
+    // The escrow still owes the bidder 0.375 SOL of its 0.875 SOL payment
+    let result = protocol.send(protocol.withdraw_proceeds_ix(&admin, BATCH, &treasury), &[admin]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::BidsOutstanding));
+
+    let balance_before = protocol.ctx.lamports(&industry.authority);
+    protocol.send(protocol.claim_tokens_ix(BATCH, &industry, &bid), &[industry.authority]).unwrap();
+    assert_eq!(protocol.ctx.lamports(&industry.authority), balance_before + 375_000_000 + bidder_position_rent() - vintage_holding_rent());
+
+    protocol.send(protocol.withdraw_proceeds_ix(&admin, BATCH, &treasury), &[admin]).unwrap();
+    assert_eq!(protocol.ctx.lamports(&treasury), 500_000_000 - Rent::default().minimum_balance(0));
+}
+
+#[test]
+fn operator_settles_a_bid_its_bidder_never_claims() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let treasury = protocol.treasury;
+    let intruder = protocol.ctx.funded_wallet(1);
+    let industry = protocol.verified_industry("Acme Steel");
+    let start = open_auction(&mut protocol);
+    protocol.ctx.warp_to(start + 25);
+    let bid = bid(&mut protocol, &industry, 100 * CT, 1);
+    protocol.ctx.warp_to(start + 100);
+    protocol.send(protocol.finalize_auction_ix(&admin, BATCH), &[admin]).unwrap();
+
+    let mut instruction = protocol.claim_tokens_for_ix(&intruder, BATCH, &industry, &bid);
+    let result = protocol.send(instruction.clone(), &[intruder]);
+    assert_eq!(result.unwrap_err(), anchor_error(ErrorCode::AccountNotInitialized));
+    instruction.accounts.iter_mut().find(|meta| meta.pubkey == role_pda(Role::AuctionOperator, &intruder)).unwrap().pubkey = carbon::ID;
+    let result = protocol.send(instruction, &[intruder]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::Unauthorized));
+
+    // The bidder is refunded and credited exactly as if it had claimed; the operator pays the holding's rent
+    let balance_before = protocol.ctx.lamports(&industry.authority);
+    protocol.send(protocol.claim_tokens_for_ix(&admin, BATCH, &industry, &bid), &[admin]).unwrap();
+    assert_eq!(protocol.ctx.lamports(&industry.authority), balance_before + 375_000_000 + bidder_position_rent());
+    assert_eq!(protocol.ctx.token_balance(&industry.ct_account), 100 * CT);
+    assert_eq!(protocol.ctx.account::<carbon::Bid>(&bid).status, BidStatus::Accepted);
+
+    protocol.send(protocol.withdraw_proceeds_ix(&admin, BATCH, &treasury), &[admin]).unwrap();
+    assert_eq!(protocol.ctx.lamports(&treasury), 500_000_000 - Rent::default().minimum_balance(0));
+    protocol.send(protocol.close_auction_ix(&admin, BATCH, &admin), &[admin]).unwrap();
+    assert!(!protocol.ctx.exists(&auction_pda(BATCH)));
+}
+
+#[test]
+fn operator_refunds_bids_of_a_cancelled_auction() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let industry = protocol.verified_industry("Acme Steel");
+    open_auction(&mut protocol);
+    let bid = bid(&mut protocol, &industry, 100 * CT, 1);
+    protocol.send(protocol.cancel_auction_ix(&admin, BATCH), &[admin]).unwrap();
+
+    let balance_before = protocol.ctx.lamports(&industry.authority);
+    protocol.send(protocol.refund_bid_for_ix(&admin, BATCH, &industry, &bid), &[admin]).unwrap();
+    assert_eq!(protocol.ctx.lamports(&industry.authority), balance_before + 1_000_000_000 + bidder_position_rent());
+    protocol.send(protocol.close_auction_ix(&admin, BATCH, &admin), &[admin]).unwrap();
+}
+
+#[test]
+fn auction_instructions_require_operator_role() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let intruder = protocol.ctx.funded_wallet(1);
+    open_auction(&mut protocol);
+
+    let result = protocol.send(protocol.create_auction_ix(&intruder, AuctionParams::standard(2)), &[intruder]);
+    assert_eq!(result.unwrap_err(), anchor_error(ErrorCode::AccountNotInitialized));
+
+    let result = protocol.send(protocol.cancel_auction_ix(&intruder, BATCH), &[intruder]);
+    assert_eq!(result.unwrap_err(), anchor_error(ErrorCode::AccountNotInitialized));
+
+    // A treasurer cannot stand in for an auction operator
+    protocol.send(protocol.grant_role_ix(Role::Treasurer, &intruder), &[admin]).unwrap();
+    let mut instruction = protocol.finalize_auction_ix(&intruder, BATCH);
+    instruction.accounts[1].pubkey = role_pda(Role::Treasurer, &intruder);
+    let result = protocol.send(instruction, &[intruder]);
+    assert_eq!(result.unwrap_err(), anchor_error(ErrorCode::ConstraintSeeds));
+}
@@ -0,0 +1,266 @@
+mod common;
+
+use anchor_lang::error::ErrorCode;
+use carbon::{
+    CarbonError, ComplianceStatus, IndustryAuthorityProposed, IndustryAuthorityTransferCancelled, IndustryAuthorityTransferred,
+    IndustryProfileUpdated, VintageHoldingMigrated,
+};
+use common::*;
+
+/// A fresh wallet for `industry` to move to, with its CT account already open.
+fn successor(protocol: &mut Protocol) -> Industry {
+    let authority = protocol.ctx.funded_wallet(10);
+    protocol.send(protocol.create_ct_account_ix(&authority, &authority), &[authority]).unwrap();
+    protocol.industry(&authority)
+}
+
+/// A verified industry with a 2 SOL bond and 100 CT of the first vintage.
+fn bonded_industry(protocol: &mut Protocol) -> Industry {
+    let industry = protocol.verified_industry("Acme Steel");
+    protocol.send(protocol.deposit_bond_ix(&industry, 2 * MIN_BOND), &[industry.authority]).unwrap();
+    protocol.buy_credits(1, &industry, 100 * CT);
+    industry
+}
+
+#[test]
+fn update_industry_profile_renames_without_reverification() {
+    let mut protocol = Protocol::new();
+    let industry = protocol.verified_industry("Acme Steel");
+
+    protocol.send(protocol.update_industry_profile_ix(&industry, "Acme Steel Holdings"), &[industry.authority]).unwrap();
+
+    let state = protocol.industry_state(&industry);
+    assert_eq!(state.company_name, "Acme Steel Holdings");
+    assert!(state.verified);
+
+    let events = protocol.ctx.events::<IndustryProfileUpdated>();
+    assert_eq!(events[0].industry, industry.account);
+    assert_eq!(events[0].previous_name, "Acme Steel");
+    assert_eq!(events[0].company_name, "Acme Steel Holdings");
+
+    for name in [String::new(), "x".repeat(101)] {
+        let result = protocol.send(protocol.update_industry_profile_ix(&industry, &name), &[industry.authority]);
+        assert_eq!(result.unwrap_err(), carbon_error(CarbonError::InvalidCompanyName));
+    }
+
+    let intruder = protocol.ctx.funded_wallet(1);
+    let mut instruction = protocol.update_industry_profile_ix(&industry, "Hijacked");
+    instruction.accounts[1].pubkey = intruder;
+    let result = protocol.send(instruction, &[intruder]);
+    assert_eq!(result.unwrap_err(), anchor_error(ErrorCode::ConstraintSeeds));
+}
+
+#[test]
+fn authority_transfer_migrates_record_bond_and_ct() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let industry = bonded_industry(&mut protocol);
+    let next = successor(&mut protocol);
+    let bond = protocol.ctx.lamports(&industry.bond_vault);
+    let previous_state = protocol.industry_state(&industry);
+
+    protocol.send(protocol.propose_industry_authority_ix(&industry, &next.authority), &[industry.authority]).unwrap();
+
+    assert_eq!(protocol.ctx.token_balance(&industry.ct_account), 0);
+    assert_eq!(protocol.ctx.token_balance(&transfer_escrow_pda(&industry.account)), 100 * CT);
+    let state = protocol.industry_state(&industry);
+    assert_eq!(state.pending_authority, Some(next.authority));
+    assert_eq!(state.ct_balance, 0);
+    let events = protocol.ctx.events::<IndustryAuthorityProposed>();
+    assert_eq!(events[0].pending_authority, next.authority);
+    assert_eq!(events[0].ct_escrowed, 100 * CT);
+
+    protocol.send(protocol.accept_industry_authority_ix(&industry, &next), &[next.authority]).unwrap();
+
+    assert!(!protocol.ctx.exists(&industry.account));
+    assert!(!protocol.ctx.exists(&industry.bond_vault));
+    assert!(!protocol.ctx.exists(&transfer_escrow_pda(&industry.account)));
+    assert_eq!(protocol.ctx.lamports(&next.bond_vault), bond);
+    assert_eq!(protocol.ctx.token_balance(&next.ct_account), 100 * CT);
+
+    let state = protocol.industry_state(&next);
+    assert_eq!(state.authority, next.authority);
+    assert_eq!(state.company_name, previous_state.company_name);
+    assert_eq!(state.bond_amount, previous_state.bond_amount);
+    assert_eq!(state.total_purchased, 100 * CT);
+    assert_eq!(state.ct_balance, 100 * CT);
+    assert_eq!(state.pending_authority, None);
+    assert_eq!(state.migrated_from, Some(industry.account));
+    assert!(!state.verified);
+
+    let events = protocol.ctx.events::<IndustryAuthorityTransferred>();
+    assert_eq!(events[0].previous_industry, industry.account);
+    assert_eq!(events[0].industry, next.account);
+    assert_eq!(events[0].bond_migrated, bond);
+    assert_eq!(events[0].ct_migrated, 100 * CT);
+
+    // The vintage holding follows, after which the re-verified authority can surrender and withdraw
+    protocol.send(protocol.verify_industry_ix(&admin, &next), &[admin]).unwrap();
+    protocol.send(protocol.migrate_vintage_holding_ix(&industry, &next, 1), &[next.authority]).unwrap();
+    assert!(!protocol.ctx.exists(&vintage_pda(&industry.account, 1)));
+    let holding: carbon::VintageHolding = protocol.ctx.account(&vintage_pda(&next.account, 1));
+    assert_eq!(holding.amount, 100 * CT);
+    let events = protocol.ctx.events::<VintageHoldingMigrated>();
+    assert_eq!(events[0].previous_industry, industry.account);
+    assert_eq!(events[0].amount, 100 * CT);
+
+    protocol.verified_report(&next, 40 * CT);
+    protocol.send(protocol.burn_ix(&next, 40 * CT), &[next.authority]).unwrap();
+    protocol.send(protocol.withdraw_bond_ix(&next, MIN_BOND), &[next.authority]).unwrap();
+    assert_eq!(protocol.ctx.lamports(&next.bond_vault), bond - MIN_BOND);
+}
+
+#[test]
+fn new_authority_must_be_verified_again() {
+    let mut protocol = Protocol::with_transfer_hook();
+    let admin = protocol.admin;
+    let industry = bonded_industry(&mut protocol);
+    let next = successor(&mut protocol);
+
+    protocol.send(protocol.propose_industry_authority_ix(&industry, &next.authority), &[industry.authority]).unwrap();
+    protocol.send(protocol.accept_industry_authority_ix(&industry, &next), &[next.authority]).unwrap();
+
+    // CT crossed the transfer hook before verification was withdrawn
+    assert_eq!(protocol.ctx.token_balance(&next.ct_account), 100 * CT);
+    let state = protocol.industry_state(&next);
+    assert!(!state.verified);
+    assert_eq!(state.compliance_status, ComplianceStatus::Pending);
+
+    let outsider = protocol.verified_industry("Globex Cement");
+    let result = protocol.send(protocol.transfer_ct_ix(&next.authority, &outsider.authority, CT), &[next.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::TransferNotPermitted));
+
+    protocol.send(protocol.verify_industry_ix(&admin, &next), &[admin]).unwrap();
+    protocol.send(protocol.transfer_ct_ix(&next.authority, &outsider.authority, CT), &[next.authority]).unwrap();
+    assert_eq!(protocol.ctx.token_balance(&outsider.ct_account), CT);
+}
+
+#[test]
+fn cancelled_transfer_returns_escrowed_ct() {
+    let mut protocol = Protocol::with_transfer_hook();
+    let industry = bonded_industry(&mut protocol);
+    let next = successor(&mut protocol);
+
+    protocol.send(protocol.propose_industry_authority_ix(&industry, &next.authority), &[industry.authority]).unwrap();
+    protocol.send(protocol.cancel_industry_authority_transfer_ix(&industry), &[industry.authority]).unwrap();
+
+    assert_eq!(protocol.ctx.token_balance(&industry.ct_account), 100 * CT);
+    assert!(!protocol.ctx.exists(&transfer_escrow_pda(&industry.account)));
+    let state = protocol.industry_state(&industry);
+    assert_eq!(state.pending_authority, None);
+    assert_eq!(state.ct_balance, 100 * CT);
+
+    let events = protocol.ctx.events::<IndustryAuthorityTransferCancelled>();
+    assert_eq!(events[0].pending_authority, next.authority);
+    assert_eq!(events[0].ct_returned, 100 * CT);
+
+    let result = protocol.send(protocol.accept_industry_authority_ix(&industry, &next), &[next.authority]);
+    assert_eq!(result.unwrap_err(), anchor_error(ErrorCode::AccountNotInitialized));
+}
+
+#[test]
+fn only_the_proposed_authority_can_accept() {
+    let mut protocol = Protocol::new();
+    let industry = bonded_industry(&mut protocol);
+    let next = successor(&mut protocol);
+    let intruder = successor(&mut protocol);
+
+    let result = protocol.send(protocol.propose_industry_authority_ix(&industry, &industry.authority), &[industry.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::InvalidNewAuthority));
+
+    protocol.send(protocol.propose_industry_authority_ix(&industry, &next.authority), &[industry.authority]).unwrap();
+
+    let result = protocol.send(protocol.propose_industry_authority_ix(&industry, &intruder.authority), &[industry.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::AuthorityTransferPending));
+
+    let result = protocol.send(protocol.accept_industry_authority_ix(&industry, &intruder), &[intruder.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::NotPendingAuthority));
+
+    // A wallet that already runs an industry cannot take over another one
+    let registered = protocol.verified_industry("Globex Cement");
+    protocol.send(protocol.cancel_industry_authority_transfer_ix(&industry), &[industry.authority]).unwrap();
+    protocol.send(protocol.propose_industry_authority_ix(&industry, &registered.authority), &[industry.authority]).unwrap();
+    assert!(protocol.send(protocol.accept_industry_authority_ix(&industry, &registered), &[registered.authority]).is_err());
+}
+
+#[test]
+fn open_positions_block_the_transfer() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let industry = bonded_industry(&mut protocol);
+    let next = successor(&mut protocol);
+
+    // A report still awaiting review
+    let timestamp = protocol.ctx.now() as u64;
+    let report = emission_report_pda(&industry.account, timestamp);
+    protocol.send(protocol.submit_emission_report_ix(&industry, 10 * CT, timestamp), &[industry.authority]).unwrap();
+    assert_eq!(protocol.industry_state(&industry).open_positions, 1);
+    let result = protocol.send(protocol.propose_industry_authority_ix(&industry, &next.authority), &[industry.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::PositionsOpen));
+    protocol.send(protocol.reject_emission_report_ix(&admin, &industry, &report), &[admin]).unwrap();
+
+    // An offer resting on the book
+    protocol.send(protocol.create_offer_ix(&industry, 1, carbon::OfferSide::Sell, 1, 10 * CT, 1_000_000), &[industry.authority]).unwrap();
+    let result = protocol.send(protocol.propose_industry_authority_ix(&industry, &next.authority), &[industry.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::PositionsOpen));
+    protocol.send(protocol.cancel_offer_ix(&industry, 1, 1), &[industry.authority]).unwrap();
+
+    // A bid in an auction that has not settled
+    protocol.send(protocol.create_auction_ix(&admin, AuctionParams::standard(2)), &[admin]).unwrap();
+    let timestamp = protocol.ctx.now() as u64;
+    protocol.send(protocol.place_bid_ix(2, &industry, CT, timestamp), &[industry.authority]).unwrap();
+    let result = protocol.send(protocol.propose_industry_authority_ix(&industry, &next.authority), &[industry.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::PositionsOpen));
+    protocol.send(protocol.cancel_auction_ix(&admin, 2), &[admin]).unwrap();
+    let bid = bid_pda(&auction_pda(2), &industry.authority, timestamp);
+    protocol.send(protocol.refund_bid_ix(2, &industry, &bid), &[industry.authority]).unwrap();
+
+    assert_eq!(protocol.industry_state(&industry).open_positions, 0);
+    protocol.send(protocol.propose_industry_authority_ix(&industry, &next.authority), &[industry.authority]).unwrap();
+}
+
+#[test]
+fn pending_transfer_blocks_new_positions() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let industry = bonded_industry(&mut protocol);
+    let next = successor(&mut protocol);
+    protocol.send(protocol.create_auction_ix(&admin, AuctionParams::standard(2)), &[admin]).unwrap();
+    protocol.send(protocol.create_sealed_bid_auction_ix(&admin, SealedBidParams::standard(3)), &[admin]).unwrap();
+
+    protocol.send(protocol.propose_industry_authority_ix(&industry, &next.authority), &[industry.authority]).unwrap();
+
+    let timestamp = protocol.ctx.now() as u64;
+    let commitment = carbon::sealed_bid_commitment(&industry.authority, CT, 8_000_000, &[7; 32]);
+    for instruction in [
+        protocol.submit_emission_report_ix(&industry, 10 * CT, timestamp),
+        protocol.create_offer_ix(&industry, 1, carbon::OfferSide::Buy, 1, 10 * CT, 1_000_000),
+        protocol.place_bid_ix(2, &industry, CT, timestamp),
+        protocol.commit_bid_ix(3, &industry, commitment, 100_000_000, timestamp),
+    ] {
+        let result = protocol.send(instruction, &[industry.authority]);
+        assert_eq!(result.unwrap_err(), carbon_error(CarbonError::AuthorityTransferPending));
+    }
+
+    protocol.send(protocol.accept_industry_authority_ix(&industry, &next), &[next.authority]).unwrap();
+    assert_eq!(protocol.industry_state(&next).open_positions, 0);
+}
+
+#[test]
+fn only_holdings_of_the_previous_record_migrate() {
+    let mut protocol = Protocol::new();
+    let industry = bonded_industry(&mut protocol);
+    let other = protocol.verified_industry("Globex Cement");
+    protocol.buy_credits(2, &other, 10 * CT);
+    let next = successor(&mut protocol);
+
+    protocol.send(protocol.propose_industry_authority_ix(&industry, &next.authority), &[industry.authority]).unwrap();
+    protocol.send(protocol.accept_industry_authority_ix(&industry, &next), &[next.authority]).unwrap();
+
+    let result = protocol.send(protocol.migrate_vintage_holding_ix(&other, &next, 1), &[next.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::InvalidVintageHolding));
+
+    // Industries that never changed hands have nothing to migrate
+    let result = protocol.send(protocol.migrate_vintage_holding_ix(&industry, &other, 1), &[other.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::InvalidVintageHolding));
+}
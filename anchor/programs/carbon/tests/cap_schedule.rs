@@ -0,0 +1,98 @@
+mod common;
+
+use anchor_lang::error::ErrorCode;
+use carbon::{CapSchedule, CapScheduleSet, CarbonError};
+use common::*;
+
+fn cap_schedule(protocol: &Protocol, compliance_period: u32) -> CapSchedule {
+    protocol.ctx.account(&cap_schedule_pda(compliance_period))
+}
+
+#[test]
+fn set_cap_schedule_records_budget() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+
+    protocol.send(protocol.set_cap_schedule_ix(2, 3_000 * CT, 1_000 * CT, 0), &[admin]).unwrap();
+
+    let schedule = cap_schedule(&protocol, 2);
+    assert_eq!(schedule.compliance_period, 2);
+    assert_eq!(schedule.auction_cap, 3_000 * CT);
+    assert_eq!(schedule.free_allocation_cap, 1_000 * CT);
+    assert_eq!(schedule.total_cap(), 4_000 * CT);
+    assert_eq!(schedule.auctioned, 0);
+
+    let events = protocol.ctx.events::<CapScheduleSet>();
+    assert_eq!(events[0].compliance_period, 2);
+    assert_eq!(events[0].auction_cap, 3_000 * CT);
+}
+
+#[test]
+fn set_cap_schedule_requires_admin() {
+    let mut protocol = Protocol::new();
+    let intruder = protocol.ctx.funded_wallet(1);
+
+    let mut instruction = protocol.set_cap_schedule_ix(2, CT, CT, 0);
+    instruction.accounts[2].pubkey = intruder;
+    let result = protocol.send(instruction, &[intruder]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::Unauthorized));
+}
+
+#[test]
+fn auctions_are_bounded_by_the_auction_cap() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    protocol.send(protocol.set_cap_schedule_ix(1, 1_500 * CT, 0, 0), &[admin]).unwrap();
+
+    protocol.send(protocol.create_auction_ix(&admin, AuctionParams::standard(1)), &[admin]).unwrap();
+    assert_eq!(cap_schedule(&protocol, 1).auctioned, 1_000 * CT);
+
+    let result = protocol.send(protocol.create_auction_ix(&admin, AuctionParams::standard(2)), &[admin]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::CapExceeded));
+    let result = protocol.send(protocol.create_sealed_bid_auction_ix(&admin, SealedBidParams::standard(2)), &[admin]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::CapExceeded));
+
+    let params = AuctionParams { total_tokens: 500 * CT, ..AuctionParams::standard(2) };
+    protocol.send(protocol.create_auction_ix(&admin, params), &[admin]).unwrap();
+    assert_eq!(cap_schedule(&protocol, 1).auctioned, 1_500 * CT);
+}
+
+#[test]
+fn auctions_need_a_schedule_for_the_open_period() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let deadline = protocol.ctx.now() + 3_600;
+    protocol.send(protocol.advance_compliance_period_ix(deadline), &[admin]).unwrap();
+
+    let result = protocol.send(protocol.create_auction_ix(&admin, AuctionParams::standard(1)), &[admin]);
+    assert_eq!(result.unwrap_err(), anchor_error(ErrorCode::AccountNotInitialized));
+}
+
+#[test]
+fn cancelled_auction_returns_its_budget() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    protocol.send(protocol.set_cap_schedule_ix(1, 1_000 * CT, 0, 0), &[admin]).unwrap();
+    protocol.send(protocol.create_auction_ix(&admin, AuctionParams::standard(1)), &[admin]).unwrap();
+
+    protocol.send(protocol.cancel_auction_ix(&admin, 1), &[admin]).unwrap();
+    assert_eq!(cap_schedule(&protocol, 1).auctioned, 0);
+
+    protocol.send(protocol.create_auction_ix(&admin, AuctionParams::standard(2)), &[admin]).unwrap();
+}
+
+#[test]
+fn cap_cannot_drop_below_issued_or_change_closed_periods() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    protocol.send(protocol.create_auction_ix(&admin, AuctionParams::standard(1)), &[admin]).unwrap();
+
+    let result = protocol.send(protocol.set_cap_schedule_ix(1, 999 * CT, 0, 0), &[admin]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::CapBelowIssued));
+    protocol.send(protocol.set_cap_schedule_ix(1, 1_000 * CT, 0, 0), &[admin]).unwrap();
+
+    let deadline = protocol.ctx.now() + 3_600;
+    protocol.send(protocol.advance_compliance_period_ix(deadline), &[admin]).unwrap();
+    let result = protocol.send(protocol.set_cap_schedule_ix(1, 2_000 * CT, 0, 0), &[admin]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::CompliancePeriodClosed));
+}
@@ -0,0 +1,1466 @@
+//! PDA derivations, instruction builders and setup flows shared by the integration tests.
+
+use anchor_lang::{
+    prelude::*,
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        program_error::ProgramError,
+        program_pack::Pack,
+        sysvar,
+    },
+    system_program, InstructionData, ToAccountMetas,
+};
+use anchor_spl::{
+    associated_token::{get_associated_token_address_with_program_id, spl_associated_token_account},
+    token::spl_token,
+    token_2022::spl_token_2022,
+};
+use carbon::{CarbonError, ExitCtPolicy, IndustryAction, OfferSide, OffsetKind, PriceCurve, Role};
+
+use super::runtime::{TestContext, TransactionError, LAMPORTS_PER_SOL};
+
+pub const CT: u64 = 1_000_000_000;
+pub const MIN_BOND: u64 = LAMPORTS_PER_SOL;
+/// Budget `Protocol` gives each period's auctions, free allocations and reserve unless a test
+/// sets its own.
+pub const DEFAULT_CAP: u64 = 1_000_000 * CT;
+
+pub fn carbon_error(error: CarbonError) -> ProgramError {
+    ProgramError::Custom(u32::from(error))
+}
+
+pub fn anchor_error(error: anchor_lang::error::ErrorCode) -> ProgramError {
+    ProgramError::Custom(error as u32)
+}
+
+pub fn config_pda() -> Pubkey {
+    Pubkey::find_program_address(&[b"config"], &carbon::ID).0
+}
+
+pub fn mint_authority_pda() -> Pubkey {
+    Pubkey::find_program_address(&[b"mint_authority"], &carbon::ID).0
+}
+
+pub fn role_pda(role: Role, holder: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"role", &[role as u8], holder.as_ref()], &carbon::ID).0
+}
+
+pub fn industry_pda(authority: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"industry", authority.as_ref()], &carbon::ID).0
+}
+
+pub fn bond_vault_pda(industry: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"bond_vault", industry.as_ref()], &carbon::ID).0
+}
+
+pub fn emission_report_pda(industry: &Pubkey, timestamp: u64) -> Pubkey {
+    Pubkey::find_program_address(&[b"emission_report", industry.as_ref(), &timestamp.to_le_bytes()], &carbon::ID).0
+}
+
+pub fn auction_pda(batch_number: u32) -> Pubkey {
+    Pubkey::find_program_address(&[b"auction", &batch_number.to_le_bytes()], &carbon::ID).0
+}
+
+pub fn escrow_pda(auction: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"escrow", auction.as_ref()], &carbon::ID).0
+}
+
+pub fn escrow_token_pda(auction: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"escrow_token", auction.as_ref()], &carbon::ID).0
+}
+
+pub fn bid_pda(auction: &Pubkey, bidder: &Pubkey, timestamp: u64) -> Pubkey {
+    Pubkey::find_program_address(&[b"bid", auction.as_ref(), bidder.as_ref(), &timestamp.to_le_bytes()], &carbon::ID).0
+}
+
+pub fn position_pda(auction: &Pubkey, bidder: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"position", auction.as_ref(), bidder.as_ref()], &carbon::ID).0
+}
+
+pub fn vintage_pda(industry: &Pubkey, vintage: u32) -> Pubkey {
+    Pubkey::find_program_address(&[b"vintage", industry.as_ref(), &vintage.to_le_bytes()], &carbon::ID).0
+}
+
+/// Rent an industry pays for its holding the first time it receives CT of a vintage.
+pub fn vintage_holding_rent() -> u64 {
+    Rent::default().minimum_balance(8 + carbon::VintageHolding::INIT_SPACE)
+}
+
+/// Rent a bidder pays for its position in an auction, returned when its last bid settles.
+pub fn bidder_position_rent() -> u64 {
+    Rent::default().minimum_balance(8 + carbon::BidderPosition::INIT_SPACE)
+}
+
+pub fn cap_schedule_pda(compliance_period: u32) -> Pubkey {
+    Pubkey::find_program_address(&[b"cap_schedule", &compliance_period.to_le_bytes()], &carbon::ID).0
+}
+
+pub fn reserve_config_pda() -> Pubkey {
+    Pubkey::find_program_address(&[b"reserve_config"], &carbon::ID).0
+}
+
+pub fn reserve_sale_pda(auction: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"reserve_sale", auction.as_ref()], &carbon::ID).0
+}
+
+pub fn trader_pda(trader: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"trader", trader.as_ref()], &carbon::ID).0
+}
+
+pub fn offer_pda(maker: &Pubkey, offer_id: u64) -> Pubkey {
+    Pubkey::find_program_address(&[b"offer", maker.as_ref(), &offer_id.to_le_bytes()], &carbon::ID).0
+}
+
+pub fn offer_escrow_pda(offer: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"offer_escrow", offer.as_ref()], &carbon::ID).0
+}
+
+pub fn offer_authority_pda() -> Pubkey {
+    Pubkey::find_program_address(&[b"offer_authority"], &carbon::ID).0
+}
+
+pub fn transfer_escrow_pda(industry: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"transfer_escrow", industry.as_ref()], &carbon::ID).0
+}
+
+pub fn industry_proposal_pda(industry: &Pubkey, proposal_id: u64) -> Pubkey {
+    Pubkey::find_program_address(&[b"industry_proposal", industry.as_ref(), &proposal_id.to_le_bytes()], &carbon::ID).0
+}
+
+pub fn tombstone_pda(industry: &Pubkey, registered_at: i64) -> Pubkey {
+    Pubkey::find_program_address(&[b"tombstone", industry.as_ref(), &registered_at.to_le_bytes()], &carbon::ID).0
+}
+
+pub fn offset_project_pda(developer: &Pubkey, project_id: u64) -> Pubkey {
+    Pubkey::find_program_address(&[b"offset_project", developer.as_ref(), &project_id.to_le_bytes()], &carbon::ID).0
+}
+
+pub fn removal_report_pda(project: &Pubkey, timestamp: u64) -> Pubkey {
+    Pubkey::find_program_address(&[b"removal_report", project.as_ref(), &timestamp.to_le_bytes()], &carbon::ID).0
+}
+
+pub fn extra_account_metas_pda(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"extra-account-metas", mint.as_ref()], &carbon::ID).0
+}
+
+fn instruction(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: carbon::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// An industry wallet together with the accounts derived from it.
+#[derive(Clone, Copy, Debug)]
+pub struct Industry {
+    pub authority: Pubkey,
+    pub account: Pubkey,
+    pub bond_vault: Pubkey,
+    pub ct_account: Pubkey,
+}
+
+/// Parameters of a Dutch auction as passed to `create_auction`.
+#[derive(Clone, Copy, Debug)]
+pub struct AuctionParams {
+    pub batch_number: u32,
+    pub total_tokens: u64,
+    pub start_price: u64,
+    pub reserve_price: u64,
+    pub duration_seconds: i64,
+}
+
+impl AuctionParams {
+    /// 1000 CT decaying from 0.01 to 0.005 SOL per CT over 100 seconds.
+    pub fn standard(batch_number: u32) -> Self {
+        Self {
+            batch_number,
+            total_tokens: 1_000 * CT,
+            start_price: 10_000_000,
+            reserve_price: 5_000_000,
+            duration_seconds: 100,
+        }
+    }
+}
+
+/// Parameters of a sealed-bid auction as passed to `create_sealed_bid_auction`.
+#[derive(Clone, Copy, Debug)]
+pub struct SealedBidParams {
+    pub batch_number: u32,
+    pub total_tokens: u64,
+    pub reserve_price: u64,
+    pub duration_seconds: i64,
+    pub reveal_duration_seconds: i64,
+    pub min_collateral: u64,
+}
+
+impl SealedBidParams {
+    /// 1000 CT with a 0.005 SOL reserve, 100 seconds of bidding, 50 of revealing and
+    /// 0.1 SOL minimum collateral.
+    pub fn standard(batch_number: u32) -> Self {
+        Self {
+            batch_number,
+            total_tokens: 1_000 * CT,
+            reserve_price: 5_000_000,
+            duration_seconds: 100,
+            reveal_duration_seconds: 50,
+            min_collateral: 100_000_000,
+        }
+    }
+}
+
+/// Optional accounts an auction instruction settles through when the auction is denominated in
+/// a payment mint, with `owner`'s associated token account on the other side of the payment.
+/// All `None` for auctions paid in lamports.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PaymentAccounts {
+    pub payment_mint: Option<Pubkey>,
+    pub escrow_token: Option<Pubkey>,
+    pub token_account: Option<Pubkey>,
+    pub payment_token_program: Option<Pubkey>,
+}
+
+pub struct Protocol {
+    pub ctx: TestContext,
+    pub admin: Pubkey,
+    pub ct_mint: Pubkey,
+    pub token_program: Pubkey,
+    pub treasury: Pubkey,
+}
+
+impl Protocol {
+    /// A fresh runtime with funded admin wallet, before `initialize` has run.
+    pub fn uninitialized() -> Self {
+        let mut ctx = TestContext::new();
+        let admin = ctx.funded_wallet(100);
+
+        Self {
+            ctx,
+            admin,
+            ct_mint: Pubkey::new_unique(),
+            token_program: spl_token::ID,
+            treasury: Pubkey::new_unique(),
+        }
+    }
+
+    /// An initialized program whose admin also holds every role.
+    pub fn new() -> Self {
+        let mut protocol = Self::uninitialized();
+        let admin = protocol.admin;
+
+        protocol.send(protocol.initialize_ix(), &[admin, protocol.ct_mint]).unwrap();
+        protocol.grant_all_roles();
+        protocol.open_cap_schedule(1);
+        protocol
+    }
+
+    /// Like `new`, but with CT issued as a Token-2022 mint behind the transfer hook.
+    pub fn with_transfer_hook() -> Self {
+        let mut protocol = Self::uninitialized();
+        let admin = protocol.admin;
+        protocol.token_program = spl_token_2022::ID;
+
+        protocol.send(protocol.initialize_with_transfer_hook_ix(), &[admin, protocol.ct_mint]).unwrap();
+        protocol.grant_all_roles();
+        protocol.open_cap_schedule(1);
+        protocol
+    }
+
+    fn grant_all_roles(&mut self) {
+        let admin = self.admin;
+        for role in [Role::Verifier, Role::AuctionOperator, Role::Treasurer, Role::Regulator] {
+            self.send(self.grant_role_ix(role, &admin), &[admin]).unwrap();
+        }
+    }
+
+    /// Gives `compliance_period` the default budget of `DEFAULT_CAP` for each of auctions,
+    /// free allocations and the reserve.
+    pub fn open_cap_schedule(&mut self, compliance_period: u32) {
+        let admin = self.admin;
+        self.send(self.set_cap_schedule_ix(compliance_period, DEFAULT_CAP, DEFAULT_CAP, DEFAULT_CAP), &[admin]).unwrap();
+    }
+
+    pub fn send(&mut self, instruction: Instruction, signers: &[Pubkey]) -> std::result::Result<(), TransactionError> {
+        self.ctx.send(instruction, signers)
+    }
+
+    pub fn config(&self) -> carbon::ProgramConfig {
+        self.ctx.account(&config_pda())
+    }
+
+    pub fn industry_state(&self, industry: &Industry) -> carbon::IndustryAccount {
+        self.ctx.account(&industry.account)
+    }
+
+    pub fn auction_state(&self, batch_number: u32) -> carbon::Auction {
+        self.ctx.account(&auction_pda(batch_number))
+    }
+
+    pub fn payment_accounts(&self, batch_number: u32, owner: &Pubkey) -> PaymentAccounts {
+        let auction = auction_pda(batch_number);
+        if !self.ctx.exists(&auction) {
+            return PaymentAccounts::default();
+        }
+        let Some(payment_mint) = self.auction_state(batch_number).payment_mint else {
+            return PaymentAccounts::default();
+        };
+        let token_program = self.ctx.get_account(&payment_mint).unwrap().owner;
+
+        PaymentAccounts {
+            payment_mint: Some(payment_mint),
+            escrow_token: Some(escrow_token_pda(&auction)),
+            token_account: Some(get_associated_token_address_with_program_id(owner, &payment_mint, &token_program)),
+            payment_token_program: Some(token_program),
+        }
+    }
+
+    /// Creates an SPL Token mint with `decimals`, such as a USD stablecoin, whose mint
+    /// authority is the admin.
+    pub fn create_payment_mint(&mut self, decimals: u8) -> Pubkey {
+        let admin = self.admin;
+        let mint = Pubkey::new_unique();
+        let space = spl_token::state::Mint::LEN;
+        let instructions = [
+            anchor_lang::solana_program::system_instruction::create_account(
+                &admin,
+                &mint,
+                Rent::default().minimum_balance(space),
+                space as u64,
+                &spl_token::ID,
+            ),
+            spl_token::instruction::initialize_mint2(&spl_token::ID, &mint, &admin, None, decimals).unwrap(),
+        ];
+        self.ctx.send_all(&instructions, &[admin, mint]).unwrap();
+        mint
+    }
+
+    /// Opens `owner`'s associated token account for `mint` and mints `amount` into it.
+    pub fn fund_payment_account(&mut self, mint: &Pubkey, owner: &Pubkey, amount: u64) -> Pubkey {
+        let admin = self.admin;
+        let account = get_associated_token_address_with_program_id(owner, mint, &spl_token::ID);
+        let instructions = [
+            spl_associated_token_account::instruction::create_associated_token_account(&admin, owner, mint, &spl_token::ID),
+            spl_token::instruction::mint_to(&spl_token::ID, mint, &account, &admin, &[], amount).unwrap(),
+        ];
+        self.ctx.send_all(&instructions, &[admin]).unwrap();
+        account
+    }
+
+    pub fn initialize_ix(&self) -> Instruction {
+        instruction(
+            carbon::accounts::InitializeMint {
+                authority: self.admin,
+                config: config_pda(),
+                ct_mint: self.ct_mint,
+                mint_authority: mint_authority_pda(),
+                token_program: spl_token::ID,
+                rent: sysvar::rent::ID,
+                system_program: system_program::ID,
+            },
+            carbon::instruction::Initialize { treasury: self.treasury },
+        )
+    }
+
+    pub fn initialize_with_transfer_hook_ix(&self) -> Instruction {
+        instruction(
+            carbon::accounts::InitializeHookedMint {
+                authority: self.admin,
+                config: config_pda(),
+                ct_mint: self.ct_mint,
+                extra_account_meta_list: extra_account_metas_pda(&self.ct_mint),
+                mint_authority: mint_authority_pda(),
+                token_program: spl_token_2022::ID,
+                system_program: system_program::ID,
+            },
+            carbon::instruction::InitializeWithTransferHook { treasury: self.treasury },
+        )
+    }
+
+    pub fn propose_admin_ix(&self, admin: &Pubkey, new_admin: &Pubkey) -> Instruction {
+        instruction(
+            carbon::accounts::ProposeAdmin { config: config_pda(), admin: *admin },
+            carbon::instruction::ProposeAdmin { new_admin: *new_admin },
+        )
+    }
+
+    pub fn accept_admin_ix(&self, new_admin: &Pubkey) -> Instruction {
+        instruction(
+            carbon::accounts::AcceptAdmin { config: config_pda(), new_admin: *new_admin },
+            carbon::instruction::AcceptAdmin {},
+        )
+    }
+
+    pub fn advance_compliance_period_ix(&self, period_deadline: i64) -> Instruction {
+        instruction(
+            carbon::accounts::UpdateComplianceTerms { config: config_pda(), admin: self.admin },
+            carbon::instruction::AdvanceCompliancePeriod { period_deadline },
+        )
+    }
+
+    pub fn set_compliance_terms_ix(&self, period_deadline: i64, penalty_per_tonne: u64) -> Instruction {
+        instruction(
+            carbon::accounts::UpdateComplianceTerms { config: config_pda(), admin: self.admin },
+            carbon::instruction::SetComplianceTerms { period_deadline, penalty_per_tonne },
+        )
+    }
+
+    pub fn set_cap_schedule_ix(&self, compliance_period: u32, auction_cap: u64, free_allocation_cap: u64, reserve_cap: u64) -> Instruction {
+        instruction(
+            carbon::accounts::SetCapSchedule {
+                config: config_pda(),
+                cap_schedule: cap_schedule_pda(compliance_period),
+                admin: self.admin,
+                system_program: system_program::ID,
+            },
+            carbon::instruction::SetCapSchedule { compliance_period, auction_cap, free_allocation_cap, reserve_cap },
+        )
+    }
+
+    pub fn set_reserve_config_ix(&self, trigger_price: u64, sale_price: u64, release_amount: u64) -> Instruction {
+        instruction(
+            carbon::accounts::SetReserveConfig {
+                config: config_pda(),
+                reserve_config: reserve_config_pda(),
+                admin: self.admin,
+                system_program: system_program::ID,
+            },
+            carbon::instruction::SetReserveConfig { trigger_price, sale_price, release_amount },
+        )
+    }
+
+    pub fn set_banking_rules_ix(&self, banking_periods: Option<u32>) -> Instruction {
+        instruction(
+            carbon::accounts::UpdateComplianceTerms { config: config_pda(), admin: self.admin },
+            carbon::instruction::SetBankingRules { banking_periods },
+        )
+    }
+
+    pub fn set_exit_ct_policy_ix(&self, exit_ct_policy: ExitCtPolicy) -> Instruction {
+        instruction(
+            carbon::accounts::UpdateComplianceTerms { config: config_pda(), admin: self.admin },
+            carbon::instruction::SetExitCtPolicy { exit_ct_policy },
+        )
+    }
+
+    pub fn grant_role_ix(&self, role: Role, holder: &Pubkey) -> Instruction {
+        instruction(
+            carbon::accounts::GrantRole {
+                config: config_pda(),
+                role_assignment: role_pda(role, holder),
+                admin: self.admin,
+                system_program: system_program::ID,
+            },
+            carbon::instruction::GrantRole { role, holder: *holder },
+        )
+    }
+
+    pub fn revoke_role_ix(&self, role: Role, holder: &Pubkey) -> Instruction {
+        instruction(
+            carbon::accounts::RevokeRole {
+                config: config_pda(),
+                role_assignment: role_pda(role, holder),
+                admin: self.admin,
+            },
+            carbon::instruction::RevokeRole {},
+        )
+    }
+
+    pub fn industry(&self, authority: &Pubkey) -> Industry {
+        let account = industry_pda(authority);
+        Industry {
+            authority: *authority,
+            account,
+            bond_vault: bond_vault_pda(&account),
+            ct_account: get_associated_token_address_with_program_id(authority, &self.ct_mint, &self.token_program),
+        }
+    }
+
+    pub fn register_industry_ix(&self, industry: &Industry, company_name: &str, bond_amount: u64) -> Instruction {
+        instruction(
+            carbon::accounts::RegisterIndustry {
+                industry_account: industry.account,
+                config: config_pda(),
+                authority: industry.authority,
+                system_program: system_program::ID,
+            },
+            carbon::instruction::RegisterIndustry {
+                company_name: company_name.to_string(),
+                bond_amount,
+            },
+        )
+    }
+
+    pub fn verify_industry_ix(&self, verifier: &Pubkey, industry: &Industry) -> Instruction {
+        instruction(
+            carbon::accounts::VerifyIndustry {
+                role_assignment: role_pda(Role::Verifier, verifier),
+                verifier: *verifier,
+                industry_account: industry.account,
+            },
+            carbon::instruction::VerifyIndustry {},
+        )
+    }
+
+    pub fn deposit_bond_ix(&self, industry: &Industry, amount: u64) -> Instruction {
+        instruction(
+            carbon::accounts::DepositBond {
+                industry_account: industry.account,
+                bond_vault: industry.bond_vault,
+                authority: industry.authority,
+                system_program: system_program::ID,
+            },
+            carbon::instruction::DepositBond { amount },
+        )
+    }
+
+    pub fn withdraw_bond_ix(&self, industry: &Industry, amount: u64) -> Instruction {
+        self.approved_withdraw_bond_ix(industry, amount, None)
+    }
+
+    /// `withdraw_bond` carrying the proposal a multisig-governed industry needs.
+    pub fn approved_withdraw_bond_ix(&self, industry: &Industry, amount: u64, proposal: Option<Pubkey>) -> Instruction {
+        instruction(
+            carbon::accounts::WithdrawBond {
+                industry_account: industry.account,
+                bond_vault: industry.bond_vault,
+                config: config_pda(),
+                proposal,
+                authority: industry.authority,
+                system_program: system_program::ID,
+            },
+            carbon::instruction::WithdrawBond { amount },
+        )
+    }
+
+    /// Sets the signer set; `proposal` is the approved `UpdateSigners` proposal once one is in place.
+    pub fn set_industry_signers_ix(&self, industry: &Industry, signers: &[Pubkey], threshold: u8, proposal: Option<Pubkey>) -> Instruction {
+        instruction(
+            carbon::accounts::SetIndustrySigners {
+                industry_account: industry.account,
+                proposal,
+                authority: industry.authority,
+            },
+            carbon::instruction::SetIndustrySigners { signers: signers.to_vec(), threshold },
+        )
+    }
+
+    pub fn propose_industry_action_ix(&self, proposer: &Pubkey, industry: &Industry, proposal_id: u64, action: IndustryAction) -> Instruction {
+        instruction(
+            carbon::accounts::ProposeIndustryAction {
+                industry_account: industry.account,
+                proposal: industry_proposal_pda(&industry.account, proposal_id),
+                proposer: *proposer,
+                system_program: system_program::ID,
+            },
+            carbon::instruction::ProposeIndustryAction { proposal_id, action },
+        )
+    }
+
+    pub fn approve_industry_action_ix(&self, signer: &Pubkey, industry: &Industry, proposal_id: u64) -> Instruction {
+        instruction(
+            carbon::accounts::ApproveIndustryAction {
+                industry_account: industry.account,
+                proposal: industry_proposal_pda(&industry.account, proposal_id),
+                signer: *signer,
+            },
+            carbon::instruction::ApproveIndustryAction {},
+        )
+    }
+
+    pub fn cancel_industry_action_ix(&self, proposer: &Pubkey, industry: &Industry, proposal_id: u64) -> Instruction {
+        instruction(
+            carbon::accounts::CancelIndustryAction {
+                proposal: industry_proposal_pda(&industry.account, proposal_id),
+                proposer: *proposer,
+            },
+            carbon::instruction::CancelIndustryAction {},
+        )
+    }
+
+    pub fn update_industry_profile_ix(&self, industry: &Industry, company_name: &str) -> Instruction {
+        instruction(
+            carbon::accounts::UpdateIndustryProfile {
+                industry_account: industry.account,
+                authority: industry.authority,
+            },
+            carbon::instruction::UpdateIndustryProfile { company_name: company_name.to_string() },
+        )
+    }
+
+    pub fn propose_industry_authority_ix(&self, industry: &Industry, new_authority: &Pubkey) -> Instruction {
+        let mut instruction = instruction(
+            carbon::accounts::ProposeIndustryAuthority {
+                industry_account: industry.account,
+                config: config_pda(),
+                ct_mint: self.ct_mint,
+                industry_ct_account: industry.ct_account,
+                transfer_escrow: transfer_escrow_pda(&industry.account),
+                offer_authority: offer_authority_pda(),
+                authority: industry.authority,
+                token_program: self.token_program,
+                system_program: system_program::ID,
+            },
+            carbon::instruction::ProposeIndustryAuthority { new_authority: *new_authority },
+        );
+        instruction.accounts.extend(self.hook_accounts(&industry.authority, &offer_authority_pda()));
+        instruction
+    }
+
+    pub fn cancel_industry_authority_transfer_ix(&self, industry: &Industry) -> Instruction {
+        let mut instruction = instruction(
+            carbon::accounts::CancelIndustryAuthorityTransfer {
+                industry_account: industry.account,
+                config: config_pda(),
+                ct_mint: self.ct_mint,
+                industry_ct_account: industry.ct_account,
+                transfer_escrow: transfer_escrow_pda(&industry.account),
+                offer_authority: offer_authority_pda(),
+                authority: industry.authority,
+                token_program: self.token_program,
+            },
+            carbon::instruction::CancelIndustryAuthorityTransfer {},
+        );
+        instruction.accounts.extend(self.hook_accounts(&offer_authority_pda(), &industry.authority));
+        instruction
+    }
+
+    /// Accepts the transfer of `previous` to `industry`, the accounts derived from the new authority.
+    pub fn accept_industry_authority_ix(&self, previous: &Industry, industry: &Industry) -> Instruction {
+        let mut instruction = instruction(
+            carbon::accounts::AcceptIndustryAuthority {
+                previous_industry: previous.account,
+                previous_bond_vault: previous.bond_vault,
+                industry_account: industry.account,
+                bond_vault: industry.bond_vault,
+                config: config_pda(),
+                ct_mint: self.ct_mint,
+                transfer_escrow: transfer_escrow_pda(&previous.account),
+                industry_ct_account: industry.ct_account,
+                offer_authority: offer_authority_pda(),
+                new_authority: industry.authority,
+                token_program: self.token_program,
+                system_program: system_program::ID,
+            },
+            carbon::instruction::AcceptIndustryAuthority {},
+        );
+        instruction.accounts.extend(self.hook_accounts(&offer_authority_pda(), &industry.authority));
+        instruction
+    }
+
+    pub fn migrate_vintage_holding_ix(&self, previous: &Industry, industry: &Industry, vintage: u32) -> Instruction {
+        instruction(
+            carbon::accounts::MigrateVintageHolding {
+                industry_account: industry.account,
+                previous_holding: vintage_pda(&previous.account, vintage),
+                vintage_holding: vintage_pda(&industry.account, vintage),
+                authority: industry.authority,
+                system_program: system_program::ID,
+            },
+            carbon::instruction::MigrateVintageHolding {},
+        )
+    }
+
+    /// Vintage holdings currently open under `industry`.
+    pub fn vintage_holdings(&self, industry: &Industry) -> Vec<Pubkey> {
+        self.ctx
+            .program_accounts::<carbon::VintageHolding>()
+            .into_iter()
+            .filter(|(_, holding)| holding.industry == industry.account)
+            .map(|(key, _)| key)
+            .collect()
+    }
+
+    /// Deregisters `industry`, passing every vintage holding it has open and the treasury's
+    /// CT account when the configured policy surrenders leftover CT to it.
+    pub fn deregister_industry_ix(&self, industry: &Industry) -> Instruction {
+        let state = self.industry_state(industry);
+        let surrender = self.config().exit_ct_policy == ExitCtPolicy::Surrender;
+        let mut instruction = instruction(
+            carbon::accounts::DeregisterIndustry {
+                industry_account: industry.account,
+                bond_vault: industry.bond_vault,
+                tombstone: tombstone_pda(&industry.account, state.created_at),
+                config: config_pda(),
+                ct_mint: self.ct_mint,
+                industry_ct_account: industry.ct_account,
+                treasury_ct_account: surrender.then(|| self.ct_account(&self.treasury)),
+                authority: industry.authority,
+                token_program: self.token_program,
+                system_program: system_program::ID,
+            },
+            carbon::instruction::DeregisterIndustry {},
+        );
+        instruction.accounts.extend(
+            self.vintage_holdings(industry)
+                .into_iter()
+                .map(|holding| AccountMeta::new(holding, false)),
+        );
+        if surrender {
+            instruction.accounts.extend(self.hook_accounts(&industry.authority, &self.treasury));
+        }
+        instruction
+    }
+
+    pub fn submit_emission_report_ix(&self, industry: &Industry, co2_emitted: u64, timestamp: u64) -> Instruction {
+        instruction(
+            carbon::accounts::SubmitEmissionReport {
+                industry_account: industry.account,
+                config: config_pda(),
+                emission_report: emission_report_pda(&industry.account, timestamp),
+                authority: industry.authority,
+                system_program: system_program::ID,
+            },
+            carbon::instruction::SubmitEmissionReport {
+                co2_emitted,
+                report_period: "2025-Q1".to_string(),
+                _timestamp: timestamp,
+            },
+        )
+    }
+
+    fn review_report_accounts(&self, verifier: &Pubkey, industry: &Industry, report: &Pubkey) -> carbon::accounts::ReviewEmissionReport {
+        carbon::accounts::ReviewEmissionReport {
+            config: config_pda(),
+            role_assignment: role_pda(Role::Verifier, verifier),
+            verifier: *verifier,
+            emission_report: *report,
+            industry_account: industry.account,
+        }
+    }
+
+    pub fn verify_emission_report_ix(&self, verifier: &Pubkey, industry: &Industry, report: &Pubkey, adjusted_co2_emitted: Option<u64>) -> Instruction {
+        instruction(
+            self.review_report_accounts(verifier, industry, report),
+            carbon::instruction::VerifyEmissionReport { adjusted_co2_emitted },
+        )
+    }
+
+    pub fn reject_emission_report_ix(&self, verifier: &Pubkey, industry: &Industry, report: &Pubkey) -> Instruction {
+        instruction(
+            self.review_report_accounts(verifier, industry, report),
+            carbon::instruction::RejectEmissionReport {},
+        )
+    }
+
+    /// Burns CT of the first period's vintage, which is what `buy_credits` hands out until
+    /// the period is advanced.
+    pub fn burn_ix(&self, industry: &Industry, amount: u64) -> Instruction {
+        self.burn_vintage_ix(industry, 1, amount)
+    }
+
+    pub fn burn_vintage_ix(&self, industry: &Industry, vintage: u32, amount: u64) -> Instruction {
+        self.approved_burn_ix(industry, vintage, amount, None)
+    }
+
+    /// `burn_ct_for_compliance` carrying the proposal a multisig-governed industry needs.
+    pub fn approved_burn_ix(&self, industry: &Industry, vintage: u32, amount: u64, proposal: Option<Pubkey>) -> Instruction {
+        instruction(
+            carbon::accounts::BurnCT {
+                industry_account: industry.account,
+                vintage_holding: vintage_pda(&industry.account, vintage),
+                config: config_pda(),
+                ct_mint: self.ct_mint,
+                industry_ct_account: industry.ct_account,
+                proposal,
+                authority: industry.authority,
+                token_program: self.token_program,
+            },
+            carbon::instruction::BurnCtForCompliance { amount },
+        )
+    }
+
+    pub fn register_offset_project_ix(&self, developer: &Pubkey, project_id: u64, kind: OffsetKind) -> Instruction {
+        instruction(
+            carbon::accounts::RegisterOffsetProject {
+                offset_project: offset_project_pda(developer, project_id),
+                developer: *developer,
+                system_program: system_program::ID,
+            },
+            carbon::instruction::RegisterOffsetProject {
+                project_id,
+                name: "Amazon Reforestation".to_string(),
+                kind,
+            },
+        )
+    }
+
+    pub fn approve_offset_project_ix(&self, verifier: &Pubkey, project: &Pubkey) -> Instruction {
+        instruction(
+            carbon::accounts::ApproveOffsetProject {
+                role_assignment: role_pda(Role::Verifier, verifier),
+                verifier: *verifier,
+                offset_project: *project,
+            },
+            carbon::instruction::ApproveOffsetProject {},
+        )
+    }
+
+    pub fn submit_removal_report_ix(&self, developer: &Pubkey, project: &Pubkey, beneficiary: &Industry, co2_removed: u64, timestamp: u64) -> Instruction {
+        instruction(
+            carbon::accounts::SubmitRemovalReport {
+                offset_project: *project,
+                beneficiary: beneficiary.account,
+                removal_report: removal_report_pda(project, timestamp),
+                developer: *developer,
+                system_program: system_program::ID,
+            },
+            carbon::instruction::SubmitRemovalReport { co2_removed, _timestamp: timestamp },
+        )
+    }
+
+    pub fn issue_offset_credits_ix(&self, verifier: &Pubkey, project: &Pubkey, report: &Pubkey, beneficiary: &Industry) -> Instruction {
+        instruction(
+            carbon::accounts::IssueOffsetCredits {
+                role_assignment: role_pda(Role::Verifier, verifier),
+                verifier: *verifier,
+                offset_project: *project,
+                removal_report: *report,
+                industry_account: beneficiary.account,
+                config: config_pda(),
+                ct_mint: self.ct_mint,
+                industry_ct_account: beneficiary.ct_account,
+                mint_authority: mint_authority_pda(),
+                token_program: self.token_program,
+            },
+            carbon::instruction::IssueOffsetCredits {},
+        )
+    }
+
+    pub fn set_offset_limit_ix(&self, regulator: &Pubkey, industry: &Industry, max_offset_bps: u16) -> Instruction {
+        instruction(
+            carbon::accounts::SetOffsetLimit {
+                role_assignment: role_pda(Role::Regulator, regulator),
+                regulator: *regulator,
+                industry_account: industry.account,
+            },
+            carbon::instruction::SetOffsetLimit { max_offset_bps },
+        )
+    }
+
+    pub fn burn_offsets_ix(&self, industry: &Industry, amount: u64) -> Instruction {
+        instruction(
+            carbon::accounts::BurnOffsetCredits {
+                industry_account: industry.account,
+                config: config_pda(),
+                ct_mint: self.ct_mint,
+                industry_ct_account: industry.ct_account,
+                authority: industry.authority,
+                token_program: self.token_program,
+            },
+            carbon::instruction::BurnOffsetCreditsForCompliance { amount },
+        )
+    }
+
+    pub fn sync_ct_balance_ix(&self, industry: &Industry) -> Instruction {
+        instruction(
+            carbon::accounts::SyncCtBalance {
+                industry_account: industry.account,
+                config: config_pda(),
+                ct_mint: self.ct_mint,
+                industry_ct_account: industry.ct_account,
+                token_program: self.token_program,
+            },
+            carbon::instruction::SyncCtBalance {},
+        )
+    }
+
+    pub fn allocate_free_allowances_ix(&self, regulator: &Pubkey, industry: &Industry, amount: u64) -> Instruction {
+        let compliance_period = self.config().compliance_period;
+        instruction(
+            carbon::accounts::AllocateFreeAllowances {
+                role_assignment: role_pda(Role::Regulator, regulator),
+                regulator: *regulator,
+                industry_account: industry.account,
+                vintage_holding: vintage_pda(&industry.account, compliance_period),
+                config: config_pda(),
+                cap_schedule: cap_schedule_pda(compliance_period),
+                ct_mint: self.ct_mint,
+                industry_ct_account: industry.ct_account,
+                mint_authority: mint_authority_pda(),
+                token_program: self.token_program,
+                system_program: system_program::ID,
+            },
+            carbon::instruction::AllocateFreeAllowances { amount },
+        )
+    }
+
+    pub fn slash_bond_ix(&self, regulator: &Pubkey, industry: &Industry) -> Instruction {
+        instruction(
+            carbon::accounts::SlashBond {
+                config: config_pda(),
+                role_assignment: role_pda(Role::Regulator, regulator),
+                regulator: *regulator,
+                industry_account: industry.account,
+                bond_vault: industry.bond_vault,
+                treasury: self.treasury,
+                system_program: system_program::ID,
+            },
+            carbon::instruction::SlashBond {},
+        )
+    }
+
+    pub fn settle_period_ix(&self, regulator: &Pubkey, industry: &Industry) -> Instruction {
+        instruction(
+            carbon::accounts::SettlePeriod {
+                config: config_pda(),
+                role_assignment: role_pda(Role::Regulator, regulator),
+                regulator: *regulator,
+                industry_account: industry.account,
+                bond_vault: industry.bond_vault,
+                treasury: self.treasury,
+                system_program: system_program::ID,
+            },
+            carbon::instruction::SettlePeriod {},
+        )
+    }
+
+    pub fn create_auction_ix(&self, operator: &Pubkey, params: AuctionParams) -> Instruction {
+        instruction(
+            carbon::accounts::CreateAuction {
+                auction: auction_pda(params.batch_number),
+                config: config_pda(),
+                cap_schedule: cap_schedule_pda(self.config().compliance_period),
+                role_assignment: role_pda(Role::AuctionOperator, operator),
+                authority: *operator,
+                system_program: system_program::ID,
+            },
+            carbon::instruction::CreateAuction {
+                batch_number: params.batch_number,
+                total_tokens: params.total_tokens,
+                start_price: params.start_price,
+                reserve_price: params.reserve_price,
+                duration_seconds: params.duration_seconds,
+            },
+        )
+    }
+
+    pub fn set_bidder_cap_ix(&self, operator: &Pubkey, batch_number: u32, max_tokens_per_bidder: u64, max_bidder_share_bps: u16) -> Instruction {
+        instruction(
+            carbon::accounts::SetBidderCap {
+                auction: auction_pda(batch_number),
+                role_assignment: role_pda(Role::AuctionOperator, operator),
+                authority: *operator,
+            },
+            carbon::instruction::SetBidderCap { max_tokens_per_bidder, max_bidder_share_bps },
+        )
+    }
+
+    pub fn set_price_curve_ix(&self, operator: &Pubkey, batch_number: u32, curve: PriceCurve) -> Instruction {
+        instruction(
+            carbon::accounts::SetPriceCurve {
+                auction: auction_pda(batch_number),
+                role_assignment: role_pda(Role::AuctionOperator, operator),
+                authority: *operator,
+            },
+            carbon::instruction::SetPriceCurve { curve },
+        )
+    }
+
+    pub fn set_payment_mint_ix(&self, operator: &Pubkey, batch_number: u32, payment_mint: &Pubkey) -> Instruction {
+        let auction = auction_pda(batch_number);
+        instruction(
+            carbon::accounts::SetPaymentMint {
+                auction,
+                payment_mint: *payment_mint,
+                escrow_token: escrow_token_pda(&auction),
+                escrow: escrow_pda(&auction),
+                role_assignment: role_pda(Role::AuctionOperator, operator),
+                authority: *operator,
+                token_program: spl_token::ID,
+                system_program: system_program::ID,
+            },
+            carbon::instruction::SetPaymentMint {},
+        )
+    }
+
+    pub fn place_bid_ix(&self, batch_number: u32, industry: &Industry, token_amount: u64, timestamp: u64) -> Instruction {
+        self.approved_place_bid_ix(batch_number, industry, token_amount, timestamp, None)
+    }
+
+    /// `place_bid` carrying the proposal a multisig-governed industry needs.
+    pub fn approved_place_bid_ix(
+        &self,
+        batch_number: u32,
+        industry: &Industry,
+        token_amount: u64,
+        timestamp: u64,
+        proposal: Option<Pubkey>,
+    ) -> Instruction {
+        let auction = auction_pda(batch_number);
+        let payment = self.payment_accounts(batch_number, &industry.authority);
+        instruction(
+            carbon::accounts::PlaceBid {
+                auction,
+                bid: bid_pda(&auction, &industry.authority, timestamp),
+                position: position_pda(&auction, &industry.authority),
+                industry_account: industry.account,
+                escrow: escrow_pda(&auction),
+                payment_mint: payment.payment_mint,
+                escrow_token: payment.escrow_token,
+                bidder_token: payment.token_account,
+                payment_token_program: payment.payment_token_program,
+                proposal,
+                bidder: industry.authority,
+                system_program: system_program::ID,
+            },
+            carbon::instruction::PlaceBid { token_amount, timestamp },
+        )
+    }
+
+    pub fn create_sealed_bid_auction_ix(&self, operator: &Pubkey, params: SealedBidParams) -> Instruction {
+        instruction(
+            carbon::accounts::CreateAuction {
+                auction: auction_pda(params.batch_number),
+                config: config_pda(),
+                cap_schedule: cap_schedule_pda(self.config().compliance_period),
+                role_assignment: role_pda(Role::AuctionOperator, operator),
+                authority: *operator,
+                system_program: system_program::ID,
+            },
+            carbon::instruction::CreateSealedBidAuction {
+                batch_number: params.batch_number,
+                total_tokens: params.total_tokens,
+                reserve_price: params.reserve_price,
+                duration_seconds: params.duration_seconds,
+                reveal_duration_seconds: params.reveal_duration_seconds,
+                min_collateral: params.min_collateral,
+            },
+        )
+    }
+
+    pub fn commit_bid_ix(&self, batch_number: u32, industry: &Industry, commitment: [u8; 32], collateral: u64, timestamp: u64) -> Instruction {
+        let auction = auction_pda(batch_number);
+        instruction(
+            carbon::accounts::CommitBid {
+                auction,
+                bid: bid_pda(&auction, &industry.authority, timestamp),
+                industry_account: industry.account,
+                escrow: escrow_pda(&auction),
+                bidder: industry.authority,
+                system_program: system_program::ID,
+            },
+            carbon::instruction::CommitBid { commitment, collateral, timestamp },
+        )
+    }
+
+    pub fn reveal_bid_ix(&self, batch_number: u32, industry: &Industry, bid: &Pubkey, token_amount: u64, price_per_token: u64, salt: [u8; 32]) -> Instruction {
+        let auction = auction_pda(batch_number);
+        instruction(
+            carbon::accounts::RevealBid {
+                auction,
+                bid: *bid,
+                industry_account: industry.account,
+                position: position_pda(&auction, &industry.authority),
+                escrow: escrow_pda(&auction),
+                bidder: industry.authority,
+                system_program: system_program::ID,
+            },
+            carbon::instruction::RevealBid { token_amount, price_per_token, salt },
+        )
+    }
+
+    /// Finalizes a sealed-bid auction, passing `bids` as the revealed bids to clear.
+    pub fn finalize_sealed_auction_ix(&self, operator: &Pubkey, batch_number: u32, bids: &[Pubkey]) -> Instruction {
+        let mut instruction = self.finalize_auction_ix(operator, batch_number);
+        instruction.accounts.extend(bids.iter().map(|bid| AccountMeta::new(*bid, false)));
+        instruction
+    }
+
+    pub fn finalize_auction_ix(&self, operator: &Pubkey, batch_number: u32) -> Instruction {
+        instruction(
+            carbon::accounts::FinalizeAuction {
+                auction: auction_pda(batch_number),
+                role_assignment: role_pda(Role::AuctionOperator, operator),
+                authority: *operator,
+            },
+            carbon::instruction::FinalizeAuction {},
+        )
+    }
+
+    pub fn release_reserve_ix(&self, operator: &Pubkey, batch_number: u32) -> Instruction {
+        let auction = auction_pda(batch_number);
+        instruction(
+            carbon::accounts::ReleaseReserve {
+                auction,
+                reserve_sale: reserve_sale_pda(&auction),
+                reserve_config: reserve_config_pda(),
+                config: config_pda(),
+                cap_schedule: cap_schedule_pda(self.auction_state(batch_number).vintage),
+                role_assignment: role_pda(Role::AuctionOperator, operator),
+                authority: *operator,
+                system_program: system_program::ID,
+            },
+            carbon::instruction::ReleaseReserve {},
+        )
+    }
+
+    pub fn buy_reserve_allowances_ix(&self, batch_number: u32, industry: &Industry, token_amount: u64) -> Instruction {
+        let vintage = self.auction_state(batch_number).vintage;
+        instruction(
+            carbon::accounts::BuyReserveAllowances {
+                reserve_sale: reserve_sale_pda(&auction_pda(batch_number)),
+                industry_account: industry.account,
+                vintage_holding: vintage_pda(&industry.account, vintage),
+                config: config_pda(),
+                ct_mint: self.ct_mint,
+                industry_ct_account: industry.ct_account,
+                mint_authority: mint_authority_pda(),
+                treasury: self.treasury,
+                buyer: industry.authority,
+                token_program: self.token_program,
+                system_program: system_program::ID,
+            },
+            carbon::instruction::BuyReserveAllowances { token_amount },
+        )
+    }
+
+    pub fn cancel_auction_ix(&self, operator: &Pubkey, batch_number: u32) -> Instruction {
+        instruction(
+            carbon::accounts::CancelAuction {
+                auction: auction_pda(batch_number),
+                cap_schedule: cap_schedule_pda(self.auction_state(batch_number).vintage),
+                role_assignment: role_pda(Role::AuctionOperator, operator),
+                authority: *operator,
+            },
+            carbon::instruction::CancelAuction {},
+        )
+    }
+
+    pub fn claim_tokens_ix(&self, batch_number: u32, industry: &Industry, bid: &Pubkey) -> Instruction {
+        self.claim_tokens_for_ix(&industry.authority, batch_number, industry, bid)
+    }
+
+    /// Claims `bid` for `industry`, signed by `authority`: the bidder itself or an auction
+    /// operator settling on its behalf.
+    pub fn claim_tokens_for_ix(&self, authority: &Pubkey, batch_number: u32, industry: &Industry, bid: &Pubkey) -> Instruction {
+        let auction = auction_pda(batch_number);
+        let vintage = self.auction_state(batch_number).vintage;
+        let payment = self.payment_accounts(batch_number, &industry.authority);
+        instruction(
+            carbon::accounts::ClaimTokens {
+                auction,
+                bid: *bid,
+                industry_account: industry.account,
+                position: self.open_position(&auction, &industry.authority),
+                vintage_holding: vintage_pda(&industry.account, vintage),
+                config: config_pda(),
+                ct_mint: self.ct_mint,
+                industry_ct_account: industry.ct_account,
+                mint_authority: mint_authority_pda(),
+                escrow: escrow_pda(&auction),
+                payment_mint: payment.payment_mint,
+                escrow_token: payment.escrow_token,
+                bidder_token: payment.token_account,
+                payment_token_program: payment.payment_token_program,
+                bidder: industry.authority,
+                role_assignment: (*authority != industry.authority).then(|| role_pda(Role::AuctionOperator, authority)),
+                authority: *authority,
+                token_program: self.token_program,
+                associated_token_program: spl_associated_token_account::ID,
+                system_program: system_program::ID,
+            },
+            carbon::instruction::ClaimTokens {},
+        )
+    }
+
+    /// The bidder's position in `auction`, while it still has bids to settle.
+    pub fn open_position(&self, auction: &Pubkey, bidder: &Pubkey) -> Option<Pubkey> {
+        let position = position_pda(auction, bidder);
+        self.ctx.exists(&position).then_some(position)
+    }
+
+    pub fn refund_bid_ix(&self, batch_number: u32, industry: &Industry, bid: &Pubkey) -> Instruction {
+        self.refund_bid_for_ix(&industry.authority, batch_number, industry, bid)
+    }
+
+    /// Refunds `bid` to `industry`, signed by `authority`: the bidder itself or an auction
+    /// operator acting on its behalf.
+    pub fn refund_bid_for_ix(&self, authority: &Pubkey, batch_number: u32, industry: &Industry, bid: &Pubkey) -> Instruction {
+        let auction = auction_pda(batch_number);
+        let payment = self.payment_accounts(batch_number, &industry.authority);
+        instruction(
+            carbon::accounts::RefundBid {
+                auction,
+                bid: *bid,
+                industry_account: industry.account,
+                position: self.open_position(&auction, &industry.authority),
+                escrow: escrow_pda(&auction),
+                payment_mint: payment.payment_mint,
+                escrow_token: payment.escrow_token,
+                bidder_token: payment.token_account,
+                payment_token_program: payment.payment_token_program,
+                bidder: industry.authority,
+                role_assignment: (*authority != industry.authority).then(|| role_pda(Role::AuctionOperator, authority)),
+                authority: *authority,
+                system_program: system_program::ID,
+            },
+            carbon::instruction::RefundBid {},
+        )
+    }
+
+    pub fn withdraw_proceeds_ix(&self, treasurer: &Pubkey, batch_number: u32, treasury: &Pubkey) -> Instruction {
+        let auction = auction_pda(batch_number);
+        let payment = self.payment_accounts(batch_number, treasury);
+        instruction(
+            carbon::accounts::WithdrawProceeds {
+                auction,
+                escrow: escrow_pda(&auction),
+                config: config_pda(),
+                role_assignment: role_pda(Role::Treasurer, treasurer),
+                treasury: *treasury,
+                payment_mint: payment.payment_mint,
+                escrow_token: payment.escrow_token,
+                treasury_token: payment.token_account,
+                payment_token_program: payment.payment_token_program,
+                authority: *treasurer,
+                system_program: system_program::ID,
+            },
+            carbon::instruction::WithdrawProceeds {},
+        )
+    }
+
+    pub fn close_bid_ix(&self, industry: &Industry, bid: &Pubkey) -> Instruction {
+        instruction(
+            carbon::accounts::CloseBid { bid: *bid, bidder: industry.authority },
+            carbon::instruction::CloseBid {},
+        )
+    }
+
+    pub fn close_auction_ix(&self, operator: &Pubkey, batch_number: u32, rent_recipient: &Pubkey) -> Instruction {
+        let auction = auction_pda(batch_number);
+        let payment = self.payment_accounts(batch_number, rent_recipient);
+        instruction(
+            carbon::accounts::CloseAuction {
+                auction,
+                escrow: escrow_pda(&auction),
+                escrow_token: payment.escrow_token,
+                payment_token_program: payment.payment_token_program,
+                config: config_pda(),
+                role_assignment: role_pda(Role::AuctionOperator, operator),
+                treasury: self.treasury,
+                rent_recipient: *rent_recipient,
+                authority: *operator,
+                system_program: system_program::ID,
+            },
+            carbon::instruction::CloseAuction {},
+        )
+    }
+
+    pub fn archive_emission_report_ix(&self, industry: &Industry, report: &Pubkey) -> Instruction {
+        instruction(
+            carbon::accounts::ArchiveEmissionReport {
+                industry_account: industry.account,
+                config: config_pda(),
+                emission_report: *report,
+                authority: industry.authority,
+            },
+            carbon::instruction::ArchiveEmissionReport {},
+        )
+    }
+
+    pub fn set_industry_suspension_ix(&self, regulator: &Pubkey, industry: &Industry, suspended: bool) -> Instruction {
+        instruction(
+            carbon::accounts::SetIndustrySuspension {
+                role_assignment: role_pda(Role::Regulator, regulator),
+                regulator: *regulator,
+                industry_account: industry.account,
+            },
+            carbon::instruction::SetIndustrySuspension { suspended },
+        )
+    }
+
+    pub fn accredit_trader_ix(&self, verifier: &Pubkey, trader: &Pubkey) -> Instruction {
+        instruction(
+            carbon::accounts::AccreditTrader {
+                role_assignment: role_pda(Role::Verifier, verifier),
+                accredited_trader: trader_pda(trader),
+                verifier: *verifier,
+                system_program: system_program::ID,
+            },
+            carbon::instruction::AccreditTrader { trader: *trader },
+        )
+    }
+
+    pub fn revoke_trader_accreditation_ix(&self, verifier: &Pubkey, trader: &Pubkey) -> Instruction {
+        instruction(
+            carbon::accounts::RevokeTraderAccreditation {
+                role_assignment: role_pda(Role::Verifier, verifier),
+                accredited_trader: trader_pda(trader),
+                verifier: *verifier,
+            },
+            carbon::instruction::RevokeTraderAccreditation {},
+        )
+    }
+
+    pub fn create_ct_account_ix(&self, payer: &Pubkey, owner: &Pubkey) -> Instruction {
+        spl_associated_token_account::instruction::create_associated_token_account(payer, owner, &self.ct_mint, &self.token_program)
+    }
+
+    pub fn ct_account(&self, owner: &Pubkey) -> Pubkey {
+        get_associated_token_address_with_program_id(owner, &self.ct_mint, &self.token_program)
+    }
+
+    /// A Token-2022 `transfer_checked` of CT between two wallets' token accounts, carrying
+    /// the extra accounts the transfer hook needs.
+    pub fn transfer_ct_ix(&self, from: &Pubkey, to: &Pubkey, amount: u64) -> Instruction {
+        let mut instruction = spl_token_2022::instruction::transfer_checked(
+            &self.token_program,
+            &self.ct_account(from),
+            &self.ct_mint,
+            &self.ct_account(to),
+            from,
+            &[],
+            amount,
+            9,
+        )
+        .unwrap();
+
+        instruction.accounts.extend(self.hook_accounts(from, to));
+        instruction
+    }
+
+    /// Extra accounts the transfer hook resolves for a CT transfer between the token
+    /// accounts of `from` and `to`; none when CT is a plain SPL Token mint.
+    pub fn hook_accounts(&self, from: &Pubkey, to: &Pubkey) -> Vec<AccountMeta> {
+        if self.token_program != spl_token_2022::ID {
+            return Vec::new();
+        }
+
+        vec![
+            AccountMeta::new_readonly(industry_pda(from), false),
+            AccountMeta::new_readonly(industry_pda(to), false),
+            AccountMeta::new_readonly(trader_pda(from), false),
+            AccountMeta::new_readonly(trader_pda(to), false),
+            AccountMeta::new_readonly(carbon::ID, false),
+            AccountMeta::new_readonly(extra_account_metas_pda(&self.ct_mint), false),
+        ]
+    }
+
+    pub fn create_offer_ix(
+        &self,
+        maker: &Industry,
+        offer_id: u64,
+        side: OfferSide,
+        vintage: u32,
+        token_amount: u64,
+        price_per_token: u64,
+    ) -> Instruction {
+        let offer = offer_pda(&maker.authority, offer_id);
+        let mut instruction = instruction(
+            carbon::accounts::CreateOffer {
+                industry_account: maker.account,
+                maker_vintage: vintage_pda(&maker.account, vintage),
+                config: config_pda(),
+                ct_mint: self.ct_mint,
+                offer,
+                offer_escrow: offer_escrow_pda(&offer),
+                maker_ct_account: maker.ct_account,
+                offer_authority: offer_authority_pda(),
+                maker: maker.authority,
+                token_program: self.token_program,
+                system_program: system_program::ID,
+            },
+            carbon::instruction::CreateOffer { offer_id, side, vintage, token_amount, price_per_token },
+        );
+        instruction.accounts.extend(self.hook_accounts(&maker.authority, &offer_authority_pda()));
+        instruction
+    }
+
+    pub fn accept_offer_ix(&self, taker: &Industry, maker: &Industry, offer_id: u64, side: OfferSide, vintage: u32) -> Instruction {
+        let offer = offer_pda(&maker.authority, offer_id);
+        let mut instruction = instruction(
+            carbon::accounts::AcceptOffer {
+                offer,
+                maker_industry: maker.account,
+                maker_vintage: vintage_pda(&maker.account, vintage),
+                taker_industry: taker.account,
+                taker_vintage: vintage_pda(&taker.account, vintage),
+                config: config_pda(),
+                ct_mint: self.ct_mint,
+                offer_escrow: offer_escrow_pda(&offer),
+                maker_ct_account: maker.ct_account,
+                taker_ct_account: taker.ct_account,
+                offer_authority: offer_authority_pda(),
+                maker: maker.authority,
+                taker: taker.authority,
+                token_program: self.token_program,
+                system_program: system_program::ID,
+            },
+            carbon::instruction::AcceptOffer {},
+        );
+        let hook_accounts = match side {
+            OfferSide::Sell => self.hook_accounts(&offer_authority_pda(), &taker.authority),
+            OfferSide::Buy => self.hook_accounts(&taker.authority, &maker.authority),
+        };
+        instruction.accounts.extend(hook_accounts);
+        instruction
+    }
+
+    pub fn cancel_offer_ix(&self, maker: &Industry, offer_id: u64, vintage: u32) -> Instruction {
+        let offer = offer_pda(&maker.authority, offer_id);
+        let mut instruction = instruction(
+            carbon::accounts::CancelOffer {
+                offer,
+                industry_account: maker.account,
+                maker_vintage: vintage_pda(&maker.account, vintage),
+                config: config_pda(),
+                ct_mint: self.ct_mint,
+                offer_escrow: offer_escrow_pda(&offer),
+                maker_ct_account: maker.ct_account,
+                offer_authority: offer_authority_pda(),
+                maker: maker.authority,
+                token_program: self.token_program,
+            },
+            carbon::instruction::CancelOffer {},
+        );
+        instruction.accounts.extend(self.hook_accounts(&offer_authority_pda(), &maker.authority));
+        instruction
+    }
+
+    /// Registers an industry, has the admin verify it and opens its CT token account.
+    pub fn verified_industry(&mut self, name: &str) -> Industry {
+        let authority = self.ctx.funded_wallet(100);
+        let industry = self.industry(&authority);
+        let admin = self.admin;
+
+        self.send(self.register_industry_ix(&industry, name, MIN_BOND), &[authority]).unwrap();
+        self.send(self.verify_industry_ix(&admin, &industry), &[admin]).unwrap();
+        self.send(self.create_ct_account_ix(&authority, &authority), &[authority]).unwrap();
+
+        industry
+    }
+
+    /// Submits a report and has the admin verify it, returning the report address.
+    pub fn verified_report(&mut self, industry: &Industry, co2_emitted: u64) -> Pubkey {
+        let timestamp = self.ctx.now() as u64;
+        let report = emission_report_pda(&industry.account, timestamp);
+        let admin = self.admin;
+
+        self.send(self.submit_emission_report_ix(industry, co2_emitted, timestamp), &[industry.authority]).unwrap();
+        self.send(self.verify_emission_report_ix(&admin, industry, &report, None), &[admin]).unwrap();
+        self.ctx.warp_forward(1);
+
+        report
+    }
+
+    /// Runs a standard auction in which `industry` buys `token_amount` CT, then finalizes
+    /// it and claims the tokens.
+    pub fn buy_credits(&mut self, batch_number: u32, industry: &Industry, token_amount: u64) {
+        let admin = self.admin;
+        let params = AuctionParams::standard(batch_number);
+        let timestamp = self.ctx.now() as u64;
+        let bid = bid_pda(&auction_pda(batch_number), &industry.authority, timestamp);
+
+        self.send(self.create_auction_ix(&admin, params), &[admin]).unwrap();
+        self.send(self.place_bid_ix(batch_number, industry, token_amount, timestamp), &[industry.authority]).unwrap();
+        self.ctx.warp_forward(params.duration_seconds);
+        self.send(self.finalize_auction_ix(&admin, batch_number), &[admin]).unwrap();
+        self.send(self.claim_tokens_ix(batch_number, industry, &bid), &[industry.authority]).unwrap();
+    }
+}
+
+impl Default for Protocol {
+    fn default() -> Self {
+        Self::new()
+    }
+}
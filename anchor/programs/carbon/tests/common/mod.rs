@@ -0,0 +1,8 @@
+#![allow(dead_code)]
+
+mod fixtures;
+mod runtime;
+
+pub use fixtures::*;
+#[allow(unused_imports)]
+pub use runtime::*;
@@ -0,0 +1,813 @@
+//! In-process runtime for exercising the `carbon` program without a validator.
+//!
+//! Each instruction is serialized into the same input layout the SBF loader produces and
+//! handed to the program's exported `entrypoint`. Cross-program invocations, sysvars, return
+//! data and logs are served through `program_stubs`, so the system, SPL Token, Token-2022
+//! and associated token programs run natively alongside `carbon` and the clock can be
+//! warped between transactions.
+//!
+//! Every invocation, including CPIs, is held to the loader's account rules: read-only and
+//! executable accounts stay untouched, only the owning program may debit lamports, write
+//! data or reassign an account, and lamports always balance. Transactions are rejected if
+//! they leave an account short of rent exemption. The invoke depth is enforced too.
+//!
+//! This is a stand-in until `litesvm` or `solana-program-test` can be vendored into the
+//! workspace, at which point the fixtures should move onto one of them and this module go.
+//! Passing here is not the same as passing on a validator. In particular, it does not model:
+//!
+//! - SBF execution. Every program, `carbon` included, runs as native host code, so
+//!   alignment, pointer-width and syscall differences of the real VM never show up.
+//! - Compute metering. Neither the 200k per-instruction default nor `ComputeBudget`
+//!   requests are enforced, and neither are the 32 KiB heap and 4 KiB stack frame limits.
+//! - Transaction limits: packet size, account count, account locks and fees. Signatures are
+//!   checked only by matching `signers` against the accounts flagged as signers.
+//! - Sysvars other than `Clock` and `Rent`, including the instructions sysvar, and slot
+//!   hashes or recent blockhashes of any kind.
+//! - Program deployment. Each program account is an executable placeholder with no program
+//!   data behind it, so upgrades and upgrade-authority checks cannot be exercised.
+//! - CPIs that Token-2022 makes on its own. Only the transfer hook's `Execute` is replayed,
+//!   by `run_transfer_hook`.
+//! - Account size limits. Growth is bounded only by what `AccountInfo::realloc` checks
+//!   itself; the 10 MiB account cap and the per-transaction growth budget are not enforced.
+
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    sync::Once,
+};
+
+use anchor_lang::{
+    prelude::*,
+    solana_program::{
+        entrypoint::{deserialize, ProgramResult, BPF_ALIGN_OF_U128, MAX_PERMITTED_DATA_INCREASE, NON_DUP_MARKER},
+        instruction::{AccountMeta, Instruction},
+        program_error::ProgramError,
+        program_stubs::{self, SyscallStubs},
+        sysvar,
+    },
+    system_program, AccountDeserialize, Discriminator, Event,
+};
+use anchor_spl::{
+    associated_token::spl_associated_token_account,
+    token::spl_token,
+    token_2022::spl_token_2022::{
+        self,
+        extension::{transfer_hook, StateWithExtensions, StateWithExtensionsMut},
+        instruction::TokenInstruction,
+    },
+};
+use spl_tlv_account_resolution::state::ExtraAccountMetaList;
+use spl_transfer_hook_interface::{get_extra_account_metas_address, instruction::ExecuteInstruction};
+
+pub const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+pub const GENESIS_TIMESTAMP: i64 = 1_700_000_000;
+
+const NATIVE_LOADER_ID: Pubkey = pubkey!("NativeLoader1111111111111111111111111111111");
+const BPF_LOADER_UPGRADEABLE_ID: Pubkey = pubkey!("BPFLoaderUpgradeab1e11111111111111111111111");
+
+/// Deepest program call stack the runtime allows, counting the top-level instruction.
+const MAX_INVOKE_STACK_HEIGHT: usize = 5;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AccountState {
+    pub lamports: u64,
+    pub data: Vec<u8>,
+    pub owner: Pubkey,
+    pub executable: bool,
+}
+
+impl AccountState {
+    fn of(info: &AccountInfo) -> Self {
+        Self {
+            lamports: info.lamports(),
+            data: info.data.borrow().to_vec(),
+            owner: *info.owner,
+            executable: info.executable,
+        }
+    }
+
+    /// Whether the account holds lamports without covering rent for its data, the state the
+    /// runtime refuses to let a transaction move an account into.
+    fn is_rent_paying(&self) -> bool {
+        self.lamports > 0 && self.lamports < Rent::default().minimum_balance(self.data.len())
+    }
+}
+
+/// A failed transaction together with the logs it produced.
+pub struct TransactionError {
+    pub error: ProgramError,
+    pub logs: Vec<String>,
+}
+
+impl PartialEq<ProgramError> for TransactionError {
+    fn eq(&self, other: &ProgramError) -> bool {
+        self.error == *other
+    }
+}
+
+impl std::fmt::Debug for TransactionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.error)?;
+        for log in &self.logs {
+            write!(f, "\n  {log}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A program invocation and the accounts it was handed, as they stood when it last had a
+/// consistent view of them: on entry, and after each CPI it made returned.
+struct Frame {
+    program_id: Pubkey,
+    accounts: HashMap<Pubkey, (bool, AccountState)>,
+    /// Lamports held across the accounts on entry, which the invocation must leave intact.
+    lamports: u128,
+}
+
+impl Frame {
+    fn new(program_id: Pubkey, accounts: &[AccountInfo]) -> Self {
+        let mut frame = Self {
+            program_id,
+            accounts: HashMap::new(),
+            lamports: 0,
+        };
+        frame.refresh(accounts);
+        frame.lamports = frame.accounts.values().map(|(_, state)| u128::from(state.lamports)).sum();
+        frame
+    }
+
+    fn refresh(&mut self, accounts: &[AccountInfo]) {
+        for info in accounts {
+            let is_writable = info.is_writable
+                || self.accounts.get(info.key).is_some_and(|(is_writable, _)| *is_writable);
+            self.accounts.insert(*info.key, (is_writable, AccountState::of(info)));
+        }
+    }
+
+    /// Checks what this frame's program did to `accounts` since it last saw them.
+    fn verify(&self, accounts: &[AccountInfo]) -> ProgramResult {
+        for info in accounts {
+            let (is_writable, before) = &self.accounts[info.key];
+            check_account_change(&self.program_id, info.key, *is_writable, before, &AccountState::of(info))?;
+        }
+        Ok(())
+    }
+
+    /// Checks that the invocation as a whole neither created nor destroyed lamports.
+    fn verify_balance(&self, accounts: &[AccountInfo]) -> ProgramResult {
+        let lamports: u128 = accounts
+            .iter()
+            .enumerate()
+            .filter(|(index, info)| !accounts[..*index].iter().any(|other| other.key == info.key))
+            .map(|(_, info)| u128::from(info.lamports()))
+            .sum();
+        if lamports != self.lamports {
+            log(format!("Program {} did not balance lamports: {} -> {lamports}", self.program_id, self.lamports));
+            return Err(ProgramError::InvalidAccountData);
+        }
+        Ok(())
+    }
+}
+
+fn log(message: String) {
+    LOGS.with(|logs| logs.borrow_mut().push(message));
+}
+
+/// The loader's rules for how one program may change an account it was handed.
+fn check_account_change(
+    program_id: &Pubkey,
+    key: &Pubkey,
+    is_writable: bool,
+    before: &AccountState,
+    after: &AccountState,
+) -> ProgramResult {
+    if before == after {
+        return Ok(());
+    }
+    let owned = before.owner == *program_id;
+    let (violation, error) = if !is_writable {
+        ("modified a read-only account", ProgramError::InvalidAccountData)
+    } else if before.executable || after.executable {
+        ("modified an executable account", ProgramError::InvalidAccountData)
+    } else if after.owner != before.owner && !(owned && after.data.iter().all(|byte| *byte == 0)) {
+        ("reassigned an account it does not own or that still holds data", ProgramError::IllegalOwner)
+    } else if after.lamports < before.lamports && !owned {
+        ("debited lamports from an account it does not own", ProgramError::IllegalOwner)
+    } else if after.data != before.data && !owned {
+        ("modified data of an account it does not own", ProgramError::IllegalOwner)
+    } else {
+        return Ok(());
+    };
+    log(format!("Program {program_id} {violation}: {key}"));
+    Err(error)
+}
+
+thread_local! {
+    static CLOCK: RefCell<Clock> = RefCell::new(Clock::default());
+    static LOGS: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+    static EVENTS: RefCell<Vec<Vec<u8>>> = const { RefCell::new(Vec::new()) };
+    static RETURN_DATA: RefCell<Option<(Pubkey, Vec<u8>)>> = const { RefCell::new(None) };
+    static FRAMES: RefCell<Vec<Frame>> = const { RefCell::new(Vec::new()) };
+}
+
+struct NativeStubs;
+
+impl SyscallStubs for NativeStubs {
+    fn sol_log(&self, message: &str) {
+        LOGS.with(|logs| logs.borrow_mut().push(message.to_string()));
+    }
+
+    fn sol_log_data(&self, fields: &[&[u8]]) {
+        EVENTS.with(|events| events.borrow_mut().push(fields.concat()));
+    }
+
+    fn sol_invoke_signed(
+        &self,
+        instruction: &Instruction,
+        account_infos: &[AccountInfo],
+        signers_seeds: &[&[&[u8]]],
+    ) -> ProgramResult {
+        let caller = FRAMES.with(|frames| frames.borrow().last().expect("CPI outside of an instruction").program_id);
+
+        let mut callee_infos = Vec::with_capacity(instruction.accounts.len());
+        for meta in &instruction.accounts {
+            let mut info = account_infos
+                .iter()
+                .find(|info| info.key == &meta.pubkey)
+                .cloned()
+                .ok_or(ProgramError::NotEnoughAccountKeys)?;
+
+            if meta.is_signer && !info.is_signer {
+                let signed_by_caller = signers_seeds.iter().any(|seeds| {
+                    Pubkey::create_program_address(seeds, &caller)
+                        .map(|address| address == meta.pubkey)
+                        .unwrap_or(false)
+                });
+                if !signed_by_caller {
+                    self.sol_log(&format!("CPI signer privilege escalated for {}", meta.pubkey));
+                    return Err(ProgramError::MissingRequiredSignature);
+                }
+            }
+            if meta.is_writable && !info.is_writable {
+                self.sol_log(&format!("CPI writable privilege escalated for {}", meta.pubkey));
+                return Err(ProgramError::InvalidArgument);
+            }
+
+            info.is_signer = meta.is_signer;
+            info.is_writable = meta.is_writable;
+            callee_infos.push(info);
+        }
+
+        // The caller's own changes must be legal before the callee gets to see them, and
+        // whatever the callee then does is judged against the callee.
+        FRAMES.with(|frames| frames.borrow().last().unwrap().verify(&callee_infos))?;
+        dispatch(&instruction.program_id, &callee_infos, &instruction.data)?;
+        FRAMES.with(|frames| frames.borrow_mut().last_mut().unwrap().refresh(&callee_infos));
+        Ok(())
+    }
+
+    fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+        let clock = CLOCK.with(|clock| clock.borrow().clone());
+        unsafe { std::ptr::write(var_addr as *mut Clock, clock) };
+        anchor_lang::solana_program::entrypoint::SUCCESS
+    }
+
+    fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+        unsafe { std::ptr::write(var_addr as *mut Rent, Rent::default()) };
+        anchor_lang::solana_program::entrypoint::SUCCESS
+    }
+
+    fn sol_set_return_data(&self, data: &[u8]) {
+        let program_id = FRAMES.with(|frames| frames.borrow().last().expect("return data outside of an instruction").program_id);
+        RETURN_DATA.with(|slot| *slot.borrow_mut() = Some((program_id, data.to_vec())));
+    }
+
+    fn sol_get_return_data(&self) -> Option<(Pubkey, Vec<u8>)> {
+        RETURN_DATA.with(|slot| slot.borrow().clone())
+    }
+
+    fn sol_get_stack_height(&self) -> u64 {
+        FRAMES.with(|frames| frames.borrow().len() as u64)
+    }
+}
+
+fn dispatch(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    if FRAMES.with(|frames| frames.borrow().len()) >= MAX_INVOKE_STACK_HEIGHT {
+        log(format!("Program {program_id} exceeded the maximum invoke depth"));
+        return Err(ProgramError::InvalidArgument);
+    }
+    FRAMES.with(|frames| frames.borrow_mut().push(Frame::new(*program_id, accounts)));
+
+    let result = run_program(program_id, accounts, data).and_then(|()| {
+        FRAMES.with(|frames| {
+            let frames = frames.borrow();
+            let frame = frames.last().unwrap();
+            frame.verify(accounts)?;
+            frame.verify_balance(accounts)
+        })
+    });
+
+    FRAMES.with(|frames| frames.borrow_mut().pop());
+    result
+}
+
+fn run_program(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    if *program_id == carbon::ID {
+        invoke_entrypoint(carbon::entrypoint, program_id, accounts, data)
+    } else if *program_id == system_program::ID {
+        process_system_instruction(accounts, data)
+    } else if *program_id == spl_token::ID {
+        spl_token::processor::Processor::process(program_id, accounts, data)
+    } else if *program_id == spl_token_2022::ID {
+        spl_token_2022::processor::Processor::process(program_id, accounts, data)
+            .and_then(|()| run_transfer_hook(accounts, data))
+    } else if *program_id == spl_associated_token_account::ID {
+        spl_associated_token_account::processor::process_instruction(program_id, accounts, data)
+    } else {
+        Err(ProgramError::IncorrectProgramId)
+    }
+}
+
+/// Serializes `accounts` into a fresh input buffer for an exported SBF-style entrypoint, as
+/// the loader does for every invocation, then copies the results back on success.
+fn invoke_entrypoint(
+    entrypoint: unsafe extern "C" fn(*mut u8) -> u64,
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    let metas: Vec<AccountMeta> = accounts
+        .iter()
+        .map(|info| AccountMeta {
+            pubkey: *info.key,
+            is_signer: info.is_signer,
+            is_writable: info.is_writable,
+        })
+        .collect();
+    let states: HashMap<Pubkey, AccountState> = accounts.iter().map(|info| (*info.key, AccountState::of(info))).collect();
+
+    let mut input = SerializedInput::new(program_id, &metas, &states, data);
+    let status = unsafe { entrypoint(input.buffer.as_mut_ptr() as *mut u8) };
+    if status != anchor_lang::solana_program::entrypoint::SUCCESS {
+        return Err(ProgramError::from(status));
+    }
+
+    for (key, _, after) in input.read_back() {
+        let info = accounts.iter().find(|info| *info.key == key).unwrap();
+        **info.try_borrow_mut_lamports()? = after.lamports;
+        if *info.owner != after.owner {
+            info.assign(&after.owner);
+        }
+        if info.data_len() != after.data.len() {
+            info.realloc(after.data.len(), false)?;
+        }
+        info.try_borrow_mut_data()?.copy_from_slice(&after.data);
+    }
+    Ok(())
+}
+
+/// `solana_cpi::invoke` is a no-op off-chain, so Token-2022 never reaches a mint's transfer
+/// hook by itself. Replays the `Execute` CPI it makes on-chain after a hooked
+/// `TransferChecked`, with both token accounts flagged as transferring.
+fn run_transfer_hook(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let Ok(TokenInstruction::TransferChecked { amount, .. }) = TokenInstruction::unpack(data) else {
+        return Ok(());
+    };
+    let [source, mint, destination, authority, additional @ ..] = accounts else {
+        return Ok(());
+    };
+    let hook_program = {
+        let mint_data = mint.try_borrow_data()?;
+        let mint_state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+        match transfer_hook::get_program_id(&mint_state) {
+            Some(program_id) => program_id,
+            None => return Ok(()),
+        }
+    };
+
+    for account in [source, destination] {
+        let mut account_data = account.try_borrow_mut_data()?;
+        let mut state = StateWithExtensionsMut::<spl_token_2022::state::Account>::unpack(&mut account_data)?;
+        transfer_hook::set_transferring(&mut state)?;
+    }
+
+    let mut instruction = spl_transfer_hook_interface::instruction::execute(
+        &hook_program,
+        source.key,
+        mint.key,
+        destination.key,
+        authority.key,
+        amount,
+    );
+    let mut account_infos = vec![source.clone(), mint.clone(), destination.clone(), authority.clone()];
+    let validation = get_extra_account_metas_address(mint.key, &hook_program);
+    if let Some(validation_info) = additional.iter().find(|info| *info.key == validation) {
+        instruction.accounts.push(AccountMeta::new_readonly(validation, false));
+        account_infos.push(validation_info.clone());
+        ExtraAccountMetaList::add_to_cpi_instruction::<ExecuteInstruction>(
+            &mut instruction,
+            &mut account_infos,
+            &validation_info.try_borrow_data()?,
+            additional,
+        )?;
+    }
+    let result = NativeStubs.sol_invoke_signed(&instruction, &account_infos, &[]);
+
+    transfer_hook::unset_transferring(source)?;
+    transfer_hook::unset_transferring(destination)?;
+    result
+}
+
+/// The subset of the system program that Anchor and the SPL programs rely on.
+fn process_system_instruction(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let read_u64 = |offset: usize| -> std::result::Result<u64, ProgramError> {
+        data.get(offset..offset + 8)
+            .map(|bytes| u64::from_le_bytes(bytes.try_into().unwrap()))
+            .ok_or(ProgramError::InvalidInstructionData)
+    };
+    let read_pubkey = |offset: usize| -> std::result::Result<Pubkey, ProgramError> {
+        data.get(offset..offset + 32)
+            .map(|bytes| Pubkey::try_from(bytes).unwrap())
+            .ok_or(ProgramError::InvalidInstructionData)
+    };
+    let tag = data
+        .get(..4)
+        .map(|bytes| u32::from_le_bytes(bytes.try_into().unwrap()))
+        .ok_or(ProgramError::InvalidInstructionData)?;
+
+    let transfer = |from: &AccountInfo, to: &AccountInfo, lamports: u64| -> ProgramResult {
+        if !from.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        if *from.owner != system_program::ID || !from.data_is_empty() {
+            return Err(ProgramError::InvalidArgument);
+        }
+        let balance = from.lamports();
+        if balance < lamports {
+            return Err(ProgramError::InsufficientFunds);
+        }
+        **from.try_borrow_mut_lamports()? = balance - lamports;
+        **to.try_borrow_mut_lamports()? += lamports;
+        Ok(())
+    };
+    let allocate = |account: &AccountInfo, space: u64| -> ProgramResult {
+        if !account.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        if *account.owner != system_program::ID || !account.data_is_empty() {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+        account.realloc(space as usize, true)
+    };
+    let assign = |account: &AccountInfo, owner: &Pubkey| -> ProgramResult {
+        if account.owner == owner {
+            return Ok(());
+        }
+        if !account.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        if *account.owner != system_program::ID {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        account.assign(owner);
+        Ok(())
+    };
+
+    match tag {
+        // CreateAccount { lamports, space, owner }
+        0 => {
+            let [from, to, ..] = accounts else {
+                return Err(ProgramError::NotEnoughAccountKeys);
+            };
+            if to.lamports() > 0 {
+                return Err(ProgramError::AccountAlreadyInitialized);
+            }
+            allocate(to, read_u64(12)?)?;
+            assign(to, &read_pubkey(20)?)?;
+            transfer(from, to, read_u64(4)?)
+        }
+        // Assign { owner }
+        1 => {
+            let [account, ..] = accounts else {
+                return Err(ProgramError::NotEnoughAccountKeys);
+            };
+            assign(account, &read_pubkey(4)?)
+        }
+        // Transfer { lamports }
+        2 => {
+            let [from, to, ..] = accounts else {
+                return Err(ProgramError::NotEnoughAccountKeys);
+            };
+            transfer(from, to, read_u64(4)?)
+        }
+        // Allocate { space }
+        8 => {
+            let [account, ..] = accounts else {
+                return Err(ProgramError::NotEnoughAccountKeys);
+            };
+            allocate(account, read_u64(4)?)
+        }
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
+
+/// Offset of each unique account's key within a serialized input buffer.
+struct SerializedInput {
+    buffer: Vec<u64>,
+    accounts: Vec<(Pubkey, bool, usize)>,
+}
+
+impl SerializedInput {
+    fn new(program_id: &Pubkey, metas: &[AccountMeta], state: &HashMap<Pubkey, AccountState>, data: &[u8]) -> Self {
+        let mut bytes = Vec::new();
+        let mut accounts = Vec::new();
+
+        bytes.extend_from_slice(&(metas.len() as u64).to_le_bytes());
+        for (index, meta) in metas.iter().enumerate() {
+            if let Some(first) = metas[..index].iter().position(|m| m.pubkey == meta.pubkey) {
+                bytes.push(first as u8);
+                bytes.extend_from_slice(&[0; 7]);
+                continue;
+            }
+
+            let is_signer = metas.iter().any(|m| m.pubkey == meta.pubkey && m.is_signer);
+            let is_writable = metas.iter().any(|m| m.pubkey == meta.pubkey && m.is_writable);
+            let account = state.get(&meta.pubkey).cloned().unwrap_or_default();
+
+            bytes.push(NON_DUP_MARKER);
+            bytes.push(is_signer as u8);
+            bytes.push(is_writable as u8);
+            bytes.push(account.executable as u8);
+            bytes.extend_from_slice(&[0; 4]);
+            accounts.push((meta.pubkey, is_writable, bytes.len()));
+            bytes.extend_from_slice(meta.pubkey.as_ref());
+            bytes.extend_from_slice(account.owner.as_ref());
+            bytes.extend_from_slice(&account.lamports.to_le_bytes());
+            bytes.extend_from_slice(&(account.data.len() as u64).to_le_bytes());
+            bytes.extend_from_slice(&account.data);
+            bytes.resize(bytes.len() + MAX_PERMITTED_DATA_INCREASE, 0);
+            bytes.resize(bytes.len().next_multiple_of(BPF_ALIGN_OF_U128), 0);
+            bytes.extend_from_slice(&u64::MAX.to_le_bytes());
+        }
+        bytes.extend_from_slice(&(data.len() as u64).to_le_bytes());
+        bytes.extend_from_slice(data);
+        bytes.extend_from_slice(program_id.as_ref());
+
+        let mut buffer = vec![0u64; bytes.len().div_ceil(8)];
+        as_bytes_mut(&mut buffer)[..bytes.len()].copy_from_slice(&bytes);
+
+        Self { buffer, accounts }
+    }
+
+    fn read_back(&mut self) -> Vec<(Pubkey, bool, AccountState)> {
+        let bytes = as_bytes_mut(&mut self.buffer);
+        let read_u64 = |offset: usize| u64::from_le_bytes(bytes[offset..offset + 8].try_into().unwrap());
+
+        self.accounts
+            .iter()
+            .map(|&(key, is_writable, offset)| {
+                let data_len = read_u64(offset + 72) as usize;
+                let account = AccountState {
+                    owner: Pubkey::try_from(&bytes[offset + 32..offset + 64]).unwrap(),
+                    lamports: read_u64(offset + 64),
+                    data: bytes[offset + 80..offset + 80 + data_len].to_vec(),
+                    executable: bytes[offset - 5] != 0,
+                };
+                (key, is_writable, account)
+            })
+            .collect()
+    }
+}
+
+fn as_bytes_mut(words: &mut [u64]) -> &mut [u8] {
+    unsafe { std::slice::from_raw_parts_mut(words.as_mut_ptr() as *mut u8, words.len() * 8) }
+}
+
+fn install_stubs() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        program_stubs::set_syscall_stubs(Box::new(NativeStubs));
+    });
+}
+
+pub struct TestContext {
+    accounts: HashMap<Pubkey, AccountState>,
+    clock: Clock,
+    logs: Vec<String>,
+    events: Vec<Vec<u8>>,
+}
+
+impl Default for TestContext {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TestContext {
+    pub fn new() -> Self {
+        install_stubs();
+
+        let mut context = Self {
+            accounts: HashMap::new(),
+            clock: Clock {
+                slot: 1,
+                unix_timestamp: GENESIS_TIMESTAMP,
+                ..Clock::default()
+            },
+            logs: Vec::new(),
+            events: Vec::new(),
+        };
+
+        for program_id in [carbon::ID, spl_token::ID, spl_token_2022::ID, spl_associated_token_account::ID] {
+            context.set_account(program_id, AccountState {
+                lamports: 1,
+                owner: BPF_LOADER_UPGRADEABLE_ID,
+                executable: true,
+                ..AccountState::default()
+            });
+        }
+        context.set_account(system_program::ID, AccountState {
+            lamports: 1,
+            owner: NATIVE_LOADER_ID,
+            executable: true,
+            ..AccountState::default()
+        });
+
+        let rent = Rent::default();
+        let mut rent_data = Vec::with_capacity(17);
+        rent_data.extend_from_slice(&rent.lamports_per_byte_year.to_le_bytes());
+        rent_data.extend_from_slice(&rent.exemption_threshold.to_le_bytes());
+        rent_data.push(rent.burn_percent);
+        context.set_account(sysvar::rent::ID, AccountState {
+            lamports: 1,
+            data: rent_data,
+            owner: sysvar::ID,
+            executable: false,
+        });
+
+        context
+    }
+
+    pub fn set_account(&mut self, key: Pubkey, account: AccountState) {
+        self.accounts.insert(key, account);
+    }
+
+    pub fn get_account(&self, key: &Pubkey) -> Option<&AccountState> {
+        self.accounts.get(key)
+    }
+
+    pub fn exists(&self, key: &Pubkey) -> bool {
+        self.accounts.contains_key(key)
+    }
+
+    pub fn lamports(&self, key: &Pubkey) -> u64 {
+        self.accounts.get(key).map_or(0, |account| account.lamports)
+    }
+
+    pub fn airdrop(&mut self, key: &Pubkey, lamports: u64) {
+        let account = self.accounts.entry(*key).or_insert_with(|| AccountState {
+            owner: system_program::ID,
+            ..AccountState::default()
+        });
+        account.lamports += lamports;
+    }
+
+    /// Creates a system-owned wallet holding `sol` SOL.
+    pub fn funded_wallet(&mut self, sol: u64) -> Pubkey {
+        let wallet = Pubkey::new_unique();
+        self.airdrop(&wallet, sol * LAMPORTS_PER_SOL);
+        wallet
+    }
+
+    /// Deserializes an Anchor account, checking its discriminator.
+    pub fn account<T: AccountDeserialize>(&self, key: &Pubkey) -> T {
+        let account = self.accounts.get(key).unwrap_or_else(|| panic!("account {key} does not exist"));
+        T::try_deserialize(&mut account.data.as_slice()).expect("account failed to deserialize")
+    }
+
+    /// Every account the program owns that deserializes as `T`, in key order.
+    pub fn program_accounts<T: AccountDeserialize>(&self) -> Vec<(Pubkey, T)> {
+        let mut accounts: Vec<_> = self.accounts
+            .iter()
+            .filter(|(_, account)| account.owner == carbon::ID)
+            .filter_map(|(key, account)| Some((*key, T::try_deserialize(&mut account.data.as_slice()).ok()?)))
+            .collect();
+        accounts.sort_by_key(|(key, _)| *key);
+        accounts
+    }
+
+    pub fn token_balance(&self, key: &Pubkey) -> u64 {
+        self.account::<anchor_spl::token_interface::TokenAccount>(key).amount
+    }
+
+    pub fn now(&self) -> i64 {
+        self.clock.unix_timestamp
+    }
+
+    pub fn warp_to(&mut self, unix_timestamp: i64) {
+        let elapsed = (unix_timestamp - self.clock.unix_timestamp).max(0) as u64;
+        self.clock.unix_timestamp = unix_timestamp;
+        self.clock.slot += elapsed.max(1);
+    }
+
+    pub fn warp_forward(&mut self, seconds: i64) {
+        self.warp_to(self.clock.unix_timestamp + seconds);
+    }
+
+    /// Logs emitted by the most recent transaction.
+    pub fn logs(&self) -> &[String] {
+        &self.logs
+    }
+
+    /// Anchor events of type `E` emitted by the most recent transaction.
+    pub fn events<E: Event + Discriminator>(&self) -> Vec<E> {
+        self.events
+            .iter()
+            .filter_map(|data| data.strip_prefix(E::DISCRIMINATOR))
+            .map(|payload| E::try_from_slice(payload).expect("event failed to deserialize"))
+            .collect()
+    }
+
+    pub fn send(&mut self, instruction: Instruction, signers: &[Pubkey]) -> std::result::Result<(), TransactionError> {
+        self.send_all(&[instruction], signers)
+    }
+
+    /// Executes `instructions` atomically: either every instruction succeeds and all
+    /// account changes are committed, or none of them are.
+    pub fn send_all(&mut self, instructions: &[Instruction], signers: &[Pubkey]) -> std::result::Result<(), TransactionError> {
+        CLOCK.with(|clock| *clock.borrow_mut() = self.clock.clone());
+        LOGS.with(|logs| logs.borrow_mut().clear());
+        EVENTS.with(|events| events.borrow_mut().clear());
+
+        let mut working = self.accounts.clone();
+        let result = instructions
+            .iter()
+            .try_for_each(|instruction| execute_instruction(&mut working, instruction, signers))
+            .and_then(|()| check_rent_states(&self.accounts, &working));
+
+        self.logs = LOGS.with(|logs| std::mem::take(&mut *logs.borrow_mut()));
+        self.events = EVENTS.with(|events| std::mem::take(&mut *events.borrow_mut()));
+
+        match result {
+            Ok(()) => {
+                working.retain(|_, account| account.lamports > 0);
+                self.accounts = working;
+                self.clock.slot += 1;
+                Ok(())
+            }
+            Err(error) => Err(TransactionError {
+                error,
+                logs: self.logs.clone(),
+            }),
+        }
+    }
+}
+
+fn execute_instruction(
+    accounts: &mut HashMap<Pubkey, AccountState>,
+    instruction: &Instruction,
+    signers: &[Pubkey],
+) -> std::result::Result<(), ProgramError> {
+    if let Some(meta) = instruction
+        .accounts
+        .iter()
+        .find(|meta| meta.is_signer && !signers.contains(&meta.pubkey))
+    {
+        log(format!("Transaction is missing a signature for {}", meta.pubkey));
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    RETURN_DATA.with(|slot| *slot.borrow_mut() = None);
+
+    let mut input = SerializedInput::new(&instruction.program_id, &instruction.accounts, accounts, &instruction.data);
+    {
+        let (program_id, account_infos, data) = unsafe { deserialize(input.buffer.as_mut_ptr() as *mut u8) };
+        dispatch(program_id, &account_infos, data)?;
+    }
+
+    for (key, is_writable, after) in input.read_back() {
+        if is_writable {
+            accounts.insert(key, after);
+        }
+    }
+    Ok(())
+}
+
+/// An account may not be left holding less than rent exemption unless it already did,
+/// kept its size and was not topped up.
+fn check_rent_states(before: &HashMap<Pubkey, AccountState>, after: &HashMap<Pubkey, AccountState>) -> std::result::Result<(), ProgramError> {
+    for (key, account) in after {
+        let previous = before.get(key);
+        if previous == Some(account) || !account.is_rent_paying() {
+            continue;
+        }
+        let still_paying = previous.is_some_and(|previous| {
+            previous.is_rent_paying() && previous.data.len() == account.data.len() && account.lamports <= previous.lamports
+        });
+        if !still_paying {
+            log(format!("Transaction leaves {key} without rent exemption"));
+            return Err(ProgramError::AccountNotRentExempt);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,207 @@
+mod common;
+
+use anchor_lang::solana_program::instruction::AccountMeta;
+use carbon::{CarbonError, ExitCtPolicy, ExitCtPolicyUpdated, IndustryDeregistered, IndustryTombstone};
+use common::*;
+
+/// 0.01 SOL for every tonne left uncovered.
+const PENALTY_PER_TONNE: u64 = 10_000_000;
+
+/// A verified industry with a 2 SOL bond and 100 CT of the first vintage, 40 of which it
+/// surrendered against a 40 tonne report.
+fn compliant_industry(protocol: &mut Protocol) -> Industry {
+    let industry = protocol.verified_industry("Acme Steel");
+    protocol.send(protocol.deposit_bond_ix(&industry, 2 * MIN_BOND), &[industry.authority]).unwrap();
+    protocol.buy_credits(1, &industry, 100 * CT);
+    protocol.verified_report(&industry, 40 * CT);
+    protocol.send(protocol.burn_ix(&industry, 40 * CT), &[industry.authority]).unwrap();
+    industry
+}
+
+#[test]
+fn deregistration_returns_the_bond_burns_ct_and_leaves_a_tombstone() {
+    let mut protocol = Protocol::new();
+    let industry = compliant_industry(&mut protocol);
+    let registered_at = protocol.industry_state(&industry).created_at;
+    let tombstone = tombstone_pda(&industry.account, registered_at);
+    let bond = protocol.ctx.lamports(&industry.bond_vault);
+    let balance = protocol.ctx.lamports(&industry.authority);
+
+    protocol.send(protocol.deregister_industry_ix(&industry), &[industry.authority]).unwrap();
+
+    assert!(!protocol.ctx.exists(&industry.account));
+    assert!(!protocol.ctx.exists(&industry.bond_vault));
+    assert_eq!(protocol.ctx.token_balance(&industry.ct_account), 0);
+    assert!(protocol.ctx.lamports(&industry.authority) > balance + bond - MIN_BOND / 100);
+
+    let record: IndustryTombstone = protocol.ctx.account(&tombstone);
+    assert_eq!(record.industry, industry.account);
+    assert_eq!(record.authority, industry.authority);
+    assert_eq!(record.company_name, "Acme Steel");
+    assert_eq!(record.registered_at, registered_at);
+    assert_eq!(record.verified_emissions, 40 * CT);
+    assert_eq!(record.total_purchased, 100 * CT);
+    assert_eq!(record.total_burned, 40 * CT);
+    assert_eq!(record.bond_returned, bond);
+    assert_eq!(record.ct_retired, 60 * CT);
+    assert_eq!(record.ct_policy, ExitCtPolicy::Burn);
+
+    let events = protocol.ctx.events::<IndustryDeregistered>();
+    assert_eq!(events[0].industry, industry.account);
+    assert_eq!(events[0].tombstone, tombstone);
+    assert_eq!(events[0].bond_returned, bond);
+    assert_eq!(events[0].ct_retired, 60 * CT);
+}
+
+#[test]
+fn surrender_policy_hands_leftover_ct_to_the_treasury() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let treasury = protocol.treasury;
+    let industry = compliant_industry(&mut protocol);
+    protocol.send(protocol.create_ct_account_ix(&admin, &treasury), &[admin]).unwrap();
+
+    protocol.send(protocol.set_exit_ct_policy_ix(ExitCtPolicy::Surrender), &[admin]).unwrap();
+    assert_eq!(protocol.config().exit_ct_policy, ExitCtPolicy::Surrender);
+    assert_eq!(protocol.ctx.events::<ExitCtPolicyUpdated>()[0].exit_ct_policy, ExitCtPolicy::Surrender);
+
+    // The treasury's CT account has to come along
+    let mut instruction = protocol.deregister_industry_ix(&industry);
+    instruction.accounts[6] = AccountMeta::new_readonly(carbon::ID, false);
+    let result = protocol.send(instruction, &[industry.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::InvalidTokenAccount));
+
+    protocol.send(protocol.deregister_industry_ix(&industry), &[industry.authority]).unwrap();
+
+    assert_eq!(protocol.ctx.token_balance(&protocol.ct_account(&treasury)), 60 * CT);
+    let record: IndustryTombstone = protocol.ctx.account(&protocol.ctx.events::<IndustryDeregistered>()[0].tombstone);
+    assert_eq!(record.ct_retired, 60 * CT);
+    assert_eq!(record.ct_policy, ExitCtPolicy::Surrender);
+
+    let outsider = protocol.ctx.funded_wallet(1);
+    let mut instruction = protocol.set_exit_ct_policy_ix(ExitCtPolicy::Burn);
+    instruction.accounts[1].pubkey = outsider;
+    let result = protocol.send(instruction, &[outsider]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::Unauthorized));
+}
+
+#[test]
+fn obligations_must_be_settled_before_deregistering() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let industry = protocol.verified_industry("Acme Steel");
+    protocol.send(protocol.deposit_bond_ix(&industry, 2 * MIN_BOND), &[industry.authority]).unwrap();
+    protocol.buy_credits(1, &industry, 10 * CT);
+    protocol.verified_report(&industry, 10 * CT);
+    protocol.send(protocol.burn_ix(&industry, 4 * CT), &[industry.authority]).unwrap();
+
+    let result = protocol.send(protocol.deregister_industry_ix(&industry), &[industry.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::ObligationsOutstanding));
+
+    // Closing the period leaves a shortfall that has not been penalised yet
+    let deadline = protocol.ctx.now() + 3_600;
+    protocol.send(protocol.set_compliance_terms_ix(deadline, PENALTY_PER_TONNE), &[admin]).unwrap();
+    protocol.send(protocol.advance_compliance_period_ix(deadline), &[admin]).unwrap();
+    let result = protocol.send(protocol.deregister_industry_ix(&industry), &[industry.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::ObligationsOutstanding));
+
+    // Settling penalises it, but the shortfall is still owed in the new period
+    protocol.send(protocol.settle_period_ix(&admin, &industry), &[admin]).unwrap();
+    let result = protocol.send(protocol.deregister_industry_ix(&industry), &[industry.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::ObligationsOutstanding));
+
+    protocol.send(protocol.burn_vintage_ix(&industry, 1, 6 * CT), &[industry.authority]).unwrap();
+    protocol.send(protocol.deregister_industry_ix(&industry), &[industry.authority]).unwrap();
+
+    let events = protocol.ctx.events::<IndustryDeregistered>();
+    assert_eq!(events[0].bond_returned, 2 * MIN_BOND - 60_000_000);
+    assert_eq!(events[0].ct_retired, 0);
+}
+
+#[test]
+fn open_positions_and_pending_transfers_block_deregistration() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let industry = compliant_industry(&mut protocol);
+
+    let timestamp = protocol.ctx.now() as u64;
+    let report = emission_report_pda(&industry.account, timestamp);
+    protocol.send(protocol.submit_emission_report_ix(&industry, 10 * CT, timestamp), &[industry.authority]).unwrap();
+    let result = protocol.send(protocol.deregister_industry_ix(&industry), &[industry.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::PositionsOpen));
+    protocol.send(protocol.reject_emission_report_ix(&admin, &industry, &report), &[admin]).unwrap();
+
+    let successor = protocol.ctx.funded_wallet(1);
+    protocol.send(protocol.propose_industry_authority_ix(&industry, &successor), &[industry.authority]).unwrap();
+    let result = protocol.send(protocol.deregister_industry_ix(&industry), &[industry.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::AuthorityTransferPending));
+    protocol.send(protocol.cancel_industry_authority_transfer_ix(&industry), &[industry.authority]).unwrap();
+
+    protocol.send(protocol.deregister_industry_ix(&industry), &[industry.authority]).unwrap();
+}
+
+#[test]
+fn vintage_holdings_close_with_the_industry() {
+    let mut protocol = Protocol::new();
+    let industry = compliant_industry(&mut protocol);
+    let other = protocol.verified_industry("Globex Cement");
+    protocol.buy_credits(2, &other, 10 * CT);
+    let holding = vintage_pda(&industry.account, 1);
+    assert_eq!(protocol.vintage_holdings(&industry), vec![holding]);
+
+    let mut instruction = protocol.deregister_industry_ix(&industry);
+    instruction.accounts.retain(|meta| meta.pubkey != holding);
+    let result = protocol.send(instruction, &[industry.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::VintageHoldingsOpen));
+
+    let mut instruction = protocol.deregister_industry_ix(&industry);
+    instruction.accounts.push(AccountMeta::new(vintage_pda(&other.account, 1), false));
+    let result = protocol.send(instruction, &[industry.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::ForeignVintageHolding));
+
+    let balance = protocol.ctx.lamports(&industry.authority);
+    let bond = protocol.ctx.lamports(&industry.bond_vault);
+    // A holding passed twice is only counted once
+    let mut instruction = protocol.deregister_industry_ix(&industry);
+    instruction.accounts.push(AccountMeta::new(holding, false));
+    protocol.send(instruction, &[industry.authority]).unwrap();
+    assert!(!protocol.ctx.exists(&holding));
+    assert!(protocol.ctx.exists(&vintage_pda(&other.account, 1)));
+    assert!(protocol.ctx.lamports(&industry.authority) > balance + bond + vintage_holding_rent() - MIN_BOND / 100);
+}
+
+#[test]
+fn bond_sent_without_a_deposit_is_returned() {
+    let mut protocol = Protocol::new();
+    let industry = protocol.verified_industry("Acme Steel");
+    assert_eq!(protocol.industry_state(&industry).bond_vault_bump, 0);
+
+    // Lamports sent straight to the vault are refunded with the vault's own bump
+    protocol.ctx.airdrop(&industry.bond_vault, MIN_BOND);
+    protocol.send(protocol.deregister_industry_ix(&industry), &[industry.authority]).unwrap();
+
+    assert!(!protocol.ctx.exists(&industry.bond_vault));
+    assert_eq!(protocol.ctx.events::<IndustryDeregistered>()[0].bond_returned, MIN_BOND);
+}
+
+#[test]
+fn each_registration_leaves_its_own_tombstone() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let industry = protocol.verified_industry("Acme Steel");
+    let first = tombstone_pda(&industry.account, protocol.industry_state(&industry).created_at);
+    protocol.send(protocol.deregister_industry_ix(&industry), &[industry.authority]).unwrap();
+
+    // The wallet can register again later; the old tombstone stays untouched
+    protocol.ctx.warp_forward(60);
+    protocol.send(protocol.register_industry_ix(&industry, "Acme Steel Recycling", MIN_BOND), &[industry.authority]).unwrap();
+    protocol.send(protocol.verify_industry_ix(&admin, &industry), &[admin]).unwrap();
+    let second = tombstone_pda(&industry.account, protocol.industry_state(&industry).created_at);
+    protocol.send(protocol.deregister_industry_ix(&industry), &[industry.authority]).unwrap();
+
+    assert_ne!(first, second);
+    let first: IndustryTombstone = protocol.ctx.account(&first);
+    let second: IndustryTombstone = protocol.ctx.account(&second);
+    assert_eq!(first.company_name, "Acme Steel");
+    assert_eq!(second.company_name, "Acme Steel Recycling");
+}
@@ -0,0 +1,389 @@
+mod common;
+
+use anchor_lang::{
+    error::ErrorCode,
+    prelude::Pubkey,
+    solana_program::{program_pack::Pack, rent::Rent},
+};
+use anchor_spl::token::spl_token;
+use carbon::{
+    BondDeposited, BondSlashed, BondWithdrawn, CarbonError, ComplianceStatus, CreditsBurned, CtBalanceSynced, IndustryRegistered,
+    IndustryVerified, ReportRejected, ReportStatus, ReportVerified,
+};
+use common::*;
+
+/// Writes an initialized mint that the program config does not know about.
+fn foreign_mint(protocol: &mut Protocol) -> Pubkey {
+    let mint = Pubkey::new_unique();
+    let mut data = vec![0; spl_token::state::Mint::LEN];
+    spl_token::state::Mint {
+        mint_authority: Some(protocol.admin).into(),
+        decimals: 9,
+        is_initialized: true,
+        ..Default::default()
+    }
+    .pack_into_slice(&mut data);
+
+    protocol.ctx.set_account(mint, AccountState {
+        lamports: Rent::default().minimum_balance(data.len()),
+        data,
+        owner: spl_token::ID,
+        executable: false,
+    });
+
+    mint
+}
+
+#[test]
+fn register_and_verify_industry() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let wallet = protocol.ctx.funded_wallet(10);
+    let industry = protocol.industry(&wallet);
+
+    protocol.send(protocol.register_industry_ix(&industry, "Acme Steel", MIN_BOND), &[wallet]).unwrap();
+    let state = protocol.industry_state(&industry);
+    assert_eq!(state.authority, wallet);
+    assert_eq!(state.company_name, "Acme Steel");
+    assert_eq!(state.bond_amount, MIN_BOND);
+    assert!(!state.verified);
+    assert_eq!(state.compliance_status, ComplianceStatus::Pending);
+    assert_eq!(protocol.ctx.events::<IndustryRegistered>()[0].company_name, "Acme Steel");
+
+    protocol.send(protocol.verify_industry_ix(&admin, &industry), &[admin]).unwrap();
+    let state = protocol.industry_state(&industry);
+    assert!(state.verified);
+    assert_eq!(state.compliance_status, ComplianceStatus::Compliant);
+    assert_eq!(protocol.ctx.events::<IndustryVerified>()[0].verifier, admin);
+}
+
+#[test]
+fn register_requires_minimum_bond() {
+    let mut protocol = Protocol::new();
+    let wallet = protocol.ctx.funded_wallet(10);
+    let industry = protocol.industry(&wallet);
+
+    let result = protocol.send(protocol.register_industry_ix(&industry, "Acme Steel", MIN_BOND - 1), &[wallet]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::InsufficientBond));
+}
+
+#[test]
+fn verify_industry_only_once() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let industry = protocol.verified_industry("Acme Steel");
+
+    let result = protocol.send(protocol.verify_industry_ix(&admin, &industry), &[admin]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::AlreadyVerified));
+}
+
+#[test]
+fn deposit_bond_requires_verification() {
+    let mut protocol = Protocol::new();
+    let wallet = protocol.ctx.funded_wallet(10);
+    let industry = protocol.industry(&wallet);
+    protocol.send(protocol.register_industry_ix(&industry, "Acme Steel", MIN_BOND), &[wallet]).unwrap();
+
+    let result = protocol.send(protocol.deposit_bond_ix(&industry, MIN_BOND), &[wallet]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::NotVerified));
+}
+
+#[test]
+fn deposit_and_withdraw_bond() {
+    let mut protocol = Protocol::new();
+    let industry = protocol.verified_industry("Acme Steel");
+
+    protocol.send(protocol.deposit_bond_ix(&industry, 2 * MIN_BOND), &[industry.authority]).unwrap();
+    assert_eq!(protocol.ctx.lamports(&industry.bond_vault), 2 * MIN_BOND);
+    assert_eq!(protocol.industry_state(&industry).bond_amount, 3 * MIN_BOND);
+    assert_eq!(protocol.ctx.events::<BondDeposited>()[0].amount, 2 * MIN_BOND);
+
+    let balance_before = protocol.ctx.lamports(&industry.authority);
+    protocol.send(protocol.withdraw_bond_ix(&industry, MIN_BOND), &[industry.authority]).unwrap();
+    assert_eq!(protocol.ctx.lamports(&industry.authority), balance_before + MIN_BOND);
+    assert_eq!(protocol.industry_state(&industry).bond_amount, 2 * MIN_BOND);
+    assert_eq!(protocol.ctx.events::<BondWithdrawn>()[0].bond_amount, 2 * MIN_BOND);
+
+    // The vault must stay rent exempt, so its full balance cannot be withdrawn
+    let result = protocol.send(protocol.withdraw_bond_ix(&industry, MIN_BOND), &[industry.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::InsufficientBond));
+
+    let result = protocol.send(protocol.withdraw_bond_ix(&industry, 3 * MIN_BOND), &[industry.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::InsufficientBond));
+}
+
+#[test]
+fn withdraw_bond_requires_compliance() {
+    let mut protocol = Protocol::new();
+    let industry = protocol.verified_industry("Acme Steel");
+    protocol.send(protocol.deposit_bond_ix(&industry, MIN_BOND), &[industry.authority]).unwrap();
+    protocol.verified_report(&industry, 10 * CT);
+
+    let result = protocol.send(protocol.withdraw_bond_ix(&industry, MIN_BOND / 2), &[industry.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::NotCompliant));
+}
+
+#[test]
+fn verified_report_creates_obligation() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let industry = protocol.verified_industry("Acme Steel");
+    let timestamp = protocol.ctx.now() as u64;
+    let report = emission_report_pda(&industry.account, timestamp);
+
+    protocol.send(protocol.submit_emission_report_ix(&industry, 12 * CT, timestamp), &[industry.authority]).unwrap();
+    let state: carbon::EmissionReport = protocol.ctx.account(&report);
+    assert_eq!(state.status, ReportStatus::Pending);
+    assert_eq!(state.compliance_period, 1);
+
+    // The verifier corrects the reported figure before accepting it
+    protocol.send(protocol.verify_emission_report_ix(&admin, &industry, &report, Some(15 * CT)), &[admin]).unwrap();
+    let state: carbon::EmissionReport = protocol.ctx.account(&report);
+    assert_eq!(state.status, ReportStatus::Verified);
+    assert_eq!(state.verifier, Some(admin));
+    assert_eq!(state.effective_co2_emitted(), 15 * CT);
+    assert_eq!(protocol.ctx.events::<ReportVerified>()[0].period_obligation, 15 * CT);
+
+    let industry_state = protocol.industry_state(&industry);
+    assert_eq!(industry_state.verified_emissions, 15 * CT);
+    assert_eq!(industry_state.outstanding_obligation(), 15 * CT);
+    assert_eq!(industry_state.compliance_status, ComplianceStatus::NonCompliant);
+
+    let result = protocol.send(protocol.verify_emission_report_ix(&admin, &industry, &report, None), &[admin]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::ReportAlreadyReviewed));
+}
+
+#[test]
+fn rejected_report_creates_no_obligation() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let industry = protocol.verified_industry("Acme Steel");
+    let timestamp = protocol.ctx.now() as u64;
+    let report = emission_report_pda(&industry.account, timestamp);
+
+    protocol.send(protocol.submit_emission_report_ix(&industry, 12 * CT, timestamp), &[industry.authority]).unwrap();
+    protocol.send(protocol.reject_emission_report_ix(&admin, &industry, &report), &[admin]).unwrap();
+
+    let state: carbon::EmissionReport = protocol.ctx.account(&report);
+    assert_eq!(state.status, ReportStatus::Rejected);
+    assert!(!state.verified);
+    assert_eq!(protocol.ctx.events::<ReportRejected>()[0].report, report);
+    assert_eq!(protocol.industry_state(&industry).outstanding_obligation(), 0);
+
+    let result = protocol.send(protocol.reject_emission_report_ix(&admin, &industry, &report), &[admin]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::ReportAlreadyReviewed));
+}
+
+#[test]
+fn report_must_belong_to_industry() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let industry = protocol.verified_industry("Acme Steel");
+    let other = protocol.verified_industry("Globex Cement");
+    let timestamp = protocol.ctx.now() as u64;
+    let report = emission_report_pda(&industry.account, timestamp);
+    protocol.send(protocol.submit_emission_report_ix(&industry, 12 * CT, timestamp), &[industry.authority]).unwrap();
+
+    let result = protocol.send(protocol.verify_emission_report_ix(&admin, &other, &report, None), &[admin]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::InvalidEmissionReport));
+}
+
+#[test]
+fn burn_settles_obligation() {
+    let mut protocol = Protocol::new();
+    let industry = protocol.verified_industry("Acme Steel");
+    protocol.buy_credits(1, &industry, 20 * CT);
+    protocol.verified_report(&industry, 15 * CT);
+
+    let result = protocol.send(protocol.burn_ix(&industry, 20 * CT), &[industry.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::ExceedsObligation));
+
+    protocol.send(protocol.burn_ix(&industry, 15 * CT), &[industry.authority]).unwrap();
+    assert_eq!(protocol.ctx.token_balance(&industry.ct_account), 5 * CT);
+
+    let state = protocol.industry_state(&industry);
+    assert_eq!(state.ct_balance, 5 * CT);
+    assert_eq!(state.total_burned, 15 * CT);
+    assert_eq!(state.period_surrendered, 15 * CT);
+    assert_eq!(state.compliance_status, ComplianceStatus::Compliant);
+
+    let events = protocol.ctx.events::<CreditsBurned>();
+    assert_eq!(events[0].amount, 15 * CT);
+    assert_eq!(events[0].compliance_status, ComplianceStatus::Compliant);
+}
+
+#[test]
+fn burn_requires_verification_and_balance() {
+    let mut protocol = Protocol::new();
+    let wallet = protocol.ctx.funded_wallet(10);
+    let unverified = protocol.industry(&wallet);
+    protocol.send(protocol.register_industry_ix(&unverified, "Acme Steel", MIN_BOND), &[wallet]).unwrap();
+    protocol.send(protocol.create_ct_account_ix(&wallet, &wallet), &[wallet]).unwrap();
+
+    // Only verified industries can acquire CT, so an unverified one has no vintage to burn
+    let result = protocol.send(protocol.burn_ix(&unverified, CT), &[wallet]);
+    assert_eq!(result.unwrap_err(), anchor_error(ErrorCode::AccountNotInitialized));
+
+    let industry = protocol.verified_industry("Globex Cement");
+    protocol.buy_credits(1, &industry, 5 * CT);
+    protocol.verified_report(&industry, 15 * CT);
+    let result = protocol.send(protocol.burn_ix(&industry, 10 * CT), &[industry.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::InsufficientCT));
+}
+
+#[test]
+fn burn_rejects_foreign_mint() {
+    let mut protocol = Protocol::new();
+    let industry = protocol.verified_industry("Acme Steel");
+    protocol.buy_credits(1, &industry, 10 * CT);
+    protocol.verified_report(&industry, 10 * CT);
+    let mint = foreign_mint(&mut protocol);
+
+    let mut instruction = protocol.burn_ix(&industry, 10 * CT);
+    instruction.accounts[3].pubkey = mint;
+    let result = protocol.send(instruction, &[industry.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::InvalidMint));
+}
+
+#[test]
+fn burn_checks_tokens_actually_held() {
+    let mut protocol = Protocol::new();
+    let seller = protocol.verified_industry("Acme Steel");
+    let buyer = protocol.verified_industry("Globex Cement");
+    protocol.buy_credits(1, &seller, 100 * CT);
+    protocol.verified_report(&seller, 80 * CT);
+
+    // A plain token transfer that bypasses the program's ledger
+    protocol.send(protocol.transfer_ct_ix(&seller.authority, &buyer.authority, 40 * CT), &[seller.authority]).unwrap();
+
+    let result = protocol.send(protocol.burn_ix(&seller, 80 * CT), &[seller.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::InsufficientCT));
+
+    protocol.send(protocol.burn_ix(&seller, 60 * CT), &[seller.authority]).unwrap();
+    assert_eq!(protocol.industry_state(&seller).ct_balance, 0);
+}
+
+#[test]
+fn burn_requires_canonical_ct_account() {
+    let mut protocol = Protocol::new();
+    let industry = protocol.verified_industry("Acme Steel");
+    let other = protocol.verified_industry("Globex Cement");
+    protocol.buy_credits(1, &industry, 10 * CT);
+    protocol.verified_report(&industry, 10 * CT);
+
+    let mut instruction = protocol.burn_ix(&industry, 10 * CT);
+    instruction.accounts[4].pubkey = other.ct_account;
+    let result = protocol.send(instruction, &[industry.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::InvalidTokenAccount));
+}
+
+#[test]
+fn sync_ct_balance_reconciles_ledger() {
+    let mut protocol = Protocol::new();
+    let seller = protocol.verified_industry("Acme Steel");
+    let buyer = protocol.verified_industry("Globex Cement");
+    protocol.buy_credits(1, &seller, 100 * CT);
+    protocol.send(protocol.transfer_ct_ix(&seller.authority, &buyer.authority, 40 * CT), &[seller.authority]).unwrap();
+    assert_eq!(protocol.industry_state(&seller).ct_balance, 100 * CT);
+
+    // Anyone can pay for the sync
+    let keeper = protocol.ctx.funded_wallet(1);
+    protocol.send(protocol.sync_ct_balance_ix(&seller), &[keeper]).unwrap();
+    assert_eq!(protocol.industry_state(&seller).ct_balance, 60 * CT);
+    let events = protocol.ctx.events::<CtBalanceSynced>();
+    assert_eq!(events[0].previous_balance, 100 * CT);
+    assert_eq!(events[0].ct_balance, 60 * CT);
+
+    protocol.send(protocol.sync_ct_balance_ix(&buyer), &[keeper]).unwrap();
+    assert_eq!(protocol.industry_state(&buyer).ct_balance, 40 * CT);
+
+    let mut instruction = protocol.sync_ct_balance_ix(&seller);
+    instruction.accounts[3].pubkey = buyer.ct_account;
+    let result = protocol.send(instruction, &[keeper]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::InvalidTokenAccount));
+}
+
+#[test]
+fn outstanding_obligation_carries_into_next_period() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let industry = protocol.verified_industry("Acme Steel");
+    protocol.buy_credits(1, &industry, 10 * CT);
+    protocol.verified_report(&industry, 15 * CT);
+    protocol.send(protocol.burn_ix(&industry, 10 * CT), &[industry.authority]).unwrap();
+
+    let deadline = protocol.ctx.now() + 3_600;
+    protocol.send(protocol.advance_compliance_period_ix(deadline), &[admin]).unwrap();
+    protocol.verified_report(&industry, 2 * CT);
+
+    let state = protocol.industry_state(&industry);
+    assert_eq!(state.compliance_period, 2);
+    assert_eq!(state.period_obligation, 7 * CT);
+    assert_eq!(state.period_surrendered, 0);
+    assert_eq!(state.compliance_status, ComplianceStatus::NonCompliant);
+}
+
+#[test]
+fn slash_bond_after_missed_deadline() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let industry = protocol.verified_industry("Acme Steel");
+    protocol.send(protocol.deposit_bond_ix(&industry, 2 * MIN_BOND), &[industry.authority]).unwrap();
+    protocol.verified_report(&industry, 10 * CT);
+
+    // 0.01 SOL for every tonne left uncovered
+    let deadline = protocol.ctx.now() + 100;
+    protocol.send(protocol.set_compliance_terms_ix(deadline, 10_000_000), &[admin]).unwrap();
+
+    let result = protocol.send(protocol.slash_bond_ix(&admin, &industry), &[admin]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::DeadlineNotPassed));
+
+    protocol.ctx.warp_to(deadline + 1);
+    protocol.send(protocol.slash_bond_ix(&admin, &industry), &[admin]).unwrap();
+    assert_eq!(protocol.ctx.lamports(&protocol.treasury), 100_000_000);
+    assert_eq!(protocol.ctx.lamports(&industry.bond_vault), 2 * MIN_BOND - 100_000_000);
+
+    let state = protocol.industry_state(&industry);
+    assert_eq!(state.total_slashed, 100_000_000);
+    assert_eq!(state.slash_count, 1);
+    assert_eq!(state.last_slashed_period, 1);
+    assert_eq!(state.bond_amount, 3 * MIN_BOND - 100_000_000);
+
+    let events = protocol.ctx.events::<BondSlashed>();
+    assert_eq!(events[0].penalty, 100_000_000);
+    assert_eq!(events[0].outstanding_obligation, 10 * CT);
+
+    let result = protocol.send(protocol.slash_bond_ix(&admin, &industry), &[admin]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::AlreadySlashed));
+}
+
+#[test]
+fn slash_bond_requires_non_compliance() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let industry = protocol.verified_industry("Acme Steel");
+    protocol.send(protocol.deposit_bond_ix(&industry, MIN_BOND), &[industry.authority]).unwrap();
+    let deadline = protocol.ctx.now() + 100;
+    protocol.send(protocol.set_compliance_terms_ix(deadline, 10_000_000), &[admin]).unwrap();
+    protocol.ctx.warp_to(deadline + 1);
+
+    let result = protocol.send(protocol.slash_bond_ix(&admin, &industry), &[admin]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::NotSlashable));
+}
+
+#[test]
+fn slash_bond_pays_configured_treasury_only() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let industry = protocol.verified_industry("Acme Steel");
+    protocol.send(protocol.deposit_bond_ix(&industry, MIN_BOND), &[industry.authority]).unwrap();
+    protocol.verified_report(&industry, 10 * CT);
+    let deadline = protocol.ctx.now() + 100;
+    protocol.send(protocol.set_compliance_terms_ix(deadline, 10_000_000), &[admin]).unwrap();
+    protocol.ctx.warp_to(deadline + 1);
+
+    let mut instruction = protocol.slash_bond_ix(&admin, &industry);
+    instruction.accounts[5].pubkey = admin;
+    let result = protocol.send(instruction, &[admin]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::InvalidTreasury));
+}
@@ -0,0 +1,241 @@
+mod common;
+
+use anchor_lang::prelude::Pubkey;
+use carbon::{
+    sealed_bid_commitment, CarbonError, IndustryAction, IndustryActionApproved, IndustryActionCancelled,
+    IndustryActionProposed, IndustryProposal, IndustrySignersUpdated, OfferSide,
+};
+use common::*;
+
+/// A verified, bonded industry holding 100 CT bought in `batch_number` and governed by
+/// three signers, any two of which can approve an action.
+fn governed_industry(protocol: &mut Protocol, batch_number: u32) -> (Industry, [Pubkey; 3]) {
+    let industry = protocol.verified_industry("Acme Steel");
+    protocol.send(protocol.deposit_bond_ix(&industry, 2 * MIN_BOND), &[industry.authority]).unwrap();
+    protocol.buy_credits(batch_number, &industry, 100 * CT);
+
+    let signers = [protocol.ctx.funded_wallet(1), protocol.ctx.funded_wallet(1), protocol.ctx.funded_wallet(1)];
+    protocol.send(protocol.set_industry_signers_ix(&industry, &signers, 2, None), &[industry.authority]).unwrap();
+    (industry, signers)
+}
+
+/// Proposes `action` from the first signer and approves it with the second.
+fn approve(protocol: &mut Protocol, industry: &Industry, signers: &[Pubkey; 3], proposal_id: u64, action: IndustryAction) -> Pubkey {
+    protocol.send(protocol.propose_industry_action_ix(&signers[0], industry, proposal_id, action), &[signers[0]]).unwrap();
+    protocol.send(protocol.approve_industry_action_ix(&signers[1], industry, proposal_id), &[signers[1]]).unwrap();
+    industry_proposal_pda(&industry.account, proposal_id)
+}
+
+#[test]
+fn authority_sets_up_the_signer_set() {
+    let mut protocol = Protocol::new();
+    let (industry, signers) = governed_industry(&mut protocol, 1);
+
+    let state = protocol.industry_state(&industry);
+    assert_eq!(state.signers, signers.to_vec());
+    assert_eq!(state.signer_threshold, 2);
+
+    let events = protocol.ctx.events::<IndustrySignersUpdated>();
+    assert_eq!(events[0].industry, industry.account);
+    assert_eq!(events[0].signers, signers.to_vec());
+    assert_eq!(events[0].threshold, 2);
+
+    let other = protocol.verified_industry("Globex Cement");
+    let many: Vec<Pubkey> = (0..11).map(|_| Pubkey::new_unique()).collect();
+    let invalid: [(&[Pubkey], u8); 5] = [
+        (&signers, 0),
+        (&signers, 4),
+        (&[], 1),
+        (&[signers[0], signers[0]], 1),
+        (&many, 2),
+    ];
+    for (set, threshold) in invalid {
+        let result = protocol.send(protocol.set_industry_signers_ix(&other, set, threshold, None), &[other.authority]);
+        assert_eq!(result.unwrap_err(), carbon_error(CarbonError::InvalidSignerSet));
+    }
+}
+
+#[test]
+fn withdrawing_the_bond_takes_enough_approvals() {
+    let mut protocol = Protocol::new();
+    let (industry, signers) = governed_industry(&mut protocol, 1);
+    let proposal = industry_proposal_pda(&industry.account, 1);
+    let vault = protocol.ctx.lamports(&industry.bond_vault);
+
+    let result = protocol.send(protocol.withdraw_bond_ix(&industry, MIN_BOND), &[industry.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::ProposalRequired));
+
+    let action = IndustryAction::WithdrawBond { amount: MIN_BOND };
+    protocol.send(protocol.propose_industry_action_ix(&signers[0], &industry, 1, action.clone()), &[signers[0]]).unwrap();
+    let events = protocol.ctx.events::<IndustryActionProposed>();
+    assert_eq!(events[0].proposal, proposal);
+    assert_eq!(events[0].proposer, signers[0]);
+    assert_eq!(events[0].action, action);
+
+    let result = protocol.send(protocol.approved_withdraw_bond_ix(&industry, MIN_BOND, Some(proposal)), &[industry.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::InsufficientApprovals));
+
+    let outsider = protocol.ctx.funded_wallet(1);
+    let result = protocol.send(protocol.approve_industry_action_ix(&outsider, &industry, 1), &[outsider]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::NotIndustrySigner));
+    let result = protocol.send(protocol.propose_industry_action_ix(&outsider, &industry, 2, action), &[outsider]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::NotIndustrySigner));
+
+    protocol.send(protocol.approve_industry_action_ix(&signers[1], &industry, 1), &[signers[1]]).unwrap();
+    let events = protocol.ctx.events::<IndustryActionApproved>();
+    assert_eq!(events[0].signer, signers[1]);
+    assert_eq!(events[0].approvals, 2);
+    assert_eq!(events[0].threshold, 2);
+    let result = protocol.send(protocol.approve_industry_action_ix(&signers[1], &industry, 1), &[signers[1]]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::AlreadyApproved));
+
+    // The approved amount is the only one the authority can withdraw
+    let result = protocol.send(protocol.approved_withdraw_bond_ix(&industry, 2 * MIN_BOND, Some(proposal)), &[industry.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::ProposalMismatch));
+
+    protocol.send(protocol.approved_withdraw_bond_ix(&industry, MIN_BOND, Some(proposal)), &[industry.authority]).unwrap();
+    assert_eq!(protocol.ctx.lamports(&industry.bond_vault), vault - MIN_BOND);
+    assert!(!protocol.ctx.exists(&proposal));
+
+    // Once carried out, the proposal cannot be replayed
+    let result = protocol.send(protocol.approved_withdraw_bond_ix(&industry, MIN_BOND, Some(proposal)), &[industry.authority]);
+    assert!(result.is_err());
+}
+
+#[test]
+fn burns_and_bids_follow_the_approved_parameters() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let (industry, signers) = governed_industry(&mut protocol, 1);
+    protocol.verified_report(&industry, 40 * CT);
+
+    let burn = approve(&mut protocol, &industry, &signers, 1, IndustryAction::BurnCt { vintage: 1, amount: 40 * CT });
+    let result = protocol.send(protocol.burn_ix(&industry, 40 * CT), &[industry.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::ProposalRequired));
+    let result = protocol.send(protocol.approved_burn_ix(&industry, 1, 30 * CT, Some(burn)), &[industry.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::ProposalMismatch));
+    protocol.send(protocol.approved_burn_ix(&industry, 1, 40 * CT, Some(burn)), &[industry.authority]).unwrap();
+    assert_eq!(protocol.industry_state(&industry).total_burned, 40 * CT);
+
+    protocol.send(protocol.create_auction_ix(&admin, AuctionParams::standard(2)), &[admin]).unwrap();
+    let start = protocol.ctx.now();
+    let action = IndustryAction::PlaceBid { auction: auction_pda(2), token_amount: 10 * CT, max_price_per_token: 8_000_000 };
+    let bid = approve(&mut protocol, &industry, &signers, 2, action);
+
+    let result = protocol.send(protocol.place_bid_ix(2, &industry, 10 * CT, 1), &[industry.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::ProposalRequired));
+    let result = protocol.send(protocol.approved_place_bid_ix(2, &industry, 20 * CT, 1, Some(bid)), &[industry.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::ProposalMismatch));
+    // Still above the approved price
+    let result = protocol.send(protocol.approved_place_bid_ix(2, &industry, 10 * CT, 1, Some(bid)), &[industry.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::BidAboveApprovedPrice));
+
+    protocol.ctx.warp_to(start + 50);
+    protocol.send(protocol.approved_place_bid_ix(2, &industry, 10 * CT, 1, Some(bid)), &[industry.authority]).unwrap();
+    let placed: carbon::Bid = protocol.ctx.account(&bid_pda(&auction_pda(2), &industry.authority, 1));
+    assert_eq!(placed.price_per_token, 7_500_000);
+    assert!(!protocol.ctx.exists(&bid));
+}
+
+#[test]
+fn changing_the_signer_set_takes_approval() {
+    let mut protocol = Protocol::new();
+    let (industry, signers) = governed_industry(&mut protocol, 1);
+    let withdraw = approve(&mut protocol, &industry, &signers, 1, IndustryAction::WithdrawBond { amount: MIN_BOND });
+
+    let result = protocol.send(protocol.set_industry_signers_ix(&industry, &[], 0, None), &[industry.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::ProposalRequired));
+
+    // Replace the second signer; its approval of the withdrawal stops counting
+    let replacement = protocol.ctx.funded_wallet(1);
+    let new_set = [signers[0], replacement, signers[2]];
+    let update = approve(&mut protocol, &industry, &signers, 2, IndustryAction::UpdateSigners { signers: new_set.to_vec(), threshold: 2 });
+    protocol.send(protocol.set_industry_signers_ix(&industry, &new_set, 2, Some(update)), &[industry.authority]).unwrap();
+    assert_eq!(protocol.industry_state(&industry).signers, new_set.to_vec());
+
+    let result = protocol.send(protocol.approved_withdraw_bond_ix(&industry, MIN_BOND, Some(withdraw)), &[industry.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::InsufficientApprovals));
+
+    // A governed industry cannot leave the signers behind by changing hands or deregistering
+    let successor = protocol.ctx.funded_wallet(1);
+    let result = protocol.send(protocol.propose_industry_authority_ix(&industry, &successor), &[industry.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::MultisigGoverned));
+    let result = protocol.send(protocol.deregister_industry_ix(&industry), &[industry.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::MultisigGoverned));
+
+    // Removing the set hands control back to the authority alone
+    protocol.send(protocol.propose_industry_action_ix(&signers[0], &industry, 3, IndustryAction::UpdateSigners { signers: vec![], threshold: 0 }), &[signers[0]]).unwrap();
+    protocol.send(protocol.approve_industry_action_ix(&replacement, &industry, 3), &[replacement]).unwrap();
+    let removal = industry_proposal_pda(&industry.account, 3);
+    protocol.send(protocol.set_industry_signers_ix(&industry, &[], 0, Some(removal)), &[industry.authority]).unwrap();
+
+    protocol.send(protocol.withdraw_bond_ix(&industry, MIN_BOND), &[industry.authority]).unwrap();
+}
+
+#[test]
+fn proposer_can_cancel_and_proposals_stay_with_their_industry() {
+    let mut protocol = Protocol::new();
+    let (industry, signers) = governed_industry(&mut protocol, 1);
+    let (other, other_signers) = governed_industry(&mut protocol, 2);
+    let proposal = approve(&mut protocol, &industry, &signers, 1, IndustryAction::WithdrawBond { amount: MIN_BOND });
+
+    // Another industry's approved proposal does not authorise this one
+    let foreign = approve(&mut protocol, &other, &other_signers, 1, IndustryAction::WithdrawBond { amount: MIN_BOND });
+    let result = protocol.send(protocol.approved_withdraw_bond_ix(&industry, MIN_BOND, Some(foreign)), &[industry.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::InvalidProposal));
+
+    let result = protocol.send(protocol.cancel_industry_action_ix(&signers[1], &industry, 1), &[signers[1]]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::Unauthorized));
+
+    let balance = protocol.ctx.lamports(&signers[0]);
+    protocol.send(protocol.cancel_industry_action_ix(&signers[0], &industry, 1), &[signers[0]]).unwrap();
+    assert!(!protocol.ctx.exists(&proposal));
+    assert!(protocol.ctx.lamports(&signers[0]) > balance);
+    assert_eq!(protocol.ctx.events::<IndustryActionCancelled>()[0].proposal, proposal);
+
+    let remaining: IndustryProposal = protocol.ctx.account(&foreign);
+    assert_eq!(remaining.approvals, vec![other_signers[0], other_signers[1]]);
+}
+
+#[test]
+fn sealed_bids_are_closed_to_governed_industries() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let industry = protocol.verified_industry("Acme Steel");
+    protocol.send(protocol.create_sealed_bid_auction_ix(&admin, SealedBidParams::standard(2)), &[admin]).unwrap();
+    let start = protocol.ctx.now();
+
+    let salt = [7; 32];
+    let commitment = sealed_bid_commitment(&industry.authority, 10 * CT, 8_000_000, &salt);
+    protocol.send(protocol.commit_bid_ix(2, &industry, commitment, 200_000_000, 1), &[industry.authority]).unwrap();
+
+    // A signer set put in place after committing also blocks the reveal
+    let signers = [protocol.ctx.funded_wallet(1), protocol.ctx.funded_wallet(1)];
+    protocol.send(protocol.set_industry_signers_ix(&industry, &signers, 2, None), &[industry.authority]).unwrap();
+
+    let result = protocol.send(protocol.commit_bid_ix(2, &industry, commitment, 200_000_000, 2), &[industry.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::MultisigGoverned));
+
+    protocol.ctx.warp_to(start + 100);
+    let bid = bid_pda(&auction_pda(2), &industry.authority, 1);
+    let result = protocol.send(protocol.reveal_bid_ix(2, &industry, &bid, 10 * CT, 8_000_000, salt), &[industry.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::MultisigGoverned));
+}
+
+#[test]
+fn offers_and_offset_burns_are_closed_to_governed_industries() {
+    let mut protocol = Protocol::new();
+    let (industry, _) = governed_industry(&mut protocol, 1);
+    let buyer = protocol.verified_industry("Globex Cement");
+
+    let result = protocol.send(protocol.create_offer_ix(&industry, 7, OfferSide::Sell, 1, 10 * CT, 8_000_000), &[industry.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::MultisigGoverned));
+
+    protocol.send(protocol.create_offer_ix(&buyer, 7, OfferSide::Buy, 1, 10 * CT, 8_000_000), &[buyer.authority]).unwrap();
+    let result = protocol.send(protocol.accept_offer_ix(&industry, &buyer, 7, OfferSide::Buy, 1), &[industry.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::MultisigGoverned));
+    assert_eq!(protocol.ctx.token_balance(&industry.ct_account), 100 * CT);
+
+    let result = protocol.send(protocol.burn_offsets_ix(&industry, 10 * CT), &[industry.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::MultisigGoverned));
+}
@@ -0,0 +1,218 @@
+mod common;
+
+use anchor_lang::{prelude::Pubkey, AccountSerialize};
+use carbon::{CarbonError, OfferAccepted, OfferCancelled, OfferCreated, OfferSide, VintageHolding};
+use common::*;
+
+const OFFER: u64 = 7;
+const VINTAGE: u32 = 1;
+/// 0.008 SOL per CT.
+const PRICE: u64 = 8_000_000;
+
+/// A seller holding 100 CT and a buyer holding none, both verified.
+fn counterparties(protocol: &mut Protocol) -> (Industry, Industry) {
+    let seller = protocol.verified_industry("Acme Steel");
+    let buyer = protocol.verified_industry("Globex Cement");
+    protocol.buy_credits(1, &seller, 100 * CT);
+    (seller, buyer)
+}
+
+fn offer_account(maker: &Industry) -> Pubkey {
+    offer_pda(&maker.authority, OFFER)
+}
+
+fn holding(protocol: &Protocol, industry: &Industry) -> u64 {
+    protocol.ctx.account::<VintageHolding>(&vintage_pda(&industry.account, VINTAGE)).amount
+}
+
+#[test]
+fn sell_offer_escrows_ct_until_accepted() {
+    let mut protocol = Protocol::new();
+    let (seller, buyer) = counterparties(&mut protocol);
+    let seller_lamports = protocol.ctx.lamports(&seller.authority);
+    let buyer_lamports = protocol.ctx.lamports(&buyer.authority);
+
+    protocol.send(protocol.create_offer_ix(&seller, OFFER, OfferSide::Sell, VINTAGE, 40 * CT, PRICE), &[seller.authority]).unwrap();
+
+    let offer = offer_account(&seller);
+    let escrow = offer_escrow_pda(&offer);
+    let state: carbon::Offer = protocol.ctx.account(&offer);
+    assert_eq!(state.maker, seller.authority);
+    assert_eq!(state.side, OfferSide::Sell);
+    assert_eq!(state.total_price, 320_000_000);
+    assert_eq!(protocol.ctx.token_balance(&escrow), 40 * CT);
+    assert_eq!(protocol.ctx.token_balance(&seller.ct_account), 60 * CT);
+    assert_eq!(protocol.industry_state(&seller).ct_balance, 60 * CT);
+    assert_eq!(holding(&protocol, &seller), 60 * CT);
+    assert_eq!(protocol.ctx.events::<OfferCreated>()[0].total_price, 320_000_000);
+
+    protocol.send(protocol.accept_offer_ix(&buyer, &seller, OFFER, OfferSide::Sell, VINTAGE), &[buyer.authority]).unwrap();
+
+    assert!(!protocol.ctx.exists(&offer));
+    assert!(!protocol.ctx.exists(&escrow));
+    assert_eq!(protocol.ctx.token_balance(&buyer.ct_account), 40 * CT);
+    let buyer_state = protocol.industry_state(&buyer);
+    assert_eq!(buyer_state.ct_balance, 40 * CT);
+    assert_eq!(buyer_state.total_purchased, 40 * CT);
+    let seller_state = protocol.industry_state(&seller);
+    assert_eq!(seller_state.ct_balance, 60 * CT);
+    assert_eq!(seller_state.total_purchased, 100 * CT);
+    assert_eq!(holding(&protocol, &buyer), 40 * CT);
+
+    // The seller is paid and gets back the rent of the offer and its escrow
+    assert_eq!(protocol.ctx.lamports(&seller.authority), seller_lamports + 320_000_000);
+    assert_eq!(protocol.ctx.lamports(&buyer.authority), buyer_lamports - 320_000_000 - vintage_holding_rent());
+
+    let events = protocol.ctx.events::<OfferAccepted>();
+    assert_eq!(events[0].taker, buyer.authority);
+    assert_eq!(events[0].token_amount, 40 * CT);
+}
+
+#[test]
+fn buy_offer_escrows_sol_until_accepted() {
+    let mut protocol = Protocol::new();
+    let (seller, buyer) = counterparties(&mut protocol);
+    let seller_lamports = protocol.ctx.lamports(&seller.authority);
+    let buyer_lamports = protocol.ctx.lamports(&buyer.authority);
+
+    protocol.send(protocol.create_offer_ix(&buyer, OFFER, OfferSide::Buy, VINTAGE, 30 * CT, PRICE), &[buyer.authority]).unwrap();
+    let offer = offer_account(&buyer);
+    assert!(protocol.ctx.lamports(&offer) > 240_000_000);
+    assert_eq!(protocol.ctx.token_balance(&offer_escrow_pda(&offer)), 0);
+
+    protocol.send(protocol.accept_offer_ix(&seller, &buyer, OFFER, OfferSide::Buy, VINTAGE), &[seller.authority]).unwrap();
+
+    assert!(!protocol.ctx.exists(&offer));
+    assert_eq!(protocol.ctx.token_balance(&seller.ct_account), 70 * CT);
+    assert_eq!(protocol.ctx.token_balance(&buyer.ct_account), 30 * CT);
+    assert_eq!(protocol.industry_state(&seller).ct_balance, 70 * CT);
+    let buyer_state = protocol.industry_state(&buyer);
+    assert_eq!(buyer_state.ct_balance, 30 * CT);
+    assert_eq!(buyer_state.total_purchased, 30 * CT);
+    assert_eq!(holding(&protocol, &seller), 70 * CT);
+    assert_eq!(holding(&protocol, &buyer), 30 * CT);
+    assert_eq!(protocol.ctx.lamports(&seller.authority), seller_lamports + 240_000_000);
+    assert_eq!(protocol.ctx.lamports(&buyer.authority), buyer_lamports - 240_000_000 - vintage_holding_rent());
+}
+
+#[test]
+fn cancel_offer_returns_escrow() {
+    let mut protocol = Protocol::new();
+    let (seller, buyer) = counterparties(&mut protocol);
+    let seller_lamports = protocol.ctx.lamports(&seller.authority);
+    let buyer_lamports = protocol.ctx.lamports(&buyer.authority);
+
+    protocol.send(protocol.create_offer_ix(&seller, OFFER, OfferSide::Sell, VINTAGE, 40 * CT, PRICE), &[seller.authority]).unwrap();
+    protocol.send(protocol.cancel_offer_ix(&seller, OFFER, VINTAGE), &[seller.authority]).unwrap();
+    assert_eq!(protocol.ctx.events::<OfferCancelled>()[0].side, OfferSide::Sell);
+
+    assert!(!protocol.ctx.exists(&offer_account(&seller)));
+    assert_eq!(protocol.ctx.token_balance(&seller.ct_account), 100 * CT);
+    assert_eq!(protocol.industry_state(&seller).ct_balance, 100 * CT);
+    assert_eq!(holding(&protocol, &seller), 100 * CT);
+    assert_eq!(protocol.ctx.lamports(&seller.authority), seller_lamports);
+
+    protocol.send(protocol.create_offer_ix(&buyer, OFFER, OfferSide::Buy, VINTAGE, 30 * CT, PRICE), &[buyer.authority]).unwrap();
+    protocol.send(protocol.cancel_offer_ix(&buyer, OFFER, VINTAGE), &[buyer.authority]).unwrap();
+
+    assert!(!protocol.ctx.exists(&offer_account(&buyer)));
+    assert_eq!(protocol.ctx.lamports(&buyer.authority), buyer_lamports - vintage_holding_rent());
+    assert_eq!(protocol.ctx.events::<OfferCancelled>()[0].side, OfferSide::Buy);
+}
+
+#[test]
+fn create_offer_validates_terms() {
+    let mut protocol = Protocol::new();
+    let (seller, _) = counterparties(&mut protocol);
+    let cases = [
+        (OfferSide::Sell, 101 * CT, PRICE, CarbonError::InsufficientCT),
+        (OfferSide::Sell, 0, PRICE, CarbonError::InvalidTokenAmount),
+        (OfferSide::Buy, 10 * CT, 0, CarbonError::InvalidOfferPrice),
+        // Worth less than a lamport in total
+        (OfferSide::Buy, 1, PRICE, CarbonError::InvalidOfferPrice),
+    ];
+
+    for (side, token_amount, price, error) in cases {
+        let result = protocol.send(protocol.create_offer_ix(&seller, OFFER, side, VINTAGE, token_amount, price), &[seller.authority]);
+        assert_eq!(result.unwrap_err(), carbon_error(error));
+    }
+
+    let wallet = protocol.ctx.funded_wallet(10);
+    let unverified = protocol.industry(&wallet);
+    protocol.send(protocol.register_industry_ix(&unverified, "Initech", MIN_BOND), &[wallet]).unwrap();
+    protocol.send(protocol.create_ct_account_ix(&wallet, &wallet), &[wallet]).unwrap();
+    let result = protocol.send(protocol.create_offer_ix(&unverified, OFFER, OfferSide::Buy, VINTAGE, CT, PRICE), &[wallet]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::NotVerified));
+}
+
+#[test]
+fn accept_offer_checks_counterparties() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let (seller, buyer) = counterparties(&mut protocol);
+    protocol.send(protocol.create_offer_ix(&buyer, OFFER, OfferSide::Buy, VINTAGE, 30 * CT, PRICE), &[buyer.authority]).unwrap();
+
+    let result = protocol.send(protocol.accept_offer_ix(&buyer, &buyer, OFFER, OfferSide::Buy, VINTAGE), &[buyer.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::SelfTrade));
+
+    let empty = protocol.verified_industry("Initech");
+    let result = protocol.send(protocol.accept_offer_ix(&empty, &buyer, OFFER, OfferSide::Buy, VINTAGE), &[empty.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::InsufficientCT));
+
+    protocol.send(protocol.set_industry_suspension_ix(&admin, &seller, true), &[admin]).unwrap();
+    let result = protocol.send(protocol.accept_offer_ix(&seller, &buyer, OFFER, OfferSide::Buy, VINTAGE), &[seller.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::IndustrySuspended));
+}
+
+#[test]
+fn buy_offer_requires_verified_maker() {
+    let mut protocol = Protocol::new();
+    let (seller, buyer) = counterparties(&mut protocol);
+    protocol.send(protocol.create_offer_ix(&buyer, OFFER, OfferSide::Buy, VINTAGE, 30 * CT, PRICE), &[buyer.authority]).unwrap();
+
+    // No instruction unverifies a standing record, so the flag is cleared in place
+    let mut state = protocol.industry_state(&buyer);
+    state.verified = false;
+    let mut account = protocol.ctx.get_account(&buyer.account).unwrap().clone();
+    state.try_serialize(&mut account.data.as_mut_slice()).unwrap();
+    protocol.ctx.set_account(buyer.account, account);
+
+    let result = protocol.send(protocol.accept_offer_ix(&seller, &buyer, OFFER, OfferSide::Buy, VINTAGE), &[seller.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::NotVerified));
+    assert_eq!(protocol.ctx.token_balance(&seller.ct_account), 100 * CT);
+}
+
+#[test]
+fn only_maker_can_cancel() {
+    let mut protocol = Protocol::new();
+    let (seller, buyer) = counterparties(&mut protocol);
+    protocol.send(protocol.create_offer_ix(&seller, OFFER, OfferSide::Sell, VINTAGE, 40 * CT, PRICE), &[seller.authority]).unwrap();
+
+    let offer = offer_account(&seller);
+    let mut instruction = protocol.cancel_offer_ix(&buyer, OFFER, VINTAGE);
+    instruction.accounts[0].pubkey = offer;
+    instruction.accounts[2].pubkey = vintage_pda(&seller.account, VINTAGE);
+    instruction.accounts[5].pubkey = offer_escrow_pda(&offer);
+    let result = protocol.send(instruction, &[buyer.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::Unauthorized));
+}
+
+#[test]
+fn offers_settle_through_the_transfer_hook() {
+    let mut protocol = Protocol::with_transfer_hook();
+    let (seller, buyer) = counterparties(&mut protocol);
+
+    protocol.send(protocol.create_offer_ix(&seller, OFFER, OfferSide::Sell, VINTAGE, 40 * CT, PRICE), &[seller.authority]).unwrap();
+    protocol.send(protocol.accept_offer_ix(&buyer, &seller, OFFER, OfferSide::Sell, VINTAGE), &[buyer.authority]).unwrap();
+
+    protocol.send(protocol.create_offer_ix(&seller, OFFER, OfferSide::Buy, VINTAGE, 10 * CT, PRICE), &[seller.authority]).unwrap();
+    protocol.send(protocol.accept_offer_ix(&buyer, &seller, OFFER, OfferSide::Buy, VINTAGE), &[buyer.authority]).unwrap();
+
+    protocol.send(protocol.create_offer_ix(&seller, OFFER, OfferSide::Sell, VINTAGE, 5 * CT, PRICE), &[seller.authority]).unwrap();
+    protocol.send(protocol.cancel_offer_ix(&seller, OFFER, VINTAGE), &[seller.authority]).unwrap();
+
+    assert_eq!(protocol.ctx.token_balance(&seller.ct_account), 70 * CT);
+    assert_eq!(protocol.ctx.token_balance(&buyer.ct_account), 30 * CT);
+    assert_eq!(protocol.industry_state(&seller).ct_balance, 70 * CT);
+    assert_eq!(protocol.industry_state(&buyer).ct_balance, 30 * CT);
+}
@@ -0,0 +1,191 @@
+mod common;
+
+use anchor_lang::{error::ErrorCode, prelude::Pubkey};
+use carbon::{
+    CarbonError, OffsetCreditsBurned, OffsetCreditsIssued, OffsetKind, OffsetProject, OffsetProjectApproved,
+    RemovalReport, ReportStatus,
+};
+use common::*;
+
+const PROJECT: u64 = 3;
+
+/// A registered and approved reforestation project, returning its developer and address.
+fn approved_project(protocol: &mut Protocol) -> (Pubkey, Pubkey) {
+    let admin = protocol.admin;
+    let developer = protocol.ctx.funded_wallet(10);
+    let project = offset_project_pda(&developer, PROJECT);
+
+    protocol.send(protocol.register_offset_project_ix(&developer, PROJECT, OffsetKind::Reforestation), &[developer]).unwrap();
+    protocol.send(protocol.approve_offset_project_ix(&admin, &project), &[admin]).unwrap();
+
+    (developer, project)
+}
+
+/// Submits a removal report for `beneficiary` and has the admin issue its credits.
+fn issue_offsets(protocol: &mut Protocol, developer: &Pubkey, project: &Pubkey, beneficiary: &Industry, co2_removed: u64) -> Pubkey {
+    let admin = protocol.admin;
+    let timestamp = protocol.ctx.now() as u64;
+    let report = removal_report_pda(project, timestamp);
+
+    protocol.send(protocol.submit_removal_report_ix(developer, project, beneficiary, co2_removed, timestamp), &[*developer]).unwrap();
+    protocol.send(protocol.issue_offset_credits_ix(&admin, project, &report, beneficiary), &[admin]).unwrap();
+    protocol.ctx.warp_forward(1);
+
+    report
+}
+
+#[test]
+fn verifier_approves_registered_projects() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let developer = protocol.ctx.funded_wallet(10);
+    let project = offset_project_pda(&developer, PROJECT);
+
+    protocol.send(protocol.register_offset_project_ix(&developer, PROJECT, OffsetKind::MethaneCapture), &[developer]).unwrap();
+    let state: OffsetProject = protocol.ctx.account(&project);
+    assert_eq!(state.developer, developer);
+    assert_eq!(state.kind, OffsetKind::MethaneCapture);
+    assert!(!state.approved);
+
+    let intruder = protocol.ctx.funded_wallet(1);
+    let result = protocol.send(protocol.approve_offset_project_ix(&intruder, &project), &[intruder]);
+    assert_eq!(result.unwrap_err(), anchor_error(ErrorCode::AccountNotInitialized));
+
+    protocol.send(protocol.approve_offset_project_ix(&admin, &project), &[admin]).unwrap();
+    let state: OffsetProject = protocol.ctx.account(&project);
+    assert!(state.approved);
+    assert_eq!(state.approved_by, Some(admin));
+    assert_eq!(protocol.ctx.events::<OffsetProjectApproved>()[0].verifier, admin);
+
+    let result = protocol.send(protocol.approve_offset_project_ix(&admin, &project), &[admin]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::ProjectAlreadyApproved));
+}
+
+#[test]
+fn unapproved_projects_cannot_report_removals() {
+    let mut protocol = Protocol::new();
+    let industry = protocol.verified_industry("Acme Steel");
+    let developer = protocol.ctx.funded_wallet(10);
+    let project = offset_project_pda(&developer, PROJECT);
+    protocol.send(protocol.register_offset_project_ix(&developer, PROJECT, OffsetKind::Reforestation), &[developer]).unwrap();
+
+    let timestamp = protocol.ctx.now() as u64;
+    let result = protocol.send(protocol.submit_removal_report_ix(&developer, &project, &industry, 10 * CT, timestamp), &[developer]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::ProjectNotApproved));
+}
+
+#[test]
+fn verified_removals_are_issued_as_offset_credits() {
+    let mut protocol = Protocol::new();
+    let industry = protocol.verified_industry("Acme Steel");
+    let (developer, project) = approved_project(&mut protocol);
+
+    let report = issue_offsets(&mut protocol, &developer, &project, &industry, 30 * CT);
+
+    let state: RemovalReport = protocol.ctx.account(&report);
+    assert_eq!(state.status, ReportStatus::Verified);
+    assert_eq!(state.verifier, Some(protocol.admin));
+    assert_eq!(protocol.ctx.account::<OffsetProject>(&project).total_issued, 30 * CT);
+    assert_eq!(protocol.ctx.token_balance(&industry.ct_account), 30 * CT);
+    let industry_state = protocol.industry_state(&industry);
+    assert_eq!(industry_state.ct_balance, 30 * CT);
+    assert_eq!(industry_state.offset_balance, 30 * CT);
+
+    let admin = protocol.admin;
+    let result = protocol.send(protocol.issue_offset_credits_ix(&admin, &project, &report, &industry), &[admin]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::ReportAlreadyReviewed));
+}
+
+#[test]
+fn credits_go_only_to_the_reported_beneficiary() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let industry = protocol.verified_industry("Acme Steel");
+    let other = protocol.verified_industry("Globex Cement");
+    let (developer, project) = approved_project(&mut protocol);
+
+    let timestamp = protocol.ctx.now() as u64;
+    let report = removal_report_pda(&project, timestamp);
+    protocol.send(protocol.submit_removal_report_ix(&developer, &project, &industry, 10 * CT, timestamp), &[developer]).unwrap();
+
+    let result = protocol.send(protocol.issue_offset_credits_ix(&admin, &project, &report, &other), &[admin]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::InvalidRemovalReport));
+
+    let balance_before = protocol.ctx.token_balance(&industry.ct_account);
+    protocol.send(protocol.issue_offset_credits_ix(&admin, &project, &report, &industry), &[admin]).unwrap();
+    assert_eq!(protocol.ctx.token_balance(&industry.ct_account), balance_before + 10 * CT);
+    assert_eq!(protocol.ctx.events::<OffsetCreditsIssued>()[0].industry, industry.account);
+}
+
+#[test]
+fn offsets_cover_only_a_fraction_of_the_obligation() {
+    let mut protocol = Protocol::new();
+    let industry = protocol.verified_industry("Acme Steel");
+    let (developer, project) = approved_project(&mut protocol);
+    issue_offsets(&mut protocol, &developer, &project, &industry, 30 * CT);
+    protocol.verified_report(&industry, 100 * CT);
+
+    // The default limit lets offsets cover 10% of the obligation
+    let result = protocol.send(protocol.burn_offsets_ix(&industry, 11 * CT), &[industry.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::OffsetLimitExceeded));
+    protocol.send(protocol.burn_offsets_ix(&industry, 6 * CT), &[industry.authority]).unwrap();
+    protocol.send(protocol.burn_offsets_ix(&industry, 4 * CT), &[industry.authority]).unwrap();
+    assert_eq!(protocol.ctx.events::<OffsetCreditsBurned>()[0].period_offsets_surrendered, 10 * CT);
+    let result = protocol.send(protocol.burn_offsets_ix(&industry, CT), &[industry.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::OffsetLimitExceeded));
+
+    let state = protocol.industry_state(&industry);
+    assert_eq!(state.offset_balance, 20 * CT);
+    assert_eq!(state.period_offsets_surrendered, 10 * CT);
+    assert_eq!(state.period_surrendered, 10 * CT);
+    assert_eq!(state.ct_balance, 20 * CT);
+    assert_eq!(state.outstanding_obligation(), 90 * CT);
+}
+
+#[test]
+fn regulator_sets_each_industrys_offset_limit() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let industry = protocol.verified_industry("Acme Steel");
+    let (developer, project) = approved_project(&mut protocol);
+    issue_offsets(&mut protocol, &developer, &project, &industry, 30 * CT);
+    protocol.verified_report(&industry, 100 * CT);
+
+    let result = protocol.send(protocol.set_offset_limit_ix(&admin, &industry, 10_001), &[admin]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::InvalidOffsetLimit));
+
+    protocol.send(protocol.set_offset_limit_ix(&admin, &industry, 2_500), &[admin]).unwrap();
+    assert_eq!(protocol.industry_state(&industry).max_offset_bps, 2_500);
+    protocol.send(protocol.burn_offsets_ix(&industry, 25 * CT), &[industry.authority]).unwrap();
+    assert_eq!(protocol.ctx.events::<OffsetCreditsBurned>()[0].period_offsets_surrendered, 25 * CT);
+
+    protocol.send(protocol.set_offset_limit_ix(&admin, &industry, 0), &[admin]).unwrap();
+    let result = protocol.send(protocol.burn_offsets_ix(&industry, CT), &[industry.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::OffsetLimitExceeded));
+}
+
+#[test]
+fn offset_credits_are_kept_apart_from_allowances() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let industry = protocol.verified_industry("Acme Steel");
+    let (developer, project) = approved_project(&mut protocol);
+    protocol.send(protocol.allocate_free_allowances_ix(&admin, &industry, 5 * CT), &[admin]).unwrap();
+    issue_offsets(&mut protocol, &developer, &project, &industry, 30 * CT);
+    protocol.verified_report(&industry, 50 * CT);
+
+    // Offsets hold no vintage, so the allowance burn cannot draw on them
+    let result = protocol.send(protocol.burn_ix(&industry, 10 * CT), &[industry.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::InsufficientVintageBalance));
+
+    // Nor can allowances be passed off as offsets
+    protocol.send(protocol.set_offset_limit_ix(&admin, &industry, 10_000), &[admin]).unwrap();
+    let result = protocol.send(protocol.burn_offsets_ix(&industry, 31 * CT), &[industry.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::InsufficientOffsetCredits));
+
+    protocol.send(protocol.burn_ix(&industry, 5 * CT), &[industry.authority]).unwrap();
+    protocol.send(protocol.burn_offsets_ix(&industry, 30 * CT), &[industry.authority]).unwrap();
+    let state = protocol.industry_state(&industry);
+    assert_eq!(state.period_surrendered, 35 * CT);
+    assert_eq!(state.ct_balance, 0);
+}
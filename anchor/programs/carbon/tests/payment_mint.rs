@@ -0,0 +1,210 @@
+mod common;
+
+use anchor_lang::{
+    error::ErrorCode,
+    prelude::Pubkey,
+    solana_program::instruction::AccountMeta,
+};
+use carbon::{CarbonError, PaymentMintSet, ProceedsWithdrawn};
+use common::*;
+
+const BATCH: u32 = 1;
+/// One unit of a six-decimal stablecoin; standard auctions then run from 10 down to 5 per CT.
+const USD: u64 = 1_000_000;
+
+/// A standard Dutch auction denominated in a fresh stablecoin, with `industry` holding 1,000 USD.
+fn stablecoin_auction(protocol: &mut Protocol, industry: &Industry) -> Pubkey {
+    let admin = protocol.admin;
+    let mint = protocol.create_payment_mint(6);
+    protocol.send(protocol.create_auction_ix(&admin, AuctionParams::standard(BATCH)), &[admin]).unwrap();
+    protocol.send(protocol.set_payment_mint_ix(&admin, BATCH, &mint), &[admin]).unwrap();
+    protocol.fund_payment_account(&mint, &industry.authority, 1_000 * USD);
+    mint
+}
+
+#[test]
+fn operator_denominates_an_auction_in_a_payment_mint() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let mint = protocol.create_payment_mint(6);
+    let auction = auction_pda(BATCH);
+    protocol.send(protocol.create_auction_ix(&admin, AuctionParams::standard(BATCH)), &[admin]).unwrap();
+
+    protocol.send(protocol.set_payment_mint_ix(&admin, BATCH, &mint), &[admin]).unwrap();
+
+    assert_eq!(protocol.auction_state(BATCH).payment_mint, Some(mint));
+    assert_eq!(protocol.ctx.token_balance(&escrow_token_pda(&auction)), 0);
+
+    let events = protocol.ctx.events::<PaymentMintSet>();
+    assert_eq!(events[0].auction, auction);
+    assert_eq!(events[0].payment_mint, mint);
+    assert_eq!(events[0].escrow_token, escrow_token_pda(&auction));
+}
+
+#[test]
+fn bids_are_paid_and_refunded_in_the_payment_mint() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let industry = protocol.verified_industry("Acme Steel");
+    let mint = stablecoin_auction(&mut protocol, &industry);
+    let auction = auction_pda(BATCH);
+    let bidder_token = protocol.payment_accounts(BATCH, &industry.authority).token_account.unwrap();
+    let escrow_lamports = protocol.ctx.lamports(&escrow_pda(&auction));
+
+    protocol.send(protocol.place_bid_ix(BATCH, &industry, 10 * CT, 1), &[industry.authority]).unwrap();
+
+    assert_eq!(protocol.ctx.token_balance(&bidder_token), 900 * USD);
+    assert_eq!(protocol.ctx.token_balance(&escrow_token_pda(&auction)), 100 * USD);
+    assert_eq!(protocol.ctx.lamports(&escrow_pda(&auction)), escrow_lamports);
+
+    // Undersubscribed, so the auction clears at the 5 USD reserve and half the payment comes back
+    protocol.ctx.warp_forward(AuctionParams::standard(BATCH).duration_seconds);
+    protocol.send(protocol.finalize_auction_ix(&admin, BATCH), &[admin]).unwrap();
+
+    // The escrowed refund is not the treasury's to take
+    let treasury = protocol.treasury;
+    let treasury_token = protocol.fund_payment_account(&mint, &treasury, 0);
+    let result = protocol.send(protocol.withdraw_proceeds_ix(&admin, BATCH, &treasury), &[admin]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::BidsOutstanding));
+
+    protocol.send(protocol.claim_tokens_ix(BATCH, &industry, &bid_pda(&auction, &industry.authority, 1)), &[industry.authority]).unwrap();
+
+    assert_eq!(protocol.ctx.token_balance(&bidder_token), 950 * USD);
+    assert_eq!(protocol.ctx.token_balance(&escrow_token_pda(&auction)), 50 * USD);
+    assert_eq!(protocol.ctx.token_balance(&industry.ct_account), 10 * CT);
+
+    protocol.send(protocol.withdraw_proceeds_ix(&admin, BATCH, &treasury), &[admin]).unwrap();
+
+    assert_eq!(protocol.ctx.token_balance(&treasury_token), 50 * USD);
+    let events = protocol.ctx.events::<ProceedsWithdrawn>();
+    assert_eq!(events[0].escrow, escrow_token_pda(&auction));
+    assert_eq!(events[0].treasury, treasury_token);
+    assert_eq!(events[0].payment_mint, Some(mint));
+    assert_eq!(events[0].amount, 50 * USD);
+
+    // Closing the auction also closes its emptied escrow token account
+    protocol.send(protocol.close_auction_ix(&admin, BATCH, &admin), &[admin]).unwrap();
+    assert!(!protocol.ctx.exists(&escrow_token_pda(&auction)));
+}
+
+#[test]
+fn operator_settles_an_unclaimed_bid_in_the_payment_mint() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let treasury = protocol.treasury;
+    let industry = protocol.verified_industry("Acme Steel");
+    let mint = stablecoin_auction(&mut protocol, &industry);
+    let auction = auction_pda(BATCH);
+    let bidder_token = protocol.payment_accounts(BATCH, &industry.authority).token_account.unwrap();
+    let treasury_token = protocol.fund_payment_account(&mint, &treasury, 0);
+
+    protocol.send(protocol.place_bid_ix(BATCH, &industry, 10 * CT, 1), &[industry.authority]).unwrap();
+    protocol.ctx.warp_forward(AuctionParams::standard(BATCH).duration_seconds);
+    protocol.send(protocol.finalize_auction_ix(&admin, BATCH), &[admin]).unwrap();
+
+    // The bidder never claims; the operator settles it so the treasury is not held up
+    let bid = bid_pda(&auction, &industry.authority, 1);
+    protocol.send(protocol.claim_tokens_for_ix(&admin, BATCH, &industry, &bid), &[admin]).unwrap();
+    assert_eq!(protocol.ctx.token_balance(&bidder_token), 950 * USD);
+    assert_eq!(protocol.ctx.token_balance(&industry.ct_account), 10 * CT);
+
+    protocol.send(protocol.withdraw_proceeds_ix(&admin, BATCH, &treasury), &[admin]).unwrap();
+    assert_eq!(protocol.ctx.token_balance(&treasury_token), 50 * USD);
+    assert_eq!(protocol.ctx.token_balance(&escrow_token_pda(&auction)), 0);
+}
+
+#[test]
+fn cancelled_auction_refunds_in_the_payment_mint() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let industry = protocol.verified_industry("Acme Steel");
+    stablecoin_auction(&mut protocol, &industry);
+    let bidder_token = protocol.payment_accounts(BATCH, &industry.authority).token_account.unwrap();
+
+    protocol.send(protocol.place_bid_ix(BATCH, &industry, 10 * CT, 1), &[industry.authority]).unwrap();
+    protocol.send(protocol.cancel_auction_ix(&admin, BATCH), &[admin]).unwrap();
+    let bid = bid_pda(&auction_pda(BATCH), &industry.authority, 1);
+    protocol.send(protocol.refund_bid_ix(BATCH, &industry, &bid), &[industry.authority]).unwrap();
+
+    assert_eq!(protocol.ctx.token_balance(&bidder_token), 1_000 * USD);
+    assert_eq!(protocol.ctx.token_balance(&escrow_token_pda(&auction_pda(BATCH))), 0);
+}
+
+#[test]
+fn payment_accounts_must_match_the_auction() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let industry = protocol.verified_industry("Acme Steel");
+
+    // Lamport accounts for a token-denominated auction
+    protocol.send(protocol.create_auction_ix(&admin, AuctionParams::standard(BATCH)), &[admin]).unwrap();
+    let lamport_bid = protocol.place_bid_ix(BATCH, &industry, CT, 1);
+    let mint = protocol.create_payment_mint(6);
+    protocol.send(protocol.set_payment_mint_ix(&admin, BATCH, &mint), &[admin]).unwrap();
+    protocol.fund_payment_account(&mint, &industry.authority, 1_000 * USD);
+    let result = protocol.send(lamport_bid, &[industry.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::InvalidPaymentMint));
+
+    // Another mint's accounts
+    let other_mint = protocol.create_payment_mint(6);
+    let other_token = protocol.fund_payment_account(&other_mint, &industry.authority, 1_000 * USD);
+    let mut instruction = protocol.place_bid_ix(BATCH, &industry, CT, 1);
+    let payment = protocol.payment_accounts(BATCH, &industry.authority);
+    for meta in instruction.accounts.iter_mut() {
+        if Some(meta.pubkey) == payment.payment_mint {
+            meta.pubkey = other_mint;
+        } else if Some(meta.pubkey) == payment.token_account {
+            meta.pubkey = other_token;
+        }
+    }
+    let result = protocol.send(instruction, &[industry.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::InvalidPaymentMint));
+
+    // Token accounts for an auction paid in lamports, which has no escrow token account
+    protocol.send(protocol.create_auction_ix(&admin, AuctionParams::standard(2)), &[admin]).unwrap();
+    let mut instruction = protocol.place_bid_ix(BATCH, &industry, CT, 1);
+    instruction.accounts[0].pubkey = auction_pda(2);
+    instruction.accounts[1].pubkey = bid_pda(&auction_pda(2), &industry.authority, 1);
+    instruction.accounts[2].pubkey = position_pda(&auction_pda(2), &industry.authority);
+    instruction.accounts[4].pubkey = escrow_pda(&auction_pda(2));
+    instruction.accounts[6] = AccountMeta::new_readonly(carbon::ID, false);
+    let result = protocol.send(instruction, &[industry.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::InvalidPaymentMint));
+}
+
+#[test]
+fn payment_mint_is_fixed_before_bidding_and_only_for_dutch_auctions() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let industry = protocol.verified_industry("Acme Steel");
+    let mint = protocol.create_payment_mint(6);
+    protocol.send(protocol.create_auction_ix(&admin, AuctionParams::standard(BATCH)), &[admin]).unwrap();
+    protocol.send(protocol.create_sealed_bid_auction_ix(&admin, SealedBidParams::standard(2)), &[admin]).unwrap();
+
+    let result = protocol.send(protocol.set_payment_mint_ix(&admin, 2, &mint), &[admin]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::InvalidAuctionKind));
+
+    let intruder = protocol.ctx.funded_wallet(1);
+    let result = protocol.send(protocol.set_payment_mint_ix(&intruder, BATCH, &mint), &[intruder]);
+    assert_eq!(result.unwrap_err(), anchor_error(ErrorCode::AccountNotInitialized));
+
+    protocol.send(protocol.place_bid_ix(BATCH, &industry, CT, 1), &[industry.authority]).unwrap();
+    let result = protocol.send(protocol.set_payment_mint_ix(&admin, BATCH, &mint), &[admin]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::AuctionHasBids));
+}
+
+#[test]
+fn token_denominated_auction_does_not_trigger_the_reserve() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let industry = protocol.verified_industry("Acme Steel");
+    stablecoin_auction(&mut protocol, &industry);
+    protocol.send(protocol.set_reserve_config_ix(1, 12_000_000, 100 * CT), &[admin]).unwrap();
+
+    protocol.send(protocol.place_bid_ix(BATCH, &industry, 10 * CT, 1), &[industry.authority]).unwrap();
+    protocol.ctx.warp_forward(AuctionParams::standard(BATCH).duration_seconds);
+    protocol.send(protocol.finalize_auction_ix(&admin, BATCH), &[admin]).unwrap();
+
+    let result = protocol.send(protocol.release_reserve_ix(&admin, BATCH), &[admin]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::ReserveNotTriggered));
+}
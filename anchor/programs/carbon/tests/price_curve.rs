@@ -0,0 +1,138 @@
+mod common;
+
+use anchor_lang::error::ErrorCode;
+use carbon::{CarbonError, PriceCurve, PriceCurveSet};
+use common::*;
+
+const BATCH: u32 = 1;
+const START_PRICE: u64 = 10_000_000;
+const RESERVE_PRICE: u64 = 5_000_000;
+const START_TIME: i64 = 1_000;
+const END_TIME: i64 = 1_100;
+
+const CURVES: [PriceCurve; 5] = [
+    PriceCurve::Linear,
+    PriceCurve::Exponential { half_life_seconds: 20 },
+    PriceCurve::Exponential { half_life_seconds: 1 },
+    PriceCurve::Stepwise { step_seconds: 10, decrement: 400_000 },
+    PriceCurve::Stepwise { step_seconds: 1, decrement: u64::MAX },
+];
+
+fn price(curve: PriceCurve, current_time: i64) -> u64 {
+    curve.price_at(START_PRICE, RESERVE_PRICE, START_TIME, END_TIME, current_time).unwrap()
+}
+
+#[test]
+fn every_curve_runs_from_start_to_reserve() {
+    for curve in CURVES {
+        assert_eq!(price(curve, START_TIME - 10), START_PRICE, "{curve:?}");
+        assert_eq!(price(curve, START_TIME), START_PRICE, "{curve:?}");
+        assert_eq!(price(curve, END_TIME), RESERVE_PRICE, "{curve:?}");
+        assert_eq!(price(curve, END_TIME + 10), RESERVE_PRICE, "{curve:?}");
+    }
+}
+
+#[test]
+fn every_curve_is_monotonic_and_floored_at_reserve() {
+    for curve in CURVES {
+        let mut previous = START_PRICE;
+        for now in START_TIME..=END_TIME {
+            let current = price(curve, now);
+            assert!(current <= previous, "{curve:?} rose at {now}");
+            assert!(current >= RESERVE_PRICE, "{curve:?} fell below reserve at {now}");
+            previous = current;
+        }
+    }
+}
+
+#[test]
+fn exponential_curve_halves_the_premium_each_half_life() {
+    let curve = PriceCurve::Exponential { half_life_seconds: 20 };
+
+    assert_eq!(price(curve, START_TIME + 20), 7_500_000);
+    assert_eq!(price(curve, START_TIME + 40), 6_250_000);
+    assert_eq!(price(curve, START_TIME + 60), 5_625_000);
+    // Halfway through a half-life the premium has fallen by a quarter
+    assert_eq!(price(curve, START_TIME + 10), 8_750_000);
+}
+
+#[test]
+fn stepwise_curve_drops_in_fixed_decrements() {
+    let curve = PriceCurve::Stepwise { step_seconds: 10, decrement: 400_000 };
+
+    assert_eq!(price(curve, START_TIME + 9), START_PRICE);
+    assert_eq!(price(curve, START_TIME + 10), 9_600_000);
+    assert_eq!(price(curve, START_TIME + 35), 8_800_000);
+    assert_eq!(price(curve, END_TIME - 1), 6_400_000);
+
+    // Steep steps stop at the reserve rather than undercutting it
+    let steep = PriceCurve::Stepwise { step_seconds: 10, decrement: 2_000_000 };
+    assert_eq!(price(steep, START_TIME + 20), 6_000_000);
+    assert_eq!(price(steep, START_TIME + 30), RESERVE_PRICE);
+}
+
+#[test]
+fn curves_survive_extreme_parameters() {
+    let curves = [
+        PriceCurve::Linear,
+        PriceCurve::Exponential { half_life_seconds: 1 },
+        PriceCurve::Exponential { half_life_seconds: i64::MAX },
+        PriceCurve::Stepwise { step_seconds: 1, decrement: u64::MAX },
+    ];
+
+    for curve in curves {
+        let late = curve.price_at(u64::MAX, 0, 0, i64::MAX, i64::MAX - 1).unwrap();
+        let early = curve.price_at(u64::MAX, 0, 0, i64::MAX, 1).unwrap();
+        assert!(late <= early, "{curve:?}");
+    }
+
+    let result = PriceCurve::Linear.price_at(RESERVE_PRICE, START_PRICE, START_TIME, END_TIME, START_TIME + 1);
+    assert_eq!(result.unwrap_err(), CarbonError::MathOverflow.into());
+}
+
+#[test]
+fn operator_sets_the_curve_before_bidding() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let industry = protocol.verified_industry("Acme Steel");
+    protocol.send(protocol.create_auction_ix(&admin, AuctionParams::standard(BATCH)), &[admin]).unwrap();
+    let start = protocol.ctx.now();
+    assert_eq!(protocol.auction_state(BATCH).curve, PriceCurve::Linear);
+
+    let curve = PriceCurve::Stepwise { step_seconds: 10, decrement: 400_000 };
+    protocol.send(protocol.set_price_curve_ix(&admin, BATCH, curve), &[admin]).unwrap();
+    assert_eq!(protocol.auction_state(BATCH).curve, curve);
+    assert_eq!(protocol.ctx.events::<PriceCurveSet>()[0].curve, curve);
+
+    protocol.ctx.warp_to(start + 25);
+    protocol.send(protocol.place_bid_ix(BATCH, &industry, CT, 25), &[industry.authority]).unwrap();
+    let bid: carbon::Bid = protocol.ctx.account(&bid_pda(&auction_pda(BATCH), &industry.authority, 25));
+    assert_eq!(bid.price_per_token, 9_200_000);
+
+    let result = protocol.send(protocol.set_price_curve_ix(&admin, BATCH, PriceCurve::Linear), &[admin]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::AuctionHasBids));
+}
+
+#[test]
+fn set_price_curve_validates_parameters() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    protocol.send(protocol.create_auction_ix(&admin, AuctionParams::standard(BATCH)), &[admin]).unwrap();
+    protocol.send(protocol.create_sealed_bid_auction_ix(&admin, SealedBidParams::standard(2)), &[admin]).unwrap();
+
+    for curve in [
+        PriceCurve::Exponential { half_life_seconds: 0 },
+        PriceCurve::Stepwise { step_seconds: 0, decrement: 1 },
+        PriceCurve::Stepwise { step_seconds: 10, decrement: 0 },
+    ] {
+        let result = protocol.send(protocol.set_price_curve_ix(&admin, BATCH, curve), &[admin]);
+        assert_eq!(result.unwrap_err(), carbon_error(CarbonError::InvalidPriceCurve));
+    }
+
+    let result = protocol.send(protocol.set_price_curve_ix(&admin, 2, PriceCurve::Linear), &[admin]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::InvalidAuctionKind));
+
+    let intruder = protocol.ctx.funded_wallet(1);
+    let result = protocol.send(protocol.set_price_curve_ix(&intruder, BATCH, PriceCurve::Linear), &[intruder]);
+    assert_eq!(result.unwrap_err(), anchor_error(ErrorCode::AccountNotInitialized));
+}
@@ -0,0 +1,222 @@
+mod common;
+
+use anchor_lang::{
+    prelude::{Pubkey, Rent},
+    solana_program::instruction::AccountMeta,
+    Space,
+};
+use carbon::{AuctionClosed, Bid, BidClosed, CarbonError, EmissionReport, ReportArchived};
+use common::*;
+
+const BATCH: u32 = 1;
+
+/// Runs the standard auction to completion with a single bid for `token_amount` CT and
+/// returns the bid address. The bid is left unclaimed.
+fn finalized_auction(protocol: &mut Protocol, industry: &Industry, token_amount: u64) -> Pubkey {
+    let admin = protocol.admin;
+    protocol.send(protocol.create_auction_ix(&admin, AuctionParams::standard(BATCH)), &[admin]).unwrap();
+    protocol.send(protocol.place_bid_ix(BATCH, industry, token_amount, 1), &[industry.authority]).unwrap();
+    protocol.ctx.warp_forward(100);
+    protocol.send(protocol.finalize_auction_ix(&admin, BATCH), &[admin]).unwrap();
+    bid_pda(&auction_pda(BATCH), &industry.authority, 1)
+}
+
+#[test]
+fn close_bid_returns_rent_to_bidder() {
+    let mut protocol = Protocol::new();
+    let industry = protocol.verified_industry("Acme Steel");
+    let bid = finalized_auction(&mut protocol, &industry, 100 * CT);
+
+    let result = protocol.send(protocol.close_bid_ix(&industry, &bid), &[industry.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::BidNotSettled));
+
+    protocol.send(protocol.claim_tokens_ix(BATCH, &industry, &bid), &[industry.authority]).unwrap();
+    let balance = protocol.ctx.lamports(&industry.authority);
+    protocol.send(protocol.close_bid_ix(&industry, &bid), &[industry.authority]).unwrap();
+
+    assert!(!protocol.ctx.exists(&bid));
+    assert_eq!(
+        protocol.ctx.lamports(&industry.authority),
+        balance + Rent::default().minimum_balance(8 + Bid::INIT_SPACE)
+    );
+    let events = protocol.ctx.events::<BidClosed>();
+    assert_eq!(events[0].bid, bid);
+    assert_eq!(events[0].bidder, industry.authority);
+}
+
+#[test]
+fn close_bid_requires_bidder() {
+    let mut protocol = Protocol::new();
+    let industry = protocol.verified_industry("Acme Steel");
+    let other = protocol.verified_industry("Globex Cement");
+    let bid = finalized_auction(&mut protocol, &industry, 100 * CT);
+    protocol.send(protocol.claim_tokens_ix(BATCH, &industry, &bid), &[industry.authority]).unwrap();
+
+    let result = protocol.send(protocol.close_bid_ix(&other, &bid), &[other.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::InvalidBid));
+}
+
+#[test]
+fn close_auction_requires_settled_bids_and_withdrawn_proceeds() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let treasury = protocol.treasury;
+    let industry = protocol.verified_industry("Acme Steel");
+    let auction = auction_pda(BATCH);
+    let escrow = escrow_pda(&auction);
+    let bid = finalized_auction(&mut protocol, &industry, 100 * CT);
+
+    let result = protocol.send(protocol.close_auction_ix(&admin, BATCH, &admin), &[admin]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::BidsOutstanding));
+
+    protocol.send(protocol.claim_tokens_ix(BATCH, &industry, &bid), &[industry.authority]).unwrap();
+    let result = protocol.send(protocol.close_auction_ix(&admin, BATCH, &admin), &[admin]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::ProceedsNotWithdrawn));
+
+    protocol.send(protocol.withdraw_proceeds_ix(&admin, BATCH, &treasury), &[admin]).unwrap();
+    let result = protocol.send(protocol.close_auction_ix(&admin, BATCH, &treasury), &[admin]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::InvalidRentRecipient));
+
+    let balance = protocol.ctx.lamports(&admin);
+    let auction_rent = protocol.ctx.lamports(&auction);
+    protocol.send(protocol.close_auction_ix(&admin, BATCH, &admin), &[admin]).unwrap();
+
+    assert!(!protocol.ctx.exists(&auction));
+    assert!(!protocol.ctx.exists(&escrow));
+    assert_eq!(protocol.ctx.lamports(&admin), balance + auction_rent);
+    // The escrow's rent reserve was paid for by bidders, so it goes to the treasury with the proceeds
+    assert_eq!(protocol.ctx.lamports(&treasury), 500_000_000);
+
+    let events = protocol.ctx.events::<AuctionClosed>();
+    assert_eq!(events[0].batch_number, BATCH);
+    assert_eq!(events[0].rent_recipient, admin);
+    assert_eq!(events[0].escrow_swept, Rent::default().minimum_balance(0));
+}
+
+#[test]
+fn bidder_position_closes_with_its_last_bid() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let treasury = protocol.treasury;
+    let industry = protocol.verified_industry("Acme Steel");
+    let auction = auction_pda(BATCH);
+    let position = position_pda(&auction, &industry.authority);
+
+    protocol.send(protocol.create_auction_ix(&admin, AuctionParams::standard(BATCH)), &[admin]).unwrap();
+    protocol.send(protocol.set_bidder_cap_ix(&admin, BATCH, 100 * CT, 0), &[admin]).unwrap();
+    for timestamp in [1, 2] {
+        protocol.send(protocol.place_bid_ix(BATCH, &industry, 50 * CT, timestamp), &[industry.authority]).unwrap();
+    }
+    assert_eq!(protocol.ctx.account::<carbon::BidderPosition>(&position).open_bids, 2);
+    protocol.ctx.warp_forward(100);
+    protocol.send(protocol.finalize_auction_ix(&admin, BATCH), &[admin]).unwrap();
+
+    let first = bid_pda(&auction, &industry.authority, 1);
+    let second = bid_pda(&auction, &industry.authority, 2);
+    protocol.send(protocol.claim_tokens_ix(BATCH, &industry, &first), &[industry.authority]).unwrap();
+    assert_eq!(protocol.ctx.account::<carbon::BidderPosition>(&position).open_bids, 1);
+
+    let mut instruction = protocol.claim_tokens_ix(BATCH, &industry, &second);
+    instruction.accounts[3] = AccountMeta::new_readonly(carbon::ID, false);
+    let result = protocol.send(instruction, &[industry.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::BidderPositionRequired));
+
+    let balance = protocol.ctx.lamports(&industry.authority);
+    protocol.send(protocol.claim_tokens_ix(BATCH, &industry, &second), &[industry.authority]).unwrap();
+    assert!(!protocol.ctx.exists(&position));
+    // 0.5 SOL paid at the 0.01 start price comes back down to the 0.005 reserve
+    assert_eq!(protocol.ctx.lamports(&industry.authority), balance + 250_000_000 + bidder_position_rent());
+
+    // A new auction for the same batch does not inherit the old bids against its cap
+    protocol.send(protocol.withdraw_proceeds_ix(&admin, BATCH, &treasury), &[admin]).unwrap();
+    protocol.send(protocol.close_auction_ix(&admin, BATCH, &admin), &[admin]).unwrap();
+    protocol.send(protocol.create_auction_ix(&admin, AuctionParams::standard(BATCH)), &[admin]).unwrap();
+    protocol.send(protocol.set_bidder_cap_ix(&admin, BATCH, 100 * CT, 0), &[admin]).unwrap();
+    protocol.send(protocol.place_bid_ix(BATCH, &industry, 100 * CT, 3), &[industry.authority]).unwrap();
+    assert_eq!(protocol.ctx.account::<carbon::BidderPosition>(&position).tokens_bid, 100 * CT);
+}
+
+#[test]
+fn close_cancelled_auction_after_refunds() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let industry = protocol.verified_industry("Acme Steel");
+    protocol.send(protocol.create_auction_ix(&admin, AuctionParams::standard(BATCH)), &[admin]).unwrap();
+
+    let result = protocol.send(protocol.close_auction_ix(&admin, BATCH, &admin), &[admin]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::InvalidAuctionStatus));
+
+    protocol.send(protocol.place_bid_ix(BATCH, &industry, 100 * CT, 1), &[industry.authority]).unwrap();
+    protocol.send(protocol.cancel_auction_ix(&admin, BATCH), &[admin]).unwrap();
+    let bid = bid_pda(&auction_pda(BATCH), &industry.authority, 1);
+
+    let result = protocol.send(protocol.close_auction_ix(&admin, BATCH, &admin), &[admin]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::BidsOutstanding));
+
+    protocol.send(protocol.refund_bid_ix(BATCH, &industry, &bid), &[industry.authority]).unwrap();
+    protocol.send(protocol.close_bid_ix(&industry, &bid), &[industry.authority]).unwrap();
+    protocol.send(protocol.close_auction_ix(&admin, BATCH, &admin), &[admin]).unwrap();
+
+    assert!(!protocol.ctx.exists(&auction_pda(BATCH)));
+    assert_eq!(protocol.ctx.events::<AuctionClosed>()[0].escrow_swept, 0);
+}
+
+#[test]
+fn archive_report_from_settled_period() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let industry = protocol.verified_industry("Acme Steel");
+    protocol.buy_credits(BATCH, &industry, 20 * CT);
+    let report = protocol.verified_report(&industry, 15 * CT);
+
+    let timestamp = protocol.ctx.now() as u64;
+    let pending = emission_report_pda(&industry.account, timestamp);
+    protocol.send(protocol.submit_emission_report_ix(&industry, CT, timestamp), &[industry.authority]).unwrap();
+
+    // Reports from the open period stay on chain
+    let result = protocol.send(protocol.archive_emission_report_ix(&industry, &report), &[industry.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::ReportNotArchivable));
+
+    let deadline = protocol.ctx.now() + 3_600;
+    protocol.send(protocol.advance_compliance_period_ix(deadline), &[admin]).unwrap();
+
+    let result = protocol.send(protocol.archive_emission_report_ix(&industry, &pending), &[industry.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::ReportNotArchivable));
+
+    // The uncovered obligation was carried into the new period
+    let result = protocol.send(protocol.archive_emission_report_ix(&industry, &report), &[industry.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::ObligationsOutstanding));
+
+    protocol.send(protocol.burn_ix(&industry, 15 * CT), &[industry.authority]).unwrap();
+    let balance = protocol.ctx.lamports(&industry.authority);
+    protocol.send(protocol.archive_emission_report_ix(&industry, &report), &[industry.authority]).unwrap();
+
+    assert!(!protocol.ctx.exists(&report));
+    assert_eq!(
+        protocol.ctx.lamports(&industry.authority),
+        balance + Rent::default().minimum_balance(8 + EmissionReport::INIT_SPACE)
+    );
+    let events = protocol.ctx.events::<ReportArchived>();
+    assert_eq!(events[0].report, report);
+    assert_eq!(events[0].compliance_period, 1);
+}
+
+#[test]
+fn archive_report_requires_owning_industry() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let industry = protocol.verified_industry("Acme Steel");
+    let other = protocol.verified_industry("Globex Cement");
+    let timestamp = protocol.ctx.now() as u64;
+    let report = emission_report_pda(&industry.account, timestamp);
+    protocol.send(protocol.submit_emission_report_ix(&industry, CT, timestamp), &[industry.authority]).unwrap();
+    protocol.send(protocol.reject_emission_report_ix(&admin, &industry, &report), &[admin]).unwrap();
+    let deadline = protocol.ctx.now() + 3_600;
+    protocol.send(protocol.advance_compliance_period_ix(deadline), &[admin]).unwrap();
+
+    let result = protocol.send(protocol.archive_emission_report_ix(&other, &report), &[other.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::InvalidEmissionReport));
+
+    protocol.send(protocol.archive_emission_report_ix(&industry, &report), &[industry.authority]).unwrap();
+    assert!(!protocol.ctx.exists(&report));
+}
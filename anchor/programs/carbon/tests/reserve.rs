@@ -0,0 +1,155 @@
+mod common;
+
+use carbon::{
+    CapSchedule, CarbonError, ReserveAllowancesPurchased, ReserveConfig, ReserveReleased, ReserveSale, VintageHolding,
+};
+use common::*;
+
+/// Standard auctions never clear below their 0.005 SOL reserve, so this always triggers.
+const TRIGGER_PRICE: u64 = 5_000_000;
+/// 0.012 SOL per CT.
+const SALE_PRICE: u64 = 12_000_000;
+const RELEASE_AMOUNT: u64 = 100 * CT;
+
+fn configure_reserve(protocol: &mut Protocol, trigger_price: u64) {
+    let admin = protocol.admin;
+    protocol.send(protocol.set_reserve_config_ix(trigger_price, SALE_PRICE, RELEASE_AMOUNT), &[admin]).unwrap();
+}
+
+fn reserve_released(protocol: &Protocol, compliance_period: u32) -> u64 {
+    protocol.ctx.account::<CapSchedule>(&cap_schedule_pda(compliance_period)).reserve_released
+}
+
+#[test]
+fn admin_configures_the_reserve() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    configure_reserve(&mut protocol, TRIGGER_PRICE);
+
+    let reserve: ReserveConfig = protocol.ctx.account(&reserve_config_pda());
+    assert_eq!(reserve.trigger_price, TRIGGER_PRICE);
+    assert_eq!(reserve.sale_price, SALE_PRICE);
+    assert_eq!(reserve.release_amount, RELEASE_AMOUNT);
+
+    let result = protocol.send(protocol.set_reserve_config_ix(TRIGGER_PRICE, 0, RELEASE_AMOUNT), &[admin]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::InvalidReserveConfig));
+
+    let intruder = protocol.ctx.funded_wallet(1);
+    let mut instruction = protocol.set_reserve_config_ix(TRIGGER_PRICE, SALE_PRICE, RELEASE_AMOUNT);
+    instruction.accounts[2].pubkey = intruder;
+    let result = protocol.send(instruction, &[intruder]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::Unauthorized));
+}
+
+#[test]
+fn high_clearing_price_releases_the_reserve() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let industry = protocol.verified_industry("Acme Steel");
+    configure_reserve(&mut protocol, 20_000_000);
+    protocol.buy_credits(1, &industry, 10 * CT);
+
+    let result = protocol.send(protocol.release_reserve_ix(&admin, 1), &[admin]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::ReserveNotTriggered));
+
+    configure_reserve(&mut protocol, TRIGGER_PRICE);
+    protocol.send(protocol.release_reserve_ix(&admin, 1), &[admin]).unwrap();
+
+    let sale: ReserveSale = protocol.ctx.account(&reserve_sale_pda(&auction_pda(1)));
+    assert_eq!(sale.trigger_auction, auction_pda(1));
+    assert_eq!(sale.vintage, 1);
+    assert_eq!(sale.price_per_token, SALE_PRICE);
+    assert_eq!(sale.tokens_remaining, RELEASE_AMOUNT);
+    assert_eq!(reserve_released(&protocol, 1), RELEASE_AMOUNT);
+
+    let events = protocol.ctx.events::<ReserveReleased>();
+    assert_eq!(events[0].clearing_price, protocol.auction_state(1).current_price);
+    assert_eq!(events[0].total_tokens, RELEASE_AMOUNT);
+
+    // One release per triggering auction
+    assert!(protocol.send(protocol.release_reserve_ix(&admin, 1), &[admin]).is_err());
+}
+
+#[test]
+fn reserve_release_needs_a_finalized_auction_of_the_open_period() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let industry = protocol.verified_industry("Acme Steel");
+    configure_reserve(&mut protocol, TRIGGER_PRICE);
+
+    protocol.send(protocol.create_auction_ix(&admin, AuctionParams::standard(1)), &[admin]).unwrap();
+    let result = protocol.send(protocol.release_reserve_ix(&admin, 1), &[admin]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::AuctionNotFinalized));
+
+    protocol.buy_credits(2, &industry, 10 * CT);
+    let deadline = protocol.ctx.now() + 3_600;
+    protocol.send(protocol.advance_compliance_period_ix(deadline), &[admin]).unwrap();
+    let result = protocol.send(protocol.release_reserve_ix(&admin, 2), &[admin]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::CompliancePeriodClosed));
+}
+
+#[test]
+fn industries_buy_reserve_allowances_at_the_fixed_price() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let industry = protocol.verified_industry("Acme Steel");
+    configure_reserve(&mut protocol, TRIGGER_PRICE);
+    protocol.buy_credits(1, &industry, 10 * CT);
+    protocol.send(protocol.release_reserve_ix(&admin, 1), &[admin]).unwrap();
+    let treasury_lamports = protocol.ctx.lamports(&protocol.treasury);
+
+    protocol.send(protocol.buy_reserve_allowances_ix(1, &industry, 40 * CT), &[industry.authority]).unwrap();
+
+    assert_eq!(protocol.ctx.lamports(&protocol.treasury), treasury_lamports + 480_000_000);
+    assert_eq!(protocol.ctx.token_balance(&industry.ct_account), 50 * CT);
+    let state = protocol.industry_state(&industry);
+    assert_eq!(state.ct_balance, 50 * CT);
+    assert_eq!(state.total_purchased, 50 * CT);
+    assert_eq!(protocol.ctx.account::<VintageHolding>(&vintage_pda(&industry.account, 1)).amount, 50 * CT);
+
+    let events = protocol.ctx.events::<ReserveAllowancesPurchased>();
+    assert_eq!(events[0].total_cost, 480_000_000);
+    assert_eq!(events[0].tokens_remaining, 60 * CT);
+
+    let result = protocol.send(protocol.buy_reserve_allowances_ix(1, &industry, 61 * CT), &[industry.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::InvalidTokenAmount));
+}
+
+#[test]
+fn reserve_is_bounded_by_the_reserve_cap() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let industry = protocol.verified_industry("Acme Steel");
+    protocol.send(protocol.set_cap_schedule_ix(1, DEFAULT_CAP, 0, 150 * CT), &[admin]).unwrap();
+    configure_reserve(&mut protocol, TRIGGER_PRICE);
+    protocol.buy_credits(1, &industry, 10 * CT);
+    protocol.buy_credits(2, &industry, 10 * CT);
+    protocol.buy_credits(3, &industry, 10 * CT);
+
+    protocol.send(protocol.release_reserve_ix(&admin, 1), &[admin]).unwrap();
+    protocol.send(protocol.release_reserve_ix(&admin, 2), &[admin]).unwrap();
+    let sale: ReserveSale = protocol.ctx.account(&reserve_sale_pda(&auction_pda(2)));
+    assert_eq!(sale.total_tokens, 50 * CT);
+    assert_eq!(reserve_released(&protocol, 1), 150 * CT);
+
+    let result = protocol.send(protocol.release_reserve_ix(&admin, 3), &[admin]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::ReserveExhausted));
+
+    let result = protocol.send(protocol.set_cap_schedule_ix(1, DEFAULT_CAP, 0, 100 * CT), &[admin]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::CapBelowIssued));
+}
+
+#[test]
+fn reserve_sale_closes_with_its_period() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let industry = protocol.verified_industry("Acme Steel");
+    configure_reserve(&mut protocol, TRIGGER_PRICE);
+    protocol.buy_credits(1, &industry, 10 * CT);
+    protocol.send(protocol.release_reserve_ix(&admin, 1), &[admin]).unwrap();
+
+    let deadline = protocol.ctx.now() + 3_600;
+    protocol.send(protocol.advance_compliance_period_ix(deadline), &[admin]).unwrap();
+    let result = protocol.send(protocol.buy_reserve_allowances_ix(1, &industry, CT), &[industry.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::ReserveSaleClosed));
+}
@@ -0,0 +1,47 @@
+mod common;
+
+use anchor_lang::{
+    prelude::{Pubkey, Rent},
+    solana_program::{instruction::AccountMeta, program_error::ProgramError},
+};
+use common::*;
+
+fn transfer(from: &Pubkey, to: &Pubkey, lamports: u64) -> anchor_lang::solana_program::instruction::Instruction {
+    anchor_lang::solana_program::system_instruction::transfer(from, to, lamports)
+}
+
+#[test]
+fn transactions_cannot_leave_accounts_short_of_rent() {
+    let mut protocol = Protocol::new();
+    let wallet = protocol.ctx.funded_wallet(1);
+    let recipient = Pubkey::new_unique();
+    let minimum = Rent::default().minimum_balance(0);
+
+    let result = protocol.send(transfer(&wallet, &recipient, minimum - 1), &[wallet]);
+    assert_eq!(result.unwrap_err(), ProgramError::AccountNotRentExempt);
+    let result = protocol.send(transfer(&wallet, &recipient, LAMPORTS_PER_SOL - 1), &[wallet]);
+    assert_eq!(result.unwrap_err(), ProgramError::AccountNotRentExempt);
+
+    // Emptying the wallet entirely is fine
+    protocol.send(transfer(&wallet, &recipient, LAMPORTS_PER_SOL), &[wallet]).unwrap();
+    assert!(!protocol.ctx.exists(&wallet));
+    assert_eq!(protocol.ctx.lamports(&recipient), LAMPORTS_PER_SOL);
+}
+
+#[test]
+fn failures_carry_the_transaction_logs() {
+    let mut protocol = Protocol::new();
+    let wallet = protocol.ctx.funded_wallet(1);
+    let recipient = protocol.ctx.funded_wallet(1);
+
+    // Crediting an account the transaction only passed as read-only
+    let mut instruction = transfer(&wallet, &recipient, LAMPORTS_PER_SOL / 2);
+    instruction.accounts[1] = AccountMeta::new_readonly(recipient, false);
+    let error = protocol.send(instruction, &[wallet]).unwrap_err();
+    assert_eq!(error, ProgramError::InvalidAccountData);
+    assert!(error.logs.iter().any(|log| log.contains("modified a read-only account")));
+
+    let error = protocol.send(transfer(&wallet, &recipient, 1), &[]).unwrap_err();
+    assert_eq!(error, ProgramError::MissingRequiredSignature);
+    assert!(error.logs.iter().any(|log| log.contains(&wallet.to_string())));
+}
@@ -0,0 +1,400 @@
+mod common;
+
+use anchor_lang::prelude::{Pubkey, Rent};
+use carbon::{
+    sealed_bid_commitment, AuctionCreated, AuctionFinalized, AuctionKind, AuctionStatus, BidCommitted,
+    BidForfeited, BidRefunded, BidRevealed, BidStatus, CarbonError,
+};
+use common::*;
+
+const BATCH: u32 = 1;
+const SALT: [u8; 32] = [7; 32];
+const COLLATERAL: u64 = 200_000_000;
+
+/// Creates the standard sealed-bid auction for `BATCH` and returns its start time.
+fn open_auction(protocol: &mut Protocol) -> i64 {
+    let admin = protocol.admin;
+    protocol.send(protocol.create_sealed_bid_auction_ix(&admin, SealedBidParams::standard(BATCH)), &[admin]).unwrap();
+    protocol.ctx.now()
+}
+
+/// Commits to buying `token_amount` CT at `price_per_token` and returns the bid address.
+fn commit(protocol: &mut Protocol, industry: &Industry, token_amount: u64, price_per_token: u64) -> Pubkey {
+    let commitment = sealed_bid_commitment(&industry.authority, token_amount, price_per_token, &SALT);
+    protocol.send(protocol.commit_bid_ix(BATCH, industry, commitment, COLLATERAL, 1), &[industry.authority]).unwrap();
+    bid_pda(&auction_pda(BATCH), &industry.authority, 1)
+}
+
+fn reveal(protocol: &mut Protocol, industry: &Industry, bid: &Pubkey, token_amount: u64, price_per_token: u64) {
+    let instruction = protocol.reveal_bid_ix(BATCH, industry, bid, token_amount, price_per_token, SALT);
+    protocol.send(instruction, &[industry.authority]).unwrap();
+}
+
+fn claim(protocol: &mut Protocol, industry: &Industry, bid: &Pubkey) {
+    protocol.send(protocol.claim_tokens_ix(BATCH, industry, bid), &[industry.authority]).unwrap();
+}
+
+#[test]
+fn create_sealed_bid_auction() {
+    let mut protocol = Protocol::new();
+    let start = open_auction(&mut protocol);
+
+    let auction = protocol.auction_state(BATCH);
+    assert_eq!(auction.kind, AuctionKind::SealedBid);
+    assert_eq!(auction.status, AuctionStatus::Active);
+    assert_eq!(auction.end_time, start + 100);
+    assert_eq!(auction.reveal_end_time, start + 150);
+    assert_eq!(auction.min_collateral, 100_000_000);
+    assert_eq!(auction.reserve_price, 5_000_000);
+    assert_eq!(protocol.ctx.events::<AuctionCreated>()[0].kind, AuctionKind::SealedBid);
+}
+
+#[test]
+fn create_sealed_bid_auction_validates_parameters() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let cases = [
+        (SealedBidParams { reveal_duration_seconds: 0, ..SealedBidParams::standard(BATCH) }, CarbonError::InvalidDuration),
+        (SealedBidParams { total_tokens: 0, ..SealedBidParams::standard(BATCH) }, CarbonError::InvalidTokenAmount),
+        (SealedBidParams { min_collateral: 0, ..SealedBidParams::standard(BATCH) }, CarbonError::InsufficientCollateral),
+        (SealedBidParams { reveal_duration_seconds: i64::MAX, ..SealedBidParams::standard(BATCH) }, CarbonError::MathOverflow),
+    ];
+
+    for (params, error) in cases {
+        let result = protocol.send(protocol.create_sealed_bid_auction_ix(&admin, params), &[admin]);
+        assert_eq!(result.unwrap_err(), carbon_error(error));
+    }
+}
+
+#[test]
+fn commit_escrows_collateral() {
+    let mut protocol = Protocol::new();
+    let industry = protocol.verified_industry("Acme Steel");
+    open_auction(&mut protocol);
+
+    let bid = commit(&mut protocol, &industry, 100 * CT, 8_000_000);
+    assert_eq!(protocol.ctx.lamports(&escrow_pda(&auction_pda(BATCH))), COLLATERAL);
+
+    let state: carbon::Bid = protocol.ctx.account(&bid);
+    assert_eq!(state.status, BidStatus::Committed);
+    assert_eq!(state.collateral, COLLATERAL);
+    assert_eq!(state.token_amount, 0);
+    assert_eq!(protocol.auction_state(BATCH).participant_count, 1);
+    assert_eq!(protocol.ctx.events::<BidCommitted>()[0].collateral, COLLATERAL);
+}
+
+#[test]
+fn commit_validates_auction_and_collateral() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let industry = protocol.verified_industry("Acme Steel");
+    let start = open_auction(&mut protocol);
+    let commitment = sealed_bid_commitment(&industry.authority, CT, 8_000_000, &SALT);
+
+    let result = protocol.send(protocol.commit_bid_ix(BATCH, &industry, commitment, 99_999_999, 1), &[industry.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::InsufficientCollateral));
+
+    let result = protocol.send(protocol.place_bid_ix(BATCH, &industry, CT, 1), &[industry.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::InvalidAuctionKind));
+
+    protocol.send(protocol.create_auction_ix(&admin, AuctionParams::standard(2)), &[admin]).unwrap();
+    let result = protocol.send(protocol.commit_bid_ix(2, &industry, commitment, COLLATERAL, 1), &[industry.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::InvalidAuctionKind));
+
+    protocol.ctx.warp_to(start + 100);
+    let result = protocol.send(protocol.commit_bid_ix(BATCH, &industry, commitment, COLLATERAL, 1), &[industry.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::AuctionEnded));
+}
+
+#[test]
+fn reveal_tops_up_escrow() {
+    let mut protocol = Protocol::new();
+    let industry = protocol.verified_industry("Acme Steel");
+    let start = open_auction(&mut protocol);
+    let bid = commit(&mut protocol, &industry, 100 * CT, 8_000_000);
+
+    protocol.ctx.warp_to(start + 100);
+    let balance_before = protocol.ctx.lamports(&industry.authority);
+    reveal(&mut protocol, &industry, &bid, 100 * CT, 8_000_000);
+
+    // 100 CT at 0.008 SOL costs 0.8 SOL, of which the collateral already covers 0.2
+    assert_eq!(protocol.ctx.lamports(&industry.authority), balance_before - 600_000_000 - bidder_position_rent());
+    assert_eq!(protocol.ctx.lamports(&escrow_pda(&auction_pda(BATCH))), 800_000_000);
+
+    let state: carbon::Bid = protocol.ctx.account(&bid);
+    assert_eq!(state.status, BidStatus::Pending);
+    assert_eq!(state.token_amount, 100 * CT);
+    assert_eq!(state.price_per_token, 8_000_000);
+    assert_eq!(state.total_cost, 800_000_000);
+    assert_eq!(protocol.auction_state(BATCH).revealed_count, 1);
+    assert_eq!(protocol.ctx.events::<BidRevealed>()[0].total_cost, 800_000_000);
+}
+
+#[test]
+fn reveal_only_within_window() {
+    let mut protocol = Protocol::new();
+    let industry = protocol.verified_industry("Acme Steel");
+    let start = open_auction(&mut protocol);
+    let bid = commit(&mut protocol, &industry, 100 * CT, 8_000_000);
+
+    let result = protocol.send(protocol.reveal_bid_ix(BATCH, &industry, &bid, 100 * CT, 8_000_000, SALT), &[industry.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::RevealNotOpen));
+
+    protocol.ctx.warp_to(start + 150);
+    let result = protocol.send(protocol.reveal_bid_ix(BATCH, &industry, &bid, 100 * CT, 8_000_000, SALT), &[industry.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::RevealClosed));
+}
+
+#[test]
+fn reveal_must_match_commitment() {
+    let mut protocol = Protocol::new();
+    let industry = protocol.verified_industry("Acme Steel");
+    let other = protocol.verified_industry("Globex Cement");
+    let start = open_auction(&mut protocol);
+    let bid = commit(&mut protocol, &industry, 100 * CT, 8_000_000);
+    let cheap = commit(&mut protocol, &other, 100 * CT, 4_000_000);
+    protocol.ctx.warp_to(start + 100);
+
+    let result = protocol.send(protocol.reveal_bid_ix(BATCH, &industry, &bid, 100 * CT, 7_000_000, SALT), &[industry.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::CommitmentMismatch));
+
+    let result = protocol.send(protocol.reveal_bid_ix(BATCH, &industry, &bid, 100 * CT, 8_000_000, [0; 32]), &[industry.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::CommitmentMismatch));
+
+    let result = protocol.send(protocol.reveal_bid_ix(BATCH, &other, &bid, 100 * CT, 8_000_000, SALT), &[other.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::InvalidBid));
+
+    let result = protocol.send(protocol.reveal_bid_ix(BATCH, &other, &cheap, 100 * CT, 4_000_000, SALT), &[other.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::BidBelowReserve));
+
+    reveal(&mut protocol, &industry, &bid, 100 * CT, 8_000_000);
+    let result = protocol.send(protocol.reveal_bid_ix(BATCH, &industry, &bid, 100 * CT, 8_000_000, SALT), &[industry.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::BidAlreadyProcessed));
+}
+
+#[test]
+fn finalize_clears_at_uniform_price() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let acme = protocol.verified_industry("Acme Steel");
+    let globex = protocol.verified_industry("Globex Cement");
+    let initech = protocol.verified_industry("Initech Chemicals");
+    let start = open_auction(&mut protocol);
+
+    let acme_bid = commit(&mut protocol, &acme, 600 * CT, 9_000_000);
+    let globex_bid = commit(&mut protocol, &globex, 300 * CT, 8_000_000);
+    let initech_bid = commit(&mut protocol, &initech, 400 * CT, 6_000_000);
+    protocol.ctx.warp_to(start + 100);
+    reveal(&mut protocol, &acme, &acme_bid, 600 * CT, 9_000_000);
+    reveal(&mut protocol, &globex, &globex_bid, 300 * CT, 8_000_000);
+    reveal(&mut protocol, &initech, &initech_bid, 400 * CT, 6_000_000);
+
+    let bids = [initech_bid, acme_bid, globex_bid];
+    let result = protocol.send(protocol.finalize_sealed_auction_ix(&admin, BATCH, &bids), &[admin]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::AuctionNotEnded));
+
+    protocol.ctx.warp_to(start + 150);
+    protocol.send(protocol.finalize_sealed_auction_ix(&admin, BATCH, &bids), &[admin]).unwrap();
+
+    // Initech's bid is the marginal one: it sets the price and gets the last 100 CT
+    let auction = protocol.auction_state(BATCH);
+    assert_eq!(auction.status, AuctionStatus::Finalized);
+    assert_eq!(auction.current_price, 6_000_000);
+    assert_eq!(auction.tokens_sold, 1_000 * CT);
+    assert_eq!(auction.tokens_remaining, 0);
+    assert_eq!(auction.total_raised, 6_000_000_000);
+    assert_eq!(protocol.ctx.events::<AuctionFinalized>()[0].clearing_price, 6_000_000);
+
+    for (industry, bid, allocation, refund) in [
+        (acme, acme_bid, 600 * CT, 1_800_000_000),
+        (globex, globex_bid, 300 * CT, 600_000_000),
+        (initech, initech_bid, 100 * CT, 1_800_000_000),
+    ] {
+        let balance_before = protocol.ctx.lamports(&industry.authority);
+        claim(&mut protocol, &industry, &bid);
+        assert_eq!(protocol.ctx.token_balance(&industry.ct_account), allocation);
+        assert_eq!(protocol.ctx.lamports(&industry.authority), balance_before + refund + bidder_position_rent() - vintage_holding_rent());
+        assert_eq!(protocol.ctx.account::<carbon::Bid>(&bid).status, BidStatus::Accepted);
+    }
+}
+
+#[test]
+fn marginal_tie_is_split_pro_rata() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let acme = protocol.verified_industry("Acme Steel");
+    let globex = protocol.verified_industry("Globex Cement");
+    let initech = protocol.verified_industry("Initech Chemicals");
+    let start = open_auction(&mut protocol);
+
+    let acme_bid = commit(&mut protocol, &acme, 800 * CT, 9_000_000);
+    let globex_bid = commit(&mut protocol, &globex, 400 * CT, 6_000_000);
+    let initech_bid = commit(&mut protocol, &initech, 200 * CT, 6_000_000);
+    protocol.ctx.warp_to(start + 100);
+    reveal(&mut protocol, &acme, &acme_bid, 800 * CT, 9_000_000);
+    reveal(&mut protocol, &globex, &globex_bid, 400 * CT, 6_000_000);
+    reveal(&mut protocol, &initech, &initech_bid, 200 * CT, 6_000_000);
+
+    protocol.ctx.warp_to(start + 150);
+    protocol.send(protocol.finalize_sealed_auction_ix(&admin, BATCH, &[acme_bid, globex_bid, initech_bid]), &[admin]).unwrap();
+
+    assert_eq!(protocol.ctx.account::<carbon::Bid>(&acme_bid).token_amount, 800 * CT);
+    assert_eq!(protocol.ctx.account::<carbon::Bid>(&globex_bid).token_amount, 133_333_333_333);
+    assert_eq!(protocol.ctx.account::<carbon::Bid>(&initech_bid).token_amount, 66_666_666_666);
+
+    let auction = protocol.auction_state(BATCH);
+    assert_eq!(auction.current_price, 6_000_000);
+    assert_eq!(auction.tokens_remaining, 1);
+}
+
+#[test]
+fn undersubscribed_auction_clears_at_reserve() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let industry = protocol.verified_industry("Acme Steel");
+    let start = open_auction(&mut protocol);
+    let bid = commit(&mut protocol, &industry, 100 * CT, 8_000_000);
+    protocol.ctx.warp_to(start + 100);
+    reveal(&mut protocol, &industry, &bid, 100 * CT, 8_000_000);
+    protocol.ctx.warp_to(start + 150);
+
+    protocol.send(protocol.finalize_sealed_auction_ix(&admin, BATCH, &[bid]), &[admin]).unwrap();
+    let auction = protocol.auction_state(BATCH);
+    assert_eq!(auction.current_price, 5_000_000);
+    assert_eq!(auction.tokens_sold, 100 * CT);
+    assert_eq!(auction.total_raised, 500_000_000);
+
+    let balance_before = protocol.ctx.lamports(&industry.authority);
+    claim(&mut protocol, &industry, &bid);
+    assert_eq!(protocol.ctx.lamports(&industry.authority), balance_before + 300_000_000 + bidder_position_rent() - vintage_holding_rent());
+}
+
+#[test]
+fn outbid_industry_is_refunded_in_full() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let acme = protocol.verified_industry("Acme Steel");
+    let globex = protocol.verified_industry("Globex Cement");
+    let start = open_auction(&mut protocol);
+    let acme_bid = commit(&mut protocol, &acme, 1_000 * CT, 9_000_000);
+    let globex_bid = commit(&mut protocol, &globex, 100 * CT, 8_000_000);
+    protocol.ctx.warp_to(start + 100);
+    reveal(&mut protocol, &acme, &acme_bid, 1_000 * CT, 9_000_000);
+    reveal(&mut protocol, &globex, &globex_bid, 100 * CT, 8_000_000);
+    protocol.ctx.warp_to(start + 150);
+    protocol.send(protocol.finalize_sealed_auction_ix(&admin, BATCH, &[acme_bid, globex_bid]), &[admin]).unwrap();
+
+    let balance_before = protocol.ctx.lamports(&globex.authority);
+    claim(&mut protocol, &globex, &globex_bid);
+    assert_eq!(protocol.ctx.lamports(&globex.authority), balance_before + 800_000_000 + bidder_position_rent() - vintage_holding_rent());
+    assert_eq!(protocol.ctx.token_balance(&globex.ct_account), 0);
+    assert_eq!(protocol.industry_state(&globex).ct_balance, 0);
+    assert_eq!(protocol.ctx.account::<carbon::Bid>(&globex_bid).status, BidStatus::Refunded);
+}
+
+#[test]
+fn finalize_requires_every_revealed_bid() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let acme = protocol.verified_industry("Acme Steel");
+    let globex = protocol.verified_industry("Globex Cement");
+    let start = open_auction(&mut protocol);
+    let acme_bid = commit(&mut protocol, &acme, 100 * CT, 9_000_000);
+    let globex_bid = commit(&mut protocol, &globex, 100 * CT, 8_000_000);
+    protocol.ctx.warp_to(start + 100);
+    reveal(&mut protocol, &acme, &acme_bid, 100 * CT, 9_000_000);
+    reveal(&mut protocol, &globex, &globex_bid, 100 * CT, 8_000_000);
+    protocol.ctx.warp_to(start + 150);
+
+    for bids in [vec![acme_bid], vec![acme_bid, acme_bid]] {
+        let result = protocol.send(protocol.finalize_sealed_auction_ix(&admin, BATCH, &bids), &[admin]);
+        assert_eq!(result.unwrap_err(), carbon_error(CarbonError::IncompleteBidSet));
+    }
+}
+
+#[test]
+fn unrevealed_commitment_forfeits_half_its_collateral() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let industry = protocol.verified_industry("Acme Steel");
+    let start = open_auction(&mut protocol);
+    let bid = commit(&mut protocol, &industry, 100 * CT, 8_000_000);
+    protocol.ctx.warp_to(start + 150);
+    protocol.send(protocol.finalize_sealed_auction_ix(&admin, BATCH, &[]), &[admin]).unwrap();
+    assert_eq!(protocol.auction_state(BATCH).tokens_sold, 0);
+
+    let balance_before = protocol.ctx.lamports(&industry.authority);
+    claim(&mut protocol, &industry, &bid);
+    assert_eq!(protocol.ctx.lamports(&industry.authority), balance_before + COLLATERAL / 2 - vintage_holding_rent());
+    assert_eq!(protocol.ctx.token_balance(&industry.ct_account), 0);
+    assert_eq!(protocol.ctx.account::<carbon::Bid>(&bid).status, BidStatus::Forfeited);
+    assert_eq!(protocol.auction_state(BATCH).total_raised, COLLATERAL / 2);
+
+    let events = protocol.ctx.events::<BidForfeited>();
+    assert_eq!(events[0].forfeited, COLLATERAL / 2);
+    assert_eq!(events[0].refund_amount, COLLATERAL / 2);
+
+    let result = protocol.send(protocol.claim_tokens_ix(BATCH, &industry, &bid), &[industry.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::BidAlreadyProcessed));
+}
+
+#[test]
+fn operator_forfeits_a_commitment_its_bidder_abandons() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let treasury = protocol.treasury;
+    let industry = protocol.verified_industry("Acme Steel");
+    let start = open_auction(&mut protocol);
+    let bid = commit(&mut protocol, &industry, 100 * CT, 8_000_000);
+    protocol.ctx.warp_to(start + 150);
+    protocol.send(protocol.finalize_sealed_auction_ix(&admin, BATCH, &[]), &[admin]).unwrap();
+
+    // The committer never reveals nor claims, so the operator settles the forfeit for it
+    let balance_before = protocol.ctx.lamports(&industry.authority);
+    protocol.send(protocol.claim_tokens_for_ix(&admin, BATCH, &industry, &bid), &[admin]).unwrap();
+    assert_eq!(protocol.ctx.lamports(&industry.authority), balance_before + COLLATERAL / 2);
+    assert_eq!(protocol.ctx.account::<carbon::Bid>(&bid).status, BidStatus::Forfeited);
+
+    protocol.send(protocol.withdraw_proceeds_ix(&admin, BATCH, &treasury), &[admin]).unwrap();
+    assert_eq!(protocol.ctx.lamports(&treasury), COLLATERAL / 2 - Rent::default().minimum_balance(0));
+}
+
+#[test]
+fn cancelled_auction_returns_collateral_in_full() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let acme = protocol.verified_industry("Acme Steel");
+    let globex = protocol.verified_industry("Globex Cement");
+    let start = open_auction(&mut protocol);
+    let acme_bid = commit(&mut protocol, &acme, 100 * CT, 8_000_000);
+    let globex_bid = commit(&mut protocol, &globex, 100 * CT, 8_000_000);
+    protocol.ctx.warp_to(start + 100);
+    reveal(&mut protocol, &acme, &acme_bid, 100 * CT, 8_000_000);
+    protocol.send(protocol.cancel_auction_ix(&admin, BATCH), &[admin]).unwrap();
+
+    // Neither the revealed bid nor the unrevealed commitment is penalised; only the revealed
+    // bid opened a position
+    for (industry, bid, amount, position_rent) in [
+        (acme, acme_bid, 800_000_000, bidder_position_rent()),
+        (globex, globex_bid, COLLATERAL, 0),
+    ] {
+        let balance_before = protocol.ctx.lamports(&industry.authority);
+        protocol.send(protocol.refund_bid_ix(BATCH, &industry, &bid), &[industry.authority]).unwrap();
+        assert_eq!(protocol.ctx.lamports(&industry.authority), balance_before + amount + position_rent);
+        assert_eq!(protocol.ctx.events::<BidRefunded>()[0].amount, amount);
+    }
+}
+
+#[test]
+fn reveal_respects_bidder_cap() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let industry = protocol.verified_industry("Acme Steel");
+    let start = open_auction(&mut protocol);
+    protocol.send(protocol.set_bidder_cap_ix(&admin, BATCH, 0, 1_000), &[admin]).unwrap();
+    let bid = commit(&mut protocol, &industry, 101 * CT, 8_000_000);
+    protocol.ctx.warp_to(start + 100);
+
+    let result = protocol.send(protocol.reveal_bid_ix(BATCH, &industry, &bid, 101 * CT, 8_000_000, SALT), &[industry.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::BidderCapExceeded));
+}
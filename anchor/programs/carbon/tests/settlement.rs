@@ -0,0 +1,264 @@
+mod common;
+
+use anchor_lang::error::ErrorCode;
+use carbon::{CarbonError, ComplianceStatus, PeriodSettled};
+use common::*;
+
+/// 0.01 SOL for every tonne left uncovered.
+const PENALTY_PER_TONNE: u64 = 10_000_000;
+
+fn close_period(protocol: &mut Protocol) {
+    let admin = protocol.admin;
+    let deadline = protocol.ctx.now() + 3_600;
+    protocol.send(protocol.set_compliance_terms_ix(deadline, PENALTY_PER_TONNE), &[admin]).unwrap();
+    protocol.send(protocol.advance_compliance_period_ix(deadline), &[admin]).unwrap();
+    let period = protocol.config().compliance_period;
+    protocol.open_cap_schedule(period);
+}
+
+#[test]
+fn settlement_penalises_the_shortfall_from_the_bond() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let industry = protocol.verified_industry("Acme Steel");
+    protocol.send(protocol.deposit_bond_ix(&industry, 2 * MIN_BOND), &[industry.authority]).unwrap();
+    protocol.buy_credits(1, &industry, 4 * CT);
+    protocol.verified_report(&industry, 10 * CT);
+    protocol.send(protocol.burn_ix(&industry, 4 * CT), &[industry.authority]).unwrap();
+    close_period(&mut protocol);
+
+    protocol.send(protocol.settle_period_ix(&admin, &industry), &[admin]).unwrap();
+
+    assert_eq!(protocol.ctx.lamports(&protocol.treasury), 60_000_000);
+    assert_eq!(protocol.ctx.lamports(&industry.bond_vault), 2 * MIN_BOND - 60_000_000);
+
+    let state = protocol.industry_state(&industry);
+    assert_eq!(state.settled_through_period, 1);
+    assert_eq!(state.unsettled_shortfall, 0);
+    assert_eq!(state.penalty_debt, 0);
+    assert_eq!(state.total_slashed, 60_000_000);
+    // The shortfall still has to be surrendered in the new period
+    assert_eq!(state.compliance_period, 2);
+    assert_eq!(state.period_obligation, 6 * CT);
+    assert_eq!(state.compliance_status, ComplianceStatus::NonCompliant);
+
+    let events = protocol.ctx.events::<PeriodSettled>();
+    assert_eq!(events[0].compliance_period, 1);
+    assert_eq!(events[0].shortfall, 6 * CT);
+    assert_eq!(events[0].penalty, 60_000_000);
+    assert_eq!(events[0].collected, 60_000_000);
+    assert_eq!(events[0].carried_obligation, 6 * CT);
+
+    let result = protocol.send(protocol.settle_period_ix(&admin, &industry), &[admin]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::PeriodAlreadySettled));
+}
+
+#[test]
+fn open_period_cannot_be_settled() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let industry = protocol.verified_industry("Acme Steel");
+    protocol.send(protocol.deposit_bond_ix(&industry, MIN_BOND), &[industry.authority]).unwrap();
+    protocol.verified_report(&industry, 10 * CT);
+
+    let result = protocol.send(protocol.settle_period_ix(&admin, &industry), &[admin]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::PeriodAlreadySettled));
+}
+
+#[test]
+fn compliant_industry_settles_without_penalty() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let industry = protocol.verified_industry("Acme Steel");
+    protocol.send(protocol.deposit_bond_ix(&industry, MIN_BOND), &[industry.authority]).unwrap();
+    protocol.buy_credits(1, &industry, 10 * CT);
+    protocol.verified_report(&industry, 10 * CT);
+    protocol.send(protocol.burn_ix(&industry, 10 * CT), &[industry.authority]).unwrap();
+    close_period(&mut protocol);
+
+    protocol.send(protocol.settle_period_ix(&admin, &industry), &[admin]).unwrap();
+
+    let events = protocol.ctx.events::<PeriodSettled>();
+    assert_eq!(events[0].shortfall, 0);
+    assert_eq!(events[0].collected, 0);
+    assert_eq!(protocol.ctx.lamports(&industry.bond_vault), MIN_BOND);
+    assert_eq!(protocol.industry_state(&industry).compliance_status, ComplianceStatus::Compliant);
+}
+
+#[test]
+fn uncovered_penalty_is_recorded_as_debt() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let industry = protocol.verified_industry("Acme Steel");
+    protocol.send(protocol.deposit_bond_ix(&industry, MIN_BOND), &[industry.authority]).unwrap();
+    // 150 tonnes uncovered at 0.01 SOL is 1.5 SOL, more than the 1 SOL in the vault
+    protocol.verified_report(&industry, 150 * CT);
+    close_period(&mut protocol);
+
+    protocol.send(protocol.settle_period_ix(&admin, &industry), &[admin]).unwrap();
+
+    let rent_exempt = protocol.ctx.lamports(&industry.bond_vault);
+    let collected = MIN_BOND - rent_exempt;
+    assert_eq!(protocol.ctx.lamports(&protocol.treasury), collected);
+    let state = protocol.industry_state(&industry);
+    assert_eq!(state.penalty_debt, 1_500_000_000 - collected);
+
+    // Debt is collected at the next settlement once the bond is topped up
+    protocol.send(protocol.deposit_bond_ix(&industry, MIN_BOND), &[industry.authority]).unwrap();
+    protocol.buy_credits(1, &industry, 150 * CT);
+    protocol.send(protocol.burn_vintage_ix(&industry, 2, 150 * CT), &[industry.authority]).unwrap();
+    close_period(&mut protocol);
+    protocol.send(protocol.settle_period_ix(&admin, &industry), &[admin]).unwrap();
+
+    let events = protocol.ctx.events::<PeriodSettled>();
+    assert_eq!(events[0].compliance_period, 2);
+    assert_eq!(events[0].shortfall, 0);
+    assert_eq!(events[0].collected, 1_500_000_000 - collected);
+    assert_eq!(protocol.industry_state(&industry).penalty_debt, 0);
+}
+
+#[test]
+fn carried_shortfall_is_penalised_once() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let industry = protocol.verified_industry("Acme Steel");
+    protocol.send(protocol.deposit_bond_ix(&industry, 2 * MIN_BOND), &[industry.authority]).unwrap();
+    protocol.verified_report(&industry, 10 * CT);
+    close_period(&mut protocol);
+    protocol.send(protocol.settle_period_ix(&admin, &industry), &[admin]).unwrap();
+
+    // Period 2 adds 5 tonnes; 8 tonnes burned cover the carried 10 tonnes first
+    protocol.verified_report(&industry, 5 * CT);
+    protocol.buy_credits(2, &industry, 8 * CT);
+    protocol.send(protocol.burn_vintage_ix(&industry, 2, 8 * CT), &[industry.authority]).unwrap();
+    close_period(&mut protocol);
+    protocol.send(protocol.settle_period_ix(&admin, &industry), &[admin]).unwrap();
+
+    let events = protocol.ctx.events::<PeriodSettled>();
+    assert_eq!(events[0].shortfall, 5 * CT);
+    assert_eq!(events[0].penalty, 50_000_000);
+    assert_eq!(events[0].carried_obligation, 7 * CT);
+}
+
+#[test]
+fn slashed_shortfall_is_not_penalised_again_at_settlement() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let industry = protocol.verified_industry("Acme Steel");
+    protocol.send(protocol.deposit_bond_ix(&industry, 2 * MIN_BOND), &[industry.authority]).unwrap();
+    protocol.verified_report(&industry, 10 * CT);
+
+    let deadline = protocol.ctx.now() + 100;
+    protocol.send(protocol.set_compliance_terms_ix(deadline, PENALTY_PER_TONNE), &[admin]).unwrap();
+    protocol.ctx.warp_to(deadline + 1);
+    protocol.send(protocol.slash_bond_ix(&admin, &industry), &[admin]).unwrap();
+    assert_eq!(protocol.ctx.lamports(&protocol.treasury), 100_000_000);
+
+    // Only the 5 tonnes reported after the slash are left for settlement
+    protocol.verified_report(&industry, 5 * CT);
+    close_period(&mut protocol);
+    protocol.send(protocol.settle_period_ix(&admin, &industry), &[admin]).unwrap();
+
+    let events = protocol.ctx.events::<PeriodSettled>();
+    assert_eq!(events[0].shortfall, 5 * CT);
+    assert_eq!(events[0].penalty, 50_000_000);
+    assert_eq!(events[0].carried_obligation, 15 * CT);
+    assert_eq!(protocol.ctx.lamports(&protocol.treasury), 150_000_000);
+    assert_eq!(protocol.industry_state(&industry).total_slashed, 150_000_000);
+
+    // The carried 15 tonnes have been penalised, so missing the next deadline adds nothing
+    let deadline = protocol.ctx.now() + 100;
+    protocol.send(protocol.set_compliance_terms_ix(deadline, PENALTY_PER_TONNE), &[admin]).unwrap();
+    protocol.ctx.warp_to(deadline + 1);
+    let result = protocol.send(protocol.slash_bond_ix(&admin, &industry), &[admin]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::NotSlashable));
+    assert_eq!(protocol.ctx.lamports(&protocol.treasury), 150_000_000);
+}
+
+#[test]
+fn slash_the_bond_cannot_cover_is_settled_later() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let industry = protocol.verified_industry("Acme Steel");
+    protocol.send(protocol.deposit_bond_ix(&industry, MIN_BOND), &[industry.authority]).unwrap();
+    // 150 tonnes uncovered at 0.01 SOL is 1.5 SOL, more than the 1 SOL in the vault
+    protocol.verified_report(&industry, 150 * CT);
+
+    let deadline = protocol.ctx.now() + 100;
+    protocol.send(protocol.set_compliance_terms_ix(deadline, PENALTY_PER_TONNE), &[admin]).unwrap();
+    protocol.ctx.warp_to(deadline + 1);
+    protocol.send(protocol.slash_bond_ix(&admin, &industry), &[admin]).unwrap();
+    let slashed = protocol.ctx.lamports(&protocol.treasury);
+    let paid_shortfall = slashed * 1_000_000_000 / PENALTY_PER_TONNE;
+    assert_eq!(protocol.industry_state(&industry).period_slashed_shortfall, paid_shortfall);
+
+    close_period(&mut protocol);
+    protocol.send(protocol.settle_period_ix(&admin, &industry), &[admin]).unwrap();
+
+    let events = protocol.ctx.events::<PeriodSettled>();
+    assert_eq!(events[0].shortfall, 150 * CT - paid_shortfall);
+    assert_eq!(events[0].collected, 0);
+    assert_eq!(protocol.industry_state(&industry).penalty_debt, events[0].penalty);
+    assert_eq!(slashed + events[0].penalty, 1_500_000_000);
+}
+
+#[test]
+fn bond_cannot_be_withdrawn_ahead_of_settlement() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let industry = protocol.verified_industry("Acme Steel");
+    protocol.send(protocol.deposit_bond_ix(&industry, 2 * MIN_BOND), &[industry.authority]).unwrap();
+    protocol.verified_report(&industry, 10 * CT);
+    close_period(&mut protocol);
+
+    // Covering the carried shortfall makes the industry compliant again, but the closed
+    // period's penalty has not been collected yet
+    protocol.buy_credits(2, &industry, 10 * CT);
+    protocol.send(protocol.burn_vintage_ix(&industry, 2, 10 * CT), &[industry.authority]).unwrap();
+    assert_eq!(protocol.industry_state(&industry).compliance_status, ComplianceStatus::Compliant);
+    let result = protocol.send(protocol.withdraw_bond_ix(&industry, MIN_BOND), &[industry.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::ObligationsOutstanding));
+
+    protocol.send(protocol.settle_period_ix(&admin, &industry), &[admin]).unwrap();
+    assert_eq!(protocol.ctx.lamports(&protocol.treasury), 100_000_000);
+    protocol.send(protocol.withdraw_bond_ix(&industry, MIN_BOND), &[industry.authority]).unwrap();
+}
+
+#[test]
+fn shortfall_is_kept_when_the_period_rolls_before_settlement() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let industry = protocol.verified_industry("Acme Steel");
+    protocol.send(protocol.deposit_bond_ix(&industry, MIN_BOND), &[industry.authority]).unwrap();
+    protocol.verified_report(&industry, 10 * CT);
+    close_period(&mut protocol);
+
+    // A report in the new period rolls the ledger before the regulator settles
+    protocol.verified_report(&industry, 3 * CT);
+    assert_eq!(protocol.industry_state(&industry).unsettled_shortfall, 10 * CT);
+
+    protocol.send(protocol.settle_period_ix(&admin, &industry), &[admin]).unwrap();
+    let events = protocol.ctx.events::<PeriodSettled>();
+    assert_eq!(events[0].shortfall, 10 * CT);
+    assert_eq!(events[0].carried_obligation, 10 * CT);
+    assert_eq!(protocol.industry_state(&industry).period_obligation, 13 * CT);
+}
+
+#[test]
+fn settlement_requires_regulator_and_treasury() {
+    let mut protocol = Protocol::new();
+    let admin = protocol.admin;
+    let industry = protocol.verified_industry("Acme Steel");
+    protocol.send(protocol.deposit_bond_ix(&industry, MIN_BOND), &[industry.authority]).unwrap();
+    protocol.verified_report(&industry, 10 * CT);
+    close_period(&mut protocol);
+
+    let intruder = protocol.ctx.funded_wallet(1);
+    let result = protocol.send(protocol.settle_period_ix(&intruder, &industry), &[intruder]);
+    assert_eq!(result.unwrap_err(), anchor_error(ErrorCode::AccountNotInitialized));
+
+    let mut instruction = protocol.settle_period_ix(&admin, &industry);
+    instruction.accounts[5].pubkey = admin;
+    let result = protocol.send(instruction, &[admin]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::InvalidTreasury));
+}
@@ -0,0 +1,164 @@
+mod common;
+
+use anchor_lang::{error::ErrorCode, prelude::Pubkey, InstructionData, ToAccountMetas};
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_spl::token_2022::spl_token_2022::{
+    self,
+    extension::{transfer_hook::TransferHook, BaseStateWithExtensions, StateWithExtensions},
+};
+use carbon::{CarbonError, IndustrySuspensionChanged, TraderAccredited, TraderAccreditationRevoked};
+use common::*;
+
+/// Two verified industries that each bought 100 CT from their own auction.
+fn trading_pair(protocol: &mut Protocol) -> (Industry, Industry) {
+    let seller = protocol.verified_industry("Acme Steel");
+    let buyer = protocol.verified_industry("Globex Cement");
+    protocol.buy_credits(1, &seller, 100 * CT);
+    protocol.buy_credits(2, &buyer, 100 * CT);
+    (seller, buyer)
+}
+
+#[test]
+fn initialize_with_transfer_hook_creates_hooked_mint() {
+    let protocol = Protocol::with_transfer_hook();
+
+    let config = protocol.config();
+    assert_eq!(config.ct_mint, protocol.ct_mint);
+    assert_eq!(config.ct_token_program, spl_token_2022::ID);
+
+    let mint = protocol.ctx.get_account(&protocol.ct_mint).unwrap();
+    assert_eq!(mint.owner, spl_token_2022::ID);
+    let state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint.data).unwrap();
+    assert_eq!(state.base.mint_authority.unwrap(), mint_authority_pda());
+    let hook = state.get_extension::<TransferHook>().unwrap();
+    assert_eq!(Option::<Pubkey>::from(hook.program_id), Some(carbon::ID));
+
+    assert_eq!(protocol.ctx.get_account(&extra_account_metas_pda(&protocol.ct_mint)).unwrap().owner, carbon::ID);
+}
+
+#[test]
+fn verified_industries_can_trade() {
+    let mut protocol = Protocol::with_transfer_hook();
+    let (seller, buyer) = trading_pair(&mut protocol);
+
+    protocol.send(protocol.transfer_ct_ix(&seller.authority, &buyer.authority, 40 * CT), &[seller.authority]).unwrap();
+
+    assert_eq!(protocol.ctx.token_balance(&seller.ct_account), 60 * CT);
+    assert_eq!(protocol.ctx.token_balance(&buyer.ct_account), 140 * CT);
+}
+
+#[test]
+fn transfers_to_unverified_wallets_are_rejected() {
+    let mut protocol = Protocol::with_transfer_hook();
+    let (seller, _) = trading_pair(&mut protocol);
+    let outsider = protocol.ctx.funded_wallet(10);
+    protocol.send(protocol.create_ct_account_ix(&outsider, &outsider), &[outsider]).unwrap();
+
+    let result = protocol.send(protocol.transfer_ct_ix(&seller.authority, &outsider, CT), &[seller.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::TransferNotPermitted));
+
+    // Registering is not enough; the industry has to be verified
+    let registered = protocol.industry(&outsider);
+    protocol.send(protocol.register_industry_ix(&registered, "Initech", MIN_BOND), &[outsider]).unwrap();
+    let result = protocol.send(protocol.transfer_ct_ix(&seller.authority, &outsider, CT), &[seller.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::TransferNotPermitted));
+}
+
+#[test]
+fn suspended_industries_cannot_trade() {
+    let mut protocol = Protocol::with_transfer_hook();
+    let admin = protocol.admin;
+    let (seller, buyer) = trading_pair(&mut protocol);
+
+    protocol.send(protocol.set_industry_suspension_ix(&admin, &buyer, true), &[admin]).unwrap();
+    assert!(protocol.industry_state(&buyer).suspended);
+    let events = protocol.ctx.events::<IndustrySuspensionChanged>();
+    assert_eq!(events[0].industry, buyer.account);
+    assert!(events[0].suspended);
+
+    let result = protocol.send(protocol.transfer_ct_ix(&seller.authority, &buyer.authority, CT), &[seller.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::TransferNotPermitted));
+    let result = protocol.send(protocol.transfer_ct_ix(&buyer.authority, &seller.authority, CT), &[buyer.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::TransferNotPermitted));
+
+    protocol.send(protocol.set_industry_suspension_ix(&admin, &buyer, false), &[admin]).unwrap();
+    protocol.send(protocol.transfer_ct_ix(&seller.authority, &buyer.authority, CT), &[seller.authority]).unwrap();
+}
+
+#[test]
+fn accredited_traders_can_hold_ct() {
+    let mut protocol = Protocol::with_transfer_hook();
+    let admin = protocol.admin;
+    let (seller, _) = trading_pair(&mut protocol);
+    let trader = protocol.ctx.funded_wallet(10);
+    protocol.send(protocol.create_ct_account_ix(&trader, &trader), &[trader]).unwrap();
+
+    protocol.send(protocol.accredit_trader_ix(&admin, &trader), &[admin]).unwrap();
+    let accreditation: carbon::AccreditedTrader = protocol.ctx.account(&trader_pda(&trader));
+    assert_eq!(accreditation.trader, trader);
+    assert_eq!(accreditation.accredited_by, admin);
+    assert_eq!(protocol.ctx.events::<TraderAccredited>()[0].trader, trader);
+
+    protocol.send(protocol.transfer_ct_ix(&seller.authority, &trader, 30 * CT), &[seller.authority]).unwrap();
+    protocol.send(protocol.transfer_ct_ix(&trader, &seller.authority, 10 * CT), &[trader]).unwrap();
+    assert_eq!(protocol.ctx.token_balance(&protocol.ct_account(&trader)), 20 * CT);
+
+    protocol.send(protocol.revoke_trader_accreditation_ix(&admin, &trader), &[admin]).unwrap();
+    assert!(!protocol.ctx.exists(&trader_pda(&trader)));
+    assert_eq!(protocol.ctx.events::<TraderAccreditationRevoked>()[0].trader, trader);
+
+    let result = protocol.send(protocol.transfer_ct_ix(&trader, &seller.authority, CT), &[trader]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::TransferNotPermitted));
+}
+
+#[test]
+fn hook_rejects_direct_invocation() {
+    let mut protocol = Protocol::with_transfer_hook();
+    let (seller, buyer) = trading_pair(&mut protocol);
+
+    let instruction = Instruction {
+        program_id: carbon::ID,
+        accounts: carbon::accounts::TransferHook {
+            source_token: seller.ct_account,
+            mint: protocol.ct_mint,
+            destination_token: buyer.ct_account,
+            owner: seller.authority,
+            extra_account_meta_list: extra_account_metas_pda(&protocol.ct_mint),
+            source_industry: seller.account,
+            destination_industry: buyer.account,
+            source_trader: trader_pda(&seller.authority),
+            destination_trader: trader_pda(&buyer.authority),
+        }
+        .to_account_metas(None),
+        data: carbon::instruction::TransferHook { _amount: CT }.data(),
+    };
+
+    let result = protocol.send(instruction, &[seller.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::NotTransferring));
+}
+
+#[test]
+fn hooked_mint_supports_compliance_burns() {
+    let mut protocol = Protocol::with_transfer_hook();
+    let industry = protocol.verified_industry("Acme Steel");
+    protocol.buy_credits(1, &industry, 20 * CT);
+    protocol.verified_report(&industry, 15 * CT);
+
+    protocol.send(protocol.burn_ix(&industry, 15 * CT), &[industry.authority]).unwrap();
+
+    assert_eq!(protocol.ctx.token_balance(&industry.ct_account), 5 * CT);
+    assert_eq!(protocol.industry_state(&industry).total_burned, 15 * CT);
+}
+
+#[test]
+fn allowlist_instructions_require_roles() {
+    let mut protocol = Protocol::with_transfer_hook();
+    let industry = protocol.verified_industry("Acme Steel");
+    let intruder = protocol.ctx.funded_wallet(1);
+
+    let result = protocol.send(protocol.set_industry_suspension_ix(&intruder, &industry, true), &[intruder]);
+    assert_eq!(result.unwrap_err(), anchor_error(ErrorCode::AccountNotInitialized));
+
+    let result = protocol.send(protocol.accredit_trader_ix(&intruder, &intruder), &[intruder]);
+    assert_eq!(result.unwrap_err(), anchor_error(ErrorCode::AccountNotInitialized));
+}
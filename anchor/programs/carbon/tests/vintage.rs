@@ -0,0 +1,130 @@
+mod common;
+
+use carbon::{AuctionCreated, BankingRulesUpdated, CarbonError, CreditsBurned, OfferSide, VintageHolding};
+use common::*;
+
+fn advance_period(protocol: &mut Protocol) {
+    let admin = protocol.admin;
+    let deadline = protocol.ctx.now() + 3_600;
+    protocol.send(protocol.advance_compliance_period_ix(deadline), &[admin]).unwrap();
+    let period = protocol.config().compliance_period;
+    protocol.open_cap_schedule(period);
+}
+
+fn holding(protocol: &Protocol, industry: &Industry, vintage: u32) -> u64 {
+    protocol.ctx.account::<VintageHolding>(&vintage_pda(&industry.account, vintage)).amount
+}
+
+#[test]
+fn auctions_sell_the_open_periods_vintage() {
+    let mut protocol = Protocol::new();
+    let industry = protocol.verified_industry("Acme Steel");
+
+    protocol.buy_credits(1, &industry, 10 * CT);
+    assert_eq!(protocol.auction_state(1).vintage, 1);
+
+    advance_period(&mut protocol);
+    let admin = protocol.admin;
+    protocol.send(protocol.create_auction_ix(&admin, AuctionParams::standard(2)), &[admin]).unwrap();
+    assert_eq!(protocol.auction_state(2).vintage, 2);
+    assert_eq!(protocol.ctx.events::<AuctionCreated>()[0].vintage, 2);
+}
+
+#[test]
+fn claims_credit_the_auctions_vintage() {
+    let mut protocol = Protocol::new();
+    let industry = protocol.verified_industry("Acme Steel");
+    protocol.buy_credits(1, &industry, 10 * CT);
+    advance_period(&mut protocol);
+    protocol.buy_credits(2, &industry, 25 * CT);
+
+    let first: VintageHolding = protocol.ctx.account(&vintage_pda(&industry.account, 1));
+    assert_eq!(first.industry, industry.account);
+    assert_eq!(first.vintage, 1);
+    assert_eq!(first.amount, 10 * CT);
+    assert_eq!(holding(&protocol, &industry, 2), 25 * CT);
+    assert_eq!(protocol.industry_state(&industry).ct_balance, 35 * CT);
+}
+
+#[test]
+fn burn_draws_on_the_chosen_vintage() {
+    let mut protocol = Protocol::new();
+    let industry = protocol.verified_industry("Acme Steel");
+    protocol.buy_credits(1, &industry, 10 * CT);
+    advance_period(&mut protocol);
+    protocol.buy_credits(2, &industry, 25 * CT);
+    protocol.verified_report(&industry, 30 * CT);
+
+    // The industry holds enough CT in total, but not of this vintage
+    let result = protocol.send(protocol.burn_vintage_ix(&industry, 1, 15 * CT), &[industry.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::InsufficientVintageBalance));
+
+    protocol.send(protocol.burn_vintage_ix(&industry, 2, 20 * CT), &[industry.authority]).unwrap();
+    assert_eq!(protocol.ctx.events::<CreditsBurned>()[0].vintage, 2);
+    protocol.send(protocol.burn_vintage_ix(&industry, 1, 10 * CT), &[industry.authority]).unwrap();
+
+    assert_eq!(holding(&protocol, &industry, 1), 0);
+    assert_eq!(holding(&protocol, &industry, 2), 5 * CT);
+    assert_eq!(protocol.industry_state(&industry).period_surrendered, 30 * CT);
+}
+
+#[test]
+fn banking_rules_expire_old_vintages() {
+    let mut protocol = Protocol::new();
+    let industry = protocol.verified_industry("Acme Steel");
+    protocol.buy_credits(1, &industry, 10 * CT);
+    advance_period(&mut protocol);
+    protocol.verified_report(&industry, 10 * CT);
+
+    protocol.send(protocol.set_banking_rules_ix(Some(0)), &[protocol.admin]).unwrap();
+    assert_eq!(protocol.config().banking_periods, Some(0));
+    let events = protocol.ctx.events::<BankingRulesUpdated>();
+    assert_eq!(events[0].compliance_period, 2);
+    assert_eq!(events[0].banking_periods, Some(0));
+
+    let result = protocol.send(protocol.burn_ix(&industry, 5 * CT), &[industry.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::VintageNotSurrenderable));
+
+    // Banking one period lets vintage 1 cover period 2, but not period 3
+    protocol.send(protocol.set_banking_rules_ix(Some(1)), &[protocol.admin]).unwrap();
+    protocol.send(protocol.burn_ix(&industry, 5 * CT), &[industry.authority]).unwrap();
+
+    advance_period(&mut protocol);
+    let result = protocol.send(protocol.burn_ix(&industry, 5 * CT), &[industry.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::VintageNotSurrenderable));
+
+    protocol.send(protocol.set_banking_rules_ix(None), &[protocol.admin]).unwrap();
+    protocol.send(protocol.burn_ix(&industry, 5 * CT), &[industry.authority]).unwrap();
+}
+
+#[test]
+fn banking_rules_require_admin() {
+    let mut protocol = Protocol::new();
+    let intruder = protocol.ctx.funded_wallet(1);
+
+    let mut instruction = protocol.set_banking_rules_ix(Some(0));
+    instruction.accounts[1].pubkey = intruder;
+    let result = protocol.send(instruction, &[intruder]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::Unauthorized));
+}
+
+#[test]
+fn offers_trade_a_single_vintage() {
+    let mut protocol = Protocol::new();
+    let seller = protocol.verified_industry("Acme Steel");
+    let buyer = protocol.verified_industry("Globex Cement");
+    protocol.buy_credits(1, &seller, 10 * CT);
+    advance_period(&mut protocol);
+    protocol.buy_credits(2, &seller, 10 * CT);
+
+    let result = protocol.send(protocol.create_offer_ix(&seller, 1, OfferSide::Sell, 2, 15 * CT, 1_000_000), &[seller.authority]);
+    assert_eq!(result.unwrap_err(), carbon_error(CarbonError::InsufficientVintageBalance));
+
+    protocol.send(protocol.create_offer_ix(&seller, 1, OfferSide::Sell, 2, 6 * CT, 1_000_000), &[seller.authority]).unwrap();
+    protocol.send(protocol.accept_offer_ix(&buyer, &seller, 1, OfferSide::Sell, 2), &[buyer.authority]).unwrap();
+
+    assert_eq!(holding(&protocol, &seller, 1), 10 * CT);
+    assert_eq!(holding(&protocol, &seller, 2), 4 * CT);
+    assert_eq!(holding(&protocol, &buyer, 2), 6 * CT);
+    assert!(!protocol.ctx.exists(&vintage_pda(&buyer.account, 1)));
+}
@@ -20,8 +20,38 @@ describe('carbon credit and auction', () => {
   const industryAuthority = Keypair.generate()
   const admin = payer // Using payer as admin
   const ctMintKeypair = Keypair.generate()
+  const treasuryKeypair = Keypair.generate()
+
+  // Role indices match the on-chain `Role` enum and seed each `RoleAssignment` PDA
+  const ROLE_VERIFIER = 0
+  const ROLE_AUCTION_OPERATOR = 1
+  const ROLE_TREASURER = 2
+  const ROLE_REGULATOR = 3
+  const rolePda = (role: number, holder: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from('role'), Buffer.from([role]), holder.toBuffer()],
+      program.programId
+    )[0]
+  // Vintage holdings are keyed by industry and the compliance period the CT was issued for
+  const vintagePda = (industry: PublicKey, vintage: number) => {
+    const vintageBytes = Buffer.alloc(4)
+    vintageBytes.writeUInt32LE(vintage)
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from('vintage'), industry.toBuffer(), vintageBytes],
+      program.programId
+    )[0]
+  }
+  const capSchedulePda = (compliancePeriod: number) => {
+    const periodBytes = Buffer.alloc(4)
+    periodBytes.writeUInt32LE(compliancePeriod)
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from('cap_schedule'), periodBytes],
+      program.programId
+    )[0]
+  }
 
   // PDAs
+  let config: PublicKey
   let mintAuthority: PublicKey
   let industryAccount: PublicKey
   let industryAccountBump: number
@@ -47,6 +77,8 @@ describe('carbon credit and auction', () => {
     await provider.connection.confirmTransaction(airdropSig)
 
     // Derive PDAs
+    ;[config] = PublicKey.findProgramAddressSync([Buffer.from('config')], program.programId)
+
     ;[mintAuthority] = PublicKey.findProgramAddressSync(
       [Buffer.from('mint_authority')],
       program.programId
@@ -71,9 +103,10 @@ describe('carbon credit and auction', () => {
   describe('Initialization', () => {
     it('Initialize CT Mint', async () => {
       const tx = await program.methods
-        .initialize()
+        .initialize(treasuryKeypair.publicKey)
         .accountsStrict({
           authority: payer.publicKey,
+          config,
           ctMint: ctMintKeypair.publicKey,
           mintAuthority,
           tokenProgram: TOKEN_PROGRAM_ID,
@@ -88,6 +121,91 @@ describe('carbon credit and auction', () => {
       // Verify mint was created
       const mintInfo = await provider.connection.getAccountInfo(ctMintKeypair.publicKey)
       expect(mintInfo).not.toBeNull()
+
+      const programConfig = await program.account.programConfig.fetch(config)
+      expect(programConfig.admin.toBase58()).toEqual(admin.publicKey.toBase58())
+      expect(programConfig.ctMint.toBase58()).toEqual(ctMintKeypair.publicKey.toBase58())
+      expect(programConfig.treasury.toBase58()).toEqual(treasuryKeypair.publicKey.toBase58())
+    })
+
+    it('Grant verifier, auction operator and treasurer roles', async () => {
+      const roles = [
+        { arg: { verifier: {} }, index: ROLE_VERIFIER },
+        { arg: { auctionOperator: {} }, index: ROLE_AUCTION_OPERATOR },
+        { arg: { treasurer: {} }, index: ROLE_TREASURER },
+      ]
+
+      for (const { arg, index } of roles) {
+        await program.methods
+          .grantRole(arg, admin.publicKey)
+          .accountsStrict({
+            config,
+            roleAssignment: rolePda(index, admin.publicKey),
+            admin: admin.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .rpc()
+      }
+
+      const assignment = await program.account.roleAssignment.fetch(rolePda(ROLE_VERIFIER, admin.publicKey))
+      expect(assignment.holder.toBase58()).toEqual(admin.publicKey.toBase58())
+      expect(assignment.role).toEqual({ verifier: {} })
+    })
+
+    it('Revoke a role', async () => {
+      const auditor = Keypair.generate()
+      const roleAssignment = rolePda(ROLE_VERIFIER, auditor.publicKey)
+
+      await program.methods
+        .grantRole({ verifier: {} }, auditor.publicKey)
+        .accountsStrict({
+          config,
+          roleAssignment,
+          admin: admin.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc()
+
+      await program.methods
+        .revokeRole()
+        .accountsStrict({ config, roleAssignment, admin: admin.publicKey })
+        .rpc()
+
+      const info = await provider.connection.getAccountInfo(roleAssignment)
+      expect(info).toBeNull()
+    })
+
+    it('Rotate admin through propose and accept', async () => {
+      const newAdmin = Keypair.generate()
+
+      await program.methods
+        .proposeAdmin(newAdmin.publicKey)
+        .accountsStrict({ config, admin: admin.publicKey })
+        .rpc()
+
+      let programConfig = await program.account.programConfig.fetch(config)
+      expect(programConfig.pendingAdmin?.toBase58()).toEqual(newAdmin.publicKey.toBase58())
+
+      await program.methods
+        .acceptAdmin()
+        .accountsStrict({ config, newAdmin: newAdmin.publicKey })
+        .signers([newAdmin])
+        .rpc()
+
+      // Hand the key back so the rest of the suite can keep using the payer as admin
+      await program.methods
+        .proposeAdmin(admin.publicKey)
+        .accountsStrict({ config, admin: newAdmin.publicKey })
+        .signers([newAdmin])
+        .rpc()
+      await program.methods
+        .acceptAdmin()
+        .accountsStrict({ config, newAdmin: admin.publicKey })
+        .rpc()
+
+      programConfig = await program.account.programConfig.fetch(config)
+      expect(programConfig.admin.toBase58()).toEqual(admin.publicKey.toBase58())
+      expect(programConfig.pendingAdmin).toBeNull()
     })
   })
 
@@ -97,6 +215,7 @@ describe('carbon credit and auction', () => {
         .registerIndustry(COMPANY_NAME, BOND_AMOUNT)
         .accountsStrict({
           industryAccount,
+          config,
           authority: industryAuthority.publicKey,
           systemProgram: SystemProgram.programId,
         })
@@ -122,11 +241,31 @@ describe('carbon credit and auction', () => {
       console.log('Bond vault PDA:', bondVault.toBase58(), 'bump:', bondVaultBump)
     })
 
+    it('Fail to verify industry without verifier role', async () => {
+      const impostor = Keypair.generate()
+
+      try {
+        await program.methods
+          .verifyIndustry()
+          .accountsStrict({
+            roleAssignment: rolePda(ROLE_VERIFIER, impostor.publicKey),
+            verifier: impostor.publicKey,
+            industryAccount,
+          })
+          .signers([impostor])
+          .rpc()
+        fail('Should have thrown an error')
+      } catch (error: any) {
+        expect(error.error.errorCode.code).toEqual('AccountNotInitialized')
+      }
+    })
+
     it('Verify Industry', async () => {
       const tx = await program.methods
         .verifyIndustry()
         .accountsStrict({
-          admin: admin.publicKey,
+          roleAssignment: rolePda(ROLE_VERIFIER, admin.publicKey),
+          verifier: admin.publicKey,
           industryAccount,
         })
         .rpc()
@@ -189,6 +328,8 @@ describe('carbon credit and auction', () => {
         .accountsStrict({
           industryAccount,
           bondVault,
+          config,
+          proposal: null,
           authority: industryAuthority.publicKey,
           systemProgram: SystemProgram.programId,
         })
@@ -209,12 +350,14 @@ describe('carbon credit and auction', () => {
   })
 
   describe('Emission Reporting', () => {
+    let emissionReport: PublicKey
+
     it('Submit Emission Report', async () => {
       const timestamp = new BN(Date.now())
       const co2Emitted = new BN(500 * 1e9) // 500 tons
       const reportPeriod = "2024-Q1"
 
-      const [emissionReport] = PublicKey.findProgramAddressSync(
+      ;[emissionReport] = PublicKey.findProgramAddressSync(
         [
           Buffer.from('emission_report'),
           industryAccount.toBuffer(),
@@ -227,6 +370,7 @@ describe('carbon credit and auction', () => {
         .submitEmissionReport(co2Emitted, reportPeriod, timestamp)
         .accountsStrict({
           industryAccount,
+          config,
           emissionReport,
           authority: industryAuthority.publicKey,
           systemProgram: SystemProgram.programId,
@@ -242,6 +386,54 @@ describe('carbon credit and auction', () => {
       expect(report.co2Emitted.toString()).toEqual(co2Emitted.toString())
       expect(report.reportPeriod).toEqual(reportPeriod)
       expect(report.verified).toBeFalsy()
+      expect(report.status).toEqual({ pending: {} })
+      expect(report.compliancePeriod).toEqual(1)
+    })
+
+    it('Verify Emission Report with an adjusted figure', async () => {
+      const adjusted = new BN(450 * 1e9)
+
+      const tx = await program.methods
+        .verifyEmissionReport(adjusted)
+        .accountsStrict({
+          config,
+          roleAssignment: rolePda(ROLE_VERIFIER, admin.publicKey),
+          verifier: admin.publicKey,
+          emissionReport,
+          industryAccount,
+        })
+        .rpc()
+
+      console.log('Verify Emission Report tx:', tx)
+
+      const report = await program.account.emissionReport.fetch(emissionReport)
+      expect(report.verified).toBeTruthy()
+      expect(report.status).toEqual({ verified: {} })
+      expect(report.verifier?.toBase58()).toEqual(admin.publicKey.toBase58())
+      expect(report.adjustedCo2Emitted?.toString()).toEqual(adjusted.toString())
+
+      const industry = await program.account.industryAccount.fetch(industryAccount)
+      expect(industry.verifiedEmissions.toString()).toEqual(adjusted.toString())
+      expect(industry.periodObligation.toString()).toEqual(adjusted.toString())
+      expect(industry.complianceStatus).toEqual({ nonCompliant: {} })
+    })
+
+    it('Fail to review an already verified report', async () => {
+      try {
+        await program.methods
+          .rejectEmissionReport()
+          .accountsStrict({
+            config,
+            roleAssignment: rolePda(ROLE_VERIFIER, admin.publicKey),
+            verifier: admin.publicKey,
+            emissionReport,
+            industryAccount,
+          })
+          .rpc()
+        fail('Should have thrown an error')
+      } catch (error: any) {
+        expect(error.error.errorMessage).toContain('already been reviewed')
+      }
     })
   })
 
@@ -250,6 +442,21 @@ describe('carbon credit and auction', () => {
     let bidAccount: PublicKey
     let industryTokenAccount: PublicKey
 
+    it('Set Cap Schedule', async () => {
+      await program.methods
+        .setCapSchedule(1, TOTAL_TOKENS.muln(10), new BN(0), new BN(0))
+        .accountsStrict({
+          config,
+          capSchedule: capSchedulePda(1),
+          admin: admin.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc()
+
+      const schedule = await program.account.capSchedule.fetch(capSchedulePda(1))
+      expect(schedule.auctionCap.toString()).toEqual(TOTAL_TOKENS.muln(10).toString())
+    })
+
     it('Create Auction', async () => {
       const tx = await program.methods
         .createAuction(
@@ -261,6 +468,9 @@ describe('carbon credit and auction', () => {
         )
         .accountsStrict({
           auction: auctionAccount,
+          config,
+          capSchedule: capSchedulePda(1),
+          roleAssignment: rolePda(ROLE_AUCTION_OPERATOR, admin.publicKey),
           authority: admin.publicKey,
           systemProgram: SystemProgram.programId,
         })
@@ -307,8 +517,17 @@ describe('carbon credit and auction', () => {
         .accountsStrict({
           auction: auctionAccount,
           bid: bidAccount,
+          position: PublicKey.findProgramAddressSync(
+            [Buffer.from('position'), auctionAccount.toBuffer(), industryAuthority.publicKey.toBuffer()],
+            program.programId
+          )[0],
           industryAccount,
           escrow: escrowAccount,
+          paymentMint: null,
+          escrowToken: null,
+          bidderToken: null,
+          paymentTokenProgram: null,
+          proposal: null,
           bidder: industryAuthority.publicKey,
           systemProgram: SystemProgram.programId,
         })
@@ -339,6 +558,7 @@ describe('carbon credit and auction', () => {
         .finalizeAuction()
         .accountsStrict({
           auction: auctionAccount,
+          roleAssignment: rolePda(ROLE_AUCTION_OPERATOR, admin.publicKey),
           authority: admin.publicKey,
         })
         .rpc()
@@ -378,11 +598,23 @@ describe('carbon credit and auction', () => {
           auction: auctionAccount,
           bid: bidAccount,
           industryAccount,
+          position: PublicKey.findProgramAddressSync(
+            [Buffer.from('position'), auctionAccount.toBuffer(), industryAuthority.publicKey.toBuffer()],
+            program.programId
+          )[0],
+          vintageHolding: vintagePda(industryAccount, 1),
+          config,
           ctMint: ctMintKeypair.publicKey,
           industryCtAccount: industryTokenAccount,
           mintAuthority,
           escrow: escrowAccount,
+          paymentMint: null,
+          escrowToken: null,
+          bidderToken: null,
+          paymentTokenProgram: null,
           bidder: industryAuthority.publicKey,
+          roleAssignment: null,
+          authority: industryAuthority.publicKey,
           tokenProgram: TOKEN_PROGRAM_ID,
           associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
@@ -403,14 +635,18 @@ describe('carbon credit and auction', () => {
     })
 
     it('Withdraw Proceeds', async () => {
-      const treasuryKeypair = Keypair.generate()
-
       const tx = await program.methods
         .withdrawProceeds()
         .accountsStrict({
           auction: auctionAccount,
           escrow: escrowAccount,
+          config,
+          roleAssignment: rolePda(ROLE_TREASURER, admin.publicKey),
           treasury: treasuryKeypair.publicKey,
+          paymentMint: null,
+          escrowToken: null,
+          treasuryToken: null,
+          paymentTokenProgram: null,
           authority: admin.publicKey,
           systemProgram: SystemProgram.programId,
         })
@@ -428,7 +664,6 @@ describe('carbon credit and auction', () => {
     it('Burn CT for Compliance', async () => {
       const industry = await program.account.industryAccount.fetch(industryAccount)
       const burnAmount = industry.ctBalance.div(new BN(2)) // Burn half
-      const emissionAmount = burnAmount
 
       const industryTokenAccount = await getAssociatedTokenAddress(
         ctMintKeypair.publicKey,
@@ -436,11 +671,14 @@ describe('carbon credit and auction', () => {
       )
 
       const tx = await program.methods
-        .burnCtForCompliance(burnAmount, emissionAmount)
+        .burnCtForCompliance(burnAmount)
         .accountsStrict({
           industryAccount,
+          vintageHolding: vintagePda(industryAccount, 1),
+          config,
           ctMint: ctMintKeypair.publicKey,
           industryCtAccount: industryTokenAccount,
+          proposal: null,
           authority: industryAuthority.publicKey,
           tokenProgram: TOKEN_PROGRAM_ID,
         })
@@ -455,7 +693,9 @@ describe('carbon credit and auction', () => {
       expect(updatedIndustry.ctBalance.toString()).toEqual(
         industry.ctBalance.sub(burnAmount).toString()
       )
-      expect(updatedIndustry.complianceStatus).toEqual({ compliant: {} })
+      expect(updatedIndustry.periodSurrendered.toString()).toEqual(burnAmount.toString())
+      // Half the purchased credits do not cover the verified 450 t obligation
+      expect(updatedIndustry.complianceStatus).toEqual({ nonCompliant: {} })
     })
 
     it('Fail to burn more than balance', async () => {
@@ -469,11 +709,14 @@ describe('carbon credit and auction', () => {
 
       try {
         await program.methods
-          .burnCtForCompliance(excessAmount, excessAmount)
+          .burnCtForCompliance(excessAmount)
           .accountsStrict({
             industryAccount,
+            vintageHolding: vintagePda(industryAccount, 1),
+            config,
             ctMint: ctMintKeypair.publicKey,
             industryCtAccount: industryTokenAccount,
+            proposal: null,
             authority: industryAuthority.publicKey,
             tokenProgram: TOKEN_PROGRAM_ID,
           })
@@ -486,6 +729,72 @@ describe('carbon credit and auction', () => {
     })
   })
 
+  describe('Bond Slashing', () => {
+    it('Slash bond of a non-compliant industry past the deadline', async () => {
+      const penaltyPerTonne = new BN(1_000_000) // 0.001 SOL per tonne of shortfall
+
+      await program.methods
+        .grantRole({ regulator: {} }, admin.publicKey)
+        .accountsStrict({
+          config,
+          roleAssignment: rolePda(ROLE_REGULATOR, admin.publicKey),
+          admin: admin.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc()
+
+      // Set a deadline just ahead and let it lapse so the shortfall is enforceable
+      await program.methods
+        .setComplianceTerms(new BN(Math.floor(Date.now() / 1000) + 2), penaltyPerTonne)
+        .accountsStrict({ config, admin: admin.publicKey })
+        .rpc()
+      await new Promise(resolve => setTimeout(resolve, 4000))
+
+      const treasuryBefore = await provider.connection.getBalance(treasuryKeypair.publicKey)
+
+      const tx = await program.methods
+        .slashBond()
+        .accountsStrict({
+          config,
+          roleAssignment: rolePda(ROLE_REGULATOR, admin.publicKey),
+          regulator: admin.publicKey,
+          industryAccount,
+          bondVault,
+          treasury: treasuryKeypair.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc()
+
+      console.log('Slash Bond tx:', tx)
+
+      const treasuryAfter = await provider.connection.getBalance(treasuryKeypair.publicKey)
+      const industry = await program.account.industryAccount.fetch(industryAccount)
+      expect(industry.slashCount).toEqual(1)
+      expect(industry.lastSlashedPeriod).toEqual(1)
+      expect(industry.totalSlashed.toNumber()).toEqual(treasuryAfter - treasuryBefore)
+    })
+
+    it('Fail to slash twice in the same period', async () => {
+      try {
+        await program.methods
+          .slashBond()
+          .accountsStrict({
+            config,
+            roleAssignment: rolePda(ROLE_REGULATOR, admin.publicKey),
+            regulator: admin.publicKey,
+            industryAccount,
+            bondVault,
+            treasury: treasuryKeypair.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .rpc()
+        fail('Should have thrown an error')
+      } catch (error: any) {
+        expect(error.error.errorMessage).toContain('already been slashed')
+      }
+    })
+  })
+
   describe('Auction Cancellation', () => {
     it('Cancel Auction with no participants', async () => {
       const newBatchNumber = 999
@@ -505,6 +814,9 @@ describe('carbon credit and auction', () => {
         )
         .accountsStrict({
           auction: newAuction,
+          config,
+          capSchedule: capSchedulePda(1),
+          roleAssignment: rolePda(ROLE_AUCTION_OPERATOR, admin.publicKey),
           authority: admin.publicKey,
           systemProgram: SystemProgram.programId,
         })
@@ -515,6 +827,8 @@ describe('carbon credit and auction', () => {
         .cancelAuction()
         .accountsStrict({
           auction: newAuction,
+          capSchedule: capSchedulePda(1),
+          roleAssignment: rolePda(ROLE_AUCTION_OPERATOR, admin.publicKey),
           authority: admin.publicKey,
         })
         .rpc()
@@ -25,10 +25,21 @@ export type AuctionStatus =
     { finalized: Record<string, never> } |
     { cancelled: Record<string, never> };
 
+export type AuctionKind = 
+    { dutch: Record<string, never> } | 
+    { sealedBid: Record<string, never> };
+
+export type PriceCurve = 
+    { linear: Record<string, never> } | 
+    { exponential: { halfLifeSeconds: BN } } |
+    { stepwise: { stepSeconds: BN, decrement: BN } };
+
 export type BidStatus = 
     { pending: Record<string, never> } | 
     { accepted: Record<string, never> } |
-    { refunded: Record<string, never> };
+    { refunded: Record<string, never> } |
+    { committed: Record<string, never> } |
+    { forfeited: Record<string, never> };
 
 export interface EmissionReport {
     industry: PublicKey,
@@ -43,9 +54,16 @@ export interface IndustryAccount {
     companyName: string,
     bondAmount: BN,
     verified: boolean,
+    suspended: boolean,
     ctBalance: BN,
     totalPurchased: BN,
+    totalAllocated: BN,
+    offsetBalance: BN,
     totalBurned: BN,
+    openPositions: number,
+    pendingAuthority: PublicKey | null,
+    signers: PublicKey[],
+    signerThreshold: number,
     complianceStatus: ComplianceStatus,
     createdAt: BN,
     bump: number,
@@ -61,6 +79,8 @@ export interface Bid {
     totalCost: BN,
     timestamp: BN,
     status: BidStatus,
+    commitment: number[],
+    collateral: BN,
 }
 
 export interface Auction {
@@ -77,6 +97,16 @@ export interface Auction {
     totalRaised: BN,
     participantCount: number,
     tokensSold: BN,
+    kind: AuctionKind,
+    revealEndTime: BN,
+    minCollateral: BN,
+    revealedCount: number,
+    maxTokensPerBidder: BN,
+    maxBidderShareBps: number,
+    settledCount: number,
+    vintage: number,
+    curve: PriceCurve,
+    paymentMint: PublicKey | null,
 }
 
 export default function CarbonChainUI() {
@@ -90,7 +120,6 @@ export default function CarbonChainUI() {
   const [depositAmount, setDepositAmount] = useState('')
   const [withdrawAmount, setWithdrawAmount] = useState('')
   const [burnAmount, setBurnAmount] = useState('')
-  const [emissionAmount, setEmissionAmount] = useState('')
   const [co2Emitted, setCo2Emitted] = useState('')
   const [reportPeriod, setReportPeriod] = useState('')
   // const [batchNumber, setBatchNumber] = useState('')
@@ -128,6 +157,8 @@ export default function CarbonChainUI() {
     finalizeAuctionHandler,
     // claimTokensHandler,
     cancelAuctionHandler,
+    refundBidHandler,
+    closeBidHandler,
     withdrawProceedsHandler,
   } = useCarbonProgram()
 
@@ -322,16 +353,14 @@ export default function CarbonChainUI() {
   }
 
   const handleBurnTokens = async () => {
-    if (!publicKey || !burnAmount || !emissionAmount || !detectedCtMint) return
+    if (!publicKey || !burnAmount || !detectedCtMint) return
     try {
       await burnCtForComplianceHandler.mutateAsync({
         industryAuthorityPubkey: publicKey,
         ctMintPubkey: new PublicKey(detectedCtMint),
         burnAmount: new BN(parseFloat(burnAmount) * 1e9),
-        emissionAmount: new BN(parseFloat(emissionAmount) * 1e9)
       })
       setBurnAmount('')
-      setEmissionAmount('')
     } catch (error) {
       console.error(error)
     }
@@ -351,6 +380,32 @@ export default function CarbonChainUI() {
   //   }
   // }
 
+  const handleRefundBid = async (bid: { account: Bid, publicKey: PublicKey }, auction: { account: Auction, publicKey: PublicKey }) => {
+    if (!publicKey) return
+    try {
+      await refundBidHandler.mutateAsync({
+        BATCH_NUMBER: auction.account.batchNumber,
+        bidderPubkey: publicKey,
+        bidTimestamp: bid.account.timestamp
+      })
+    } catch (error) {
+      console.error(error)
+    }
+  }
+
+  const handleCloseBid = async (bid: { account: Bid, publicKey: PublicKey }, auction: { account: Auction, publicKey: PublicKey }) => {
+    if (!publicKey) return
+    try {
+      await closeBidHandler.mutateAsync({
+        BATCH_NUMBER: auction.account.batchNumber,
+        bidderPubkey: publicKey,
+        bidTimestamp: bid.account.timestamp
+      })
+    } catch (error) {
+      console.error(error)
+    }
+  }
+
   const handleVerifyIndustry = async () => {
     if (!publicKey || !verifyIndustryAddress) return
     try {
@@ -888,9 +943,30 @@ export default function CarbonChainUI() {
                                 {/* {statusKey === 'accepted' && auction && Object.keys(auction.account.status)[0] == 'completed'  && (
                                   <span className="text-emerald-600 font-semibold text-sm">✓ Claimed</span>
                                 )} */}
-                                {statusKey === 'pending' && auction && Object.keys(auction.account.status)[0] !== 'finalized' && (
+                                {(statusKey === 'pending' || statusKey === 'committed') && auction && Object.keys(auction.account.status)[0] === 'cancelled' && (
+                                  <button 
+                                    onClick={() => handleRefundBid(bid, auction)}
+                                    disabled={refundBidHandler.isPending}
+                                    className="px-4 py-2 bg-gradient-to-r from-amber-500 to-orange-500 text-white rounded-lg hover:from-amber-600 hover:to-orange-600 transition font-semibold text-sm disabled:opacity-50 disabled:cursor-not-allowed"
+                                  >
+                                    {refundBidHandler.isPending ? 'Refunding...' : 'Refund'}
+                                  </button>
+                                )}
+                                {statusKey === 'pending' && auction && !['finalized', 'cancelled'].includes(Object.keys(auction.account.status)[0]) && (
                                   <span className="text-amber-600 font-semibold text-sm">⏳ Pending</span>
                                 )}
+                                {statusKey === 'refunded' && (
+                                  <span className="text-gray-600 font-semibold text-sm">↩ Refunded</span>
+                                )}
+                                {['accepted', 'refunded', 'forfeited'].includes(statusKey) && auction && (
+                                  <button
+                                    onClick={() => handleCloseBid(bid, auction)}
+                                    disabled={closeBidHandler.isPending}
+                                    className="ml-3 px-3 py-1 border border-gray-300 text-gray-600 rounded-lg hover:bg-gray-100 transition font-semibold text-xs disabled:opacity-50 disabled:cursor-not-allowed"
+                                  >
+                                    {closeBidHandler.isPending ? 'Closing...' : 'Close'}
+                                  </button>
+                                )}
                               </div>
                             </div>
                           </div>
@@ -976,19 +1052,9 @@ export default function CarbonChainUI() {
                                 className="w-full px-4 py-3 border border-gray-200 rounded-xl focus:outline-none focus:ring-2 focus:ring-emerald-500 text-gray-900"
                               />
                             </div>
-                            <div>
-                              <label className="text-sm font-medium text-gray-700 mb-1 block">CO2 Emissions (tons)</label>
-                              <input
-                                type="number"
-                                placeholder="0.00"
-                                value={emissionAmount}
-                                onChange={(e) => setEmissionAmount(e.target.value)}
-                                className="w-full px-4 py-3 border border-gray-200 rounded-xl focus:outline-none focus:ring-2 focus:ring-emerald-500 text-gray-900"
-                              />
-                            </div>
                             <button 
                               onClick={handleBurnTokens}
-                              disabled={!burnAmount || !emissionAmount || !detectedCtMint || burnCtForComplianceHandler.isPending}
+                              disabled={!burnAmount || !detectedCtMint || burnCtForComplianceHandler.isPending}
                               className="w-full px-6 py-3 bg-gradient-to-r from-orange-500 to-red-500 text-white rounded-xl hover:from-orange-600 hover:to-red-600 transition shadow-lg font-semibold disabled:opacity-50 disabled:cursor-not-allowed"
                             >
                               {burnCtForComplianceHandler.isPending ? 'Burning...' : 'Burn Tokens'}
@@ -14,6 +14,28 @@ import BN from 'bn.js'
 
 interface InitializeArgs {
   authorityPubkey: PublicKey
+  treasuryPubkey?: PublicKey // Defaults to the initializing authority
+}
+
+type Role = 'verifier' | 'auctionOperator' | 'treasurer'
+
+// Index of each role in the on-chain `Role` enum, used as the `RoleAssignment` PDA seed
+const ROLE_SEEDS: Record<Role, number> = {
+  verifier: 0,
+  auctionOperator: 1,
+  treasurer: 2,
+}
+
+interface GrantRoleArgs {
+  role: Role
+  holderPubkey: PublicKey
+  adminPubkey: PublicKey
+}
+
+interface RevokeRoleArgs {
+  role: Role
+  holderPubkey: PublicKey
+  adminPubkey: PublicKey
 }
 
 interface RegisterIndustryArgs {
@@ -83,7 +105,19 @@ interface BurnCtForComplianceArgs {
   industryAuthorityPubkey: PublicKey
   ctMintPubkey: PublicKey
   burnAmount: BN
-  emissionAmount: BN
+  vintage?: number // Defaults to the open compliance period
+}
+
+interface RefundBidArgs {
+  BATCH_NUMBER: number
+  bidderPubkey: PublicKey
+  bidTimestamp: BN
+}
+
+interface CloseBidArgs {
+  BATCH_NUMBER: number
+  bidderPubkey: PublicKey
+  bidTimestamp: BN
 }
 
 interface CancelAuctionArgs {
@@ -98,6 +132,52 @@ export function useCarbonProgram() {
   const provider = useAnchorProvider()
   const programId = useMemo(() => getCarbonProgramId(cluster.network as Cluster), [cluster])
   const program = useMemo(() => getCarbonProgram(provider, programId), [provider, programId])
+  const [configAccount] = useMemo(
+    () => PublicKey.findProgramAddressSync([Buffer.from('config')], programId),
+    [programId]
+  )
+  const rolePda = (role: Role, holder: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from('role'), Buffer.from([ROLE_SEEDS[role]]), holder.toBuffer()],
+      programId
+    )[0]
+
+  const vintagePda = (industry: PublicKey, vintage: number) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from('vintage'), industry.toBuffer(), new BN(vintage).toArrayLike(Buffer, 'le', 4)],
+      programId
+    )[0]
+
+  const capSchedulePda = (compliancePeriod: number) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from('cap_schedule'), new BN(compliancePeriod).toArrayLike(Buffer, 'le', 4)],
+      programId
+    )[0]
+
+  // Payment-mint accounts an auction settles through, with `owner` on the other side; all null
+  // for auctions paid in lamports
+  const paymentAccounts = async (auction: PublicKey, owner: PublicKey) => {
+    const { paymentMint } = await program.account.auction.fetch(auction)
+    if (!paymentMint) {
+      return { paymentMint: null, escrowToken: null, tokenAccount: null, paymentTokenProgram: null }
+    }
+    const [escrowToken] = PublicKey.findProgramAddressSync([Buffer.from('escrow_token'), auction.toBuffer()], programId)
+    return {
+      paymentMint,
+      escrowToken,
+      tokenAccount: await getAssociatedTokenAddress(paymentMint, owner),
+      paymentTokenProgram: TOKEN_PROGRAM_ID,
+    }
+  }
+
+  // A bidder's position only exists while one of their placed or revealed bids is still open
+  const openPosition = async (auction: PublicKey, bidder: PublicKey) => {
+    const [position] = PublicKey.findProgramAddressSync(
+      [Buffer.from('position'), auction.toBuffer(), bidder.toBuffer()],
+      programId
+    )
+    return (await connection.getAccountInfo(position)) ? position : null
+  }
 
   const auctionAccounts = useQuery({
     queryKey: ['auction', 'all', { cluster }],
@@ -126,7 +206,7 @@ export function useCarbonProgram() {
 
   const initializeHandler = useMutation<string, Error, InitializeArgs>({
     mutationKey: ['carbon', 'initialize', { cluster }], // Fixed: better naming
-    mutationFn: async ({ authorityPubkey }) => {
+    mutationFn: async ({ authorityPubkey, treasuryPubkey }) => {
       const ctMintKeypair = Keypair.generate()
       const [mintAuthority] = PublicKey.findProgramAddressSync(
         [Buffer.from('mint_authority')],
@@ -134,9 +214,10 @@ export function useCarbonProgram() {
       )
 
       return await program.methods
-        .initialize()
+        .initialize(treasuryPubkey ?? authorityPubkey)
         .accountsStrict({
           authority: authorityPubkey,
+          config: configAccount,
           ctMint: ctMintKeypair.publicKey,
           mintAuthority,
           tokenProgram: TOKEN_PROGRAM_ID,
@@ -159,6 +240,49 @@ export function useCarbonProgram() {
     },
   })
 
+  const grantRoleHandler = useMutation<string, Error, GrantRoleArgs>({
+    mutationKey: ['carbon', 'grantRole', { cluster }],
+    mutationFn: async ({ role, holderPubkey, adminPubkey }) => {
+      return await program.methods
+        .grantRole({ [role]: {} } as never, holderPubkey)
+        .accountsStrict({
+          config: configAccount,
+          roleAssignment: rolePda(role, holderPubkey),
+          admin: adminPubkey,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc()
+    },
+    onSuccess: (signature) => {
+      transactionToast(signature)
+    },
+    onError: (error) => {
+      console.error('Grant role error:', error)
+      toast.error('Failed to grant role')
+    },
+  })
+
+  const revokeRoleHandler = useMutation<string, Error, RevokeRoleArgs>({
+    mutationKey: ['carbon', 'revokeRole', { cluster }],
+    mutationFn: async ({ role, holderPubkey, adminPubkey }) => {
+      return await program.methods
+        .revokeRole()
+        .accountsStrict({
+          config: configAccount,
+          roleAssignment: rolePda(role, holderPubkey),
+          admin: adminPubkey,
+        })
+        .rpc()
+    },
+    onSuccess: (signature) => {
+      transactionToast(signature)
+    },
+    onError: (error) => {
+      console.error('Revoke role error:', error)
+      toast.error('Failed to revoke role')
+    },
+  })
+
   const registerIndustryHandler = useMutation<string, Error, RegisterIndustryArgs>({
     mutationKey: ['carbon', 'registerIndustry', { cluster }],
     mutationFn: async ({ industryAuthority, COMPANY_NAME, BOND_AMOUNT }) => {
@@ -171,6 +295,7 @@ export function useCarbonProgram() {
         .registerIndustry(COMPANY_NAME, BOND_AMOUNT)
         .accountsStrict({
           industryAccount,
+          config: configAccount,
           authority: industryAuthority,
           systemProgram: SystemProgram.programId,
         })
@@ -197,7 +322,8 @@ export function useCarbonProgram() {
       return await program.methods
         .verifyIndustry()
         .accountsStrict({
-          admin: adminPubkey,
+          roleAssignment: rolePda('verifier', adminPubkey),
+          verifier: adminPubkey,
           industryAccount,
         })
         .rpc()
@@ -261,6 +387,8 @@ export function useCarbonProgram() {
         .accountsStrict({
           industryAccount,
           bondVault,
+          config: configAccount,
+          proposal: null,
           authority: industryAuthorityPubkey,
           systemProgram: SystemProgram.programId,
         })
@@ -299,6 +427,7 @@ export function useCarbonProgram() {
         .submitEmissionReport(co2Emitted, reportPeriod, timestamp)
         .accountsStrict({
           industryAccount,
+          config: configAccount,
           emissionReport,
           authority: industryAuthorityPubkey,
           systemProgram: SystemProgram.programId,
@@ -323,6 +452,8 @@ export function useCarbonProgram() {
         [Buffer.from('auction'), new BN(BATCH_NUMBER).toArrayLike(Buffer, 'le', 4)],
         program.programId
       )
+      // Auctions draw on the cap schedule of the open compliance period
+      const { compliancePeriod } = await program.account.programConfig.fetch(configAccount)
 
       return await program.methods
         .createAuction(
@@ -334,6 +465,9 @@ export function useCarbonProgram() {
         )
         .accountsStrict({
           auction: auctionAccount,
+          config: configAccount,
+          capSchedule: capSchedulePda(compliancePeriod),
+          roleAssignment: rolePda('auctionOperator', adminPubkey),
           authority: adminPubkey,
           systemProgram: SystemProgram.programId,
         })
@@ -376,13 +510,24 @@ export function useCarbonProgram() {
         program.programId
       )
 
+      const [positionAccount] = PublicKey.findProgramAddressSync(
+        [Buffer.from('position'), auctionAccount.toBuffer(), industryAuthorityPubkey.toBuffer()],
+        program.programId
+      )
+
+      const { tokenAccount, ...payment } = await paymentAccounts(auctionAccount, industryAuthorityPubkey)
+
       return await program.methods
         .placeBid(tokenAmount, bidTimestamp)
         .accountsStrict({
           auction: auctionAccount,
           bid: bidAccount,
+          position: positionAccount,
           industryAccount,
           escrow: escrowAccount,
+          ...payment,
+          bidderToken: tokenAccount,
+          proposal: null,
           bidder: industryAuthorityPubkey,
           systemProgram: SystemProgram.programId,
         })
@@ -412,6 +557,7 @@ export function useCarbonProgram() {
         .finalizeAuction()
         .accountsStrict({
           auction: auctionAccount,
+          roleAssignment: rolePda('auctionOperator', adminPubkey),
           authority: adminPubkey,
         })
         .rpc()
@@ -452,15 +598,22 @@ export function useCarbonProgram() {
         [Buffer.from('industry'), industryAuthorityPubkey.toBuffer()],
         program.programId
       )
+      // CT lives under SPL Token or Token-2022 depending on how the program was initialized
+      const { ctTokenProgram } = await program.account.programConfig.fetch(configAccount)
       const industryTokenAccount = await getAssociatedTokenAddress(
         ctMintPubkey,
-        industryAuthorityPubkey
+        industryAuthorityPubkey,
+        false,
+        ctTokenProgram
       )
 
       const [mintAuthority] = PublicKey.findProgramAddressSync(
         [Buffer.from('mint_authority')],
         program.programId
       )
+      const { vintage } = await program.account.auction.fetch(auctionAccount)
+      const { tokenAccount, ...payment } = await paymentAccounts(auctionAccount, industryAuthorityPubkey)
+      const position = await openPosition(auctionAccount, industryAuthorityPubkey)
 
       return await program.methods
         .claimTokens()
@@ -468,12 +621,19 @@ export function useCarbonProgram() {
           auction: auctionAccount,
           bid: bidAccount,
           industryAccount,
+          position,
+          vintageHolding: vintagePda(industryAccount, vintage),
+          config: configAccount,
           ctMint: ctMintPubkey,
           industryCtAccount: industryTokenAccount,
           mintAuthority,
           escrow: escrowAccount,
+          ...payment,
+          bidderToken: tokenAccount,
           bidder: industryAuthorityPubkey,
-          tokenProgram: TOKEN_PROGRAM_ID,
+          roleAssignment: null,
+          authority: industryAuthorityPubkey,
+          tokenProgram: ctTokenProgram,
           associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
         })
@@ -503,12 +663,18 @@ export function useCarbonProgram() {
         program.programId
       )
 
+      const { tokenAccount, ...payment } = await paymentAccounts(auctionAccount, treasuryPubkey)
+
       return await program.methods
         .withdrawProceeds()
         .accountsStrict({
           auction: auctionAccount,
           escrow: escrowAccount,
+          config: configAccount,
+          roleAssignment: rolePda('treasurer', adminPubkey),
           treasury: treasuryPubkey, // Fixed: Use provided treasury address
+          ...payment,
+          treasuryToken: tokenAccount,
           authority: adminPubkey,
           systemProgram: SystemProgram.programId,
         })
@@ -526,25 +692,31 @@ export function useCarbonProgram() {
 
   const burnCtForComplianceHandler = useMutation<string, Error, BurnCtForComplianceArgs>({
     mutationKey: ['carbon', 'burnCtForCompliance', { cluster }],
-    mutationFn: async ({ industryAuthorityPubkey, ctMintPubkey, burnAmount, emissionAmount }) => {
+    mutationFn: async ({ industryAuthorityPubkey, ctMintPubkey, burnAmount, vintage }) => {
       const [industryAccount] = PublicKey.findProgramAddressSync(
         [Buffer.from('industry'), industryAuthorityPubkey.toBuffer()],
         program.programId
       )
 
+      const { ctTokenProgram, compliancePeriod } = await program.account.programConfig.fetch(configAccount)
       const industryTokenAccount = await getAssociatedTokenAddress(
         ctMintPubkey,
-        industryAuthorityPubkey
+        industryAuthorityPubkey,
+        false,
+        ctTokenProgram
       )
 
       return await program.methods
-        .burnCtForCompliance(burnAmount, emissionAmount)
+        .burnCtForCompliance(burnAmount)
         .accountsStrict({
           industryAccount,
+          vintageHolding: vintagePda(industryAccount, vintage ?? compliancePeriod),
+          config: configAccount,
           ctMint: ctMintPubkey,
           industryCtAccount: industryTokenAccount,
+          proposal: null,
           authority: industryAuthorityPubkey,
-          tokenProgram: TOKEN_PROGRAM_ID,
+          tokenProgram: ctTokenProgram,
         })
         .rpc()
     },
@@ -566,10 +738,14 @@ export function useCarbonProgram() {
         program.programId
       )
 
+      const { vintage } = await program.account.auction.fetch(auctionPDA)
+
       return await program.methods
         .cancelAuction()
         .accountsStrict({
           auction: auctionPDA,
+          capSchedule: capSchedulePda(vintage),
+          roleAssignment: rolePda('auctionOperator', adminPubkey),
           authority: adminPubkey,
         })
         .rpc()
@@ -584,6 +760,98 @@ export function useCarbonProgram() {
     },
   })
 
+  const refundBidHandler = useMutation<string, Error, RefundBidArgs>({
+    mutationKey: ['carbon', 'refundBid', { cluster }],
+    mutationFn: async ({ BATCH_NUMBER, bidderPubkey, bidTimestamp }) => {
+      const [auctionAccount] = PublicKey.findProgramAddressSync(
+        [Buffer.from('auction'), new BN(BATCH_NUMBER).toArrayLike(Buffer, 'le', 4)],
+        program.programId
+      )
+
+      const [escrowAccount] = PublicKey.findProgramAddressSync(
+        [Buffer.from('escrow'), auctionAccount.toBuffer()],
+        program.programId
+      )
+      const [bidAccount] = PublicKey.findProgramAddressSync(
+        [
+          Buffer.from('bid'),
+          auctionAccount.toBuffer(),
+          bidderPubkey.toBuffer(),
+          bidTimestamp.toArrayLike(Buffer, 'le', 8),
+        ],
+        program.programId
+      )
+
+      const [industryAccount] = PublicKey.findProgramAddressSync(
+        [Buffer.from('industry'), bidderPubkey.toBuffer()],
+        program.programId
+      )
+
+      const { tokenAccount, ...payment } = await paymentAccounts(auctionAccount, bidderPubkey)
+      const position = await openPosition(auctionAccount, bidderPubkey)
+
+      return await program.methods
+        .refundBid()
+        .accountsStrict({
+          auction: auctionAccount,
+          bid: bidAccount,
+          industryAccount,
+          position,
+          escrow: escrowAccount,
+          ...payment,
+          bidderToken: tokenAccount,
+          bidder: bidderPubkey,
+          roleAssignment: null,
+          authority: bidderPubkey,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc()
+    },
+    onSuccess: async (signature) => {
+      transactionToast(signature)
+      await bidAccounts.refetch()
+    },
+    onError: (error) => {
+      console.error('Refund bid error:', error)
+      toast.error('Failed to refund bid')
+    },
+  })
+
+  const closeBidHandler = useMutation<string, Error, CloseBidArgs>({
+    mutationKey: ['carbon', 'closeBid', { cluster }],
+    mutationFn: async ({ BATCH_NUMBER, bidderPubkey, bidTimestamp }) => {
+      const [auctionAccount] = PublicKey.findProgramAddressSync(
+        [Buffer.from('auction'), new BN(BATCH_NUMBER).toArrayLike(Buffer, 'le', 4)],
+        program.programId
+      )
+      const [bidAccount] = PublicKey.findProgramAddressSync(
+        [
+          Buffer.from('bid'),
+          auctionAccount.toBuffer(),
+          bidderPubkey.toBuffer(),
+          bidTimestamp.toArrayLike(Buffer, 'le', 8),
+        ],
+        program.programId
+      )
+
+      return await program.methods
+        .closeBid()
+        .accountsStrict({
+          bid: bidAccount,
+          bidder: bidderPubkey,
+        })
+        .rpc()
+    },
+    onSuccess: async (signature) => {
+      transactionToast(signature)
+      await bidAccounts.refetch()
+    },
+    onError: (error) => {
+      console.error('Close bid error:', error)
+      toast.error('Failed to close bid')
+    },
+  })
+
   return {
     program,
     programId,
@@ -593,6 +861,8 @@ export function useCarbonProgram() {
     emissionReportAccounts,
     getProgramAccount,
     initializeHandler,
+    grantRoleHandler,
+    revokeRoleHandler,
     registerIndustryHandler,
     verifyIndustryHandler,
     depositBondHandler,
@@ -605,6 +875,8 @@ export function useCarbonProgram() {
     withdrawProceedsHandler,
     burnCtForComplianceHandler,
     cancelAuctionHandler,
+    refundBidHandler,
+    closeBidHandler,
   }
 }
 